@@ -0,0 +1,464 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use seyeon_trading_engine::data_point::{self, DataPoint, FRAME_COLUMNS};
+use seyeon_trading_engine::indicators::Indicators;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+pub const DEFAULT_CACHE_DIR: &str = "apps/oversight/cache";
+pub const DEFAULT_TTL_HOURS: i64 = 24;
+
+static CONFIG: OnceLock<CacheConfig> = OnceLock::new();
+
+/// Where the price cache lives and how long it stays fresh.
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    pub dir: PathBuf,
+    pub format: CacheFormat,
+    /// Age after which a cache is updated.
+    pub ttl: Duration,
+    /// TTL of specific symbols (uppercase), overriding `ttl`.
+    pub symbol_ttl: HashMap<String, Duration>,
+    /// Never hit the network: use the cache whatever its age, and fail when it is missing.
+    pub offline: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from(DEFAULT_CACHE_DIR),
+            format: CacheFormat::default(),
+            ttl: Duration::hours(DEFAULT_TTL_HOURS),
+            symbol_ttl: HashMap::new(),
+            offline: false,
+        }
+    }
+}
+
+/// Parses per-symbol TTLs written as `BTC=6,SHIB=48` (hours).
+pub fn parse_symbol_ttl(value: &str) -> Result<HashMap<String, Duration>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (symbol, hours) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid cache TTL '{}', expected SYMBOL=HOURS", entry))?;
+            let hours: i64 = hours
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid cache TTL hours '{}' for {}", hours.trim(), symbol.trim()))?;
+            Ok((symbol.trim().to_uppercase(), Duration::hours(hours)))
+        })
+        .collect()
+}
+
+impl CacheConfig {
+    /// Reads `CACHE_DIR`, `CACHE_FORMAT`, `CACHE_TTL_HOURS`, `CACHE_TTL_OVERRIDES` and
+    /// `OFFLINE`, with the defaults for anything unset or invalid.
+    pub fn from_env() -> Self {
+        let mut config = Self {
+            format: CacheFormat::from_env(),
+            ..Default::default()
+        };
+
+        if let Ok(dir) = env::var("CACHE_DIR") {
+            config.dir = PathBuf::from(dir);
+        }
+        if let Ok(hours) = env::var("CACHE_TTL_HOURS") {
+            match hours.trim().parse::<i64>() {
+                Ok(hours) => config.ttl = Duration::hours(hours),
+                Err(_) => eprintln!("Invalid CACHE_TTL_HOURS '{}', using {}", hours, DEFAULT_TTL_HOURS),
+            }
+        }
+        if let Ok(overrides) = env::var("CACHE_TTL_OVERRIDES") {
+            match parse_symbol_ttl(&overrides) {
+                Ok(symbol_ttl) => config.symbol_ttl = symbol_ttl,
+                Err(e) => eprintln!("{}, ignoring CACHE_TTL_OVERRIDES", e),
+            }
+        }
+        config.offline = env::var("OFFLINE")
+            .is_ok_and(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"));
+
+        config
+    }
+
+    pub fn ttl_for(&self, symbol: &str) -> Duration {
+        self.symbol_ttl.get(&symbol.to_uppercase()).copied().unwrap_or(self.ttl)
+    }
+
+    pub fn is_fresh(&self, symbol: &str, entry: &CacheEntry) -> bool {
+        Utc::now() - entry.last_updated <= self.ttl_for(symbol)
+    }
+}
+
+/// Sets the configuration used for the rest of the run. Only the first call has an effect.
+pub fn configure(config: CacheConfig) {
+    let _ = CONFIG.set(config);
+}
+
+/// The configuration set with `configure`, or else the one of the environment.
+pub fn config() -> &'static CacheConfig {
+    CONFIG.get_or_init(CacheConfig::from_env)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub last_updated: DateTime<Utc>,
+    pub data: Vec<DataPoint>,
+}
+
+/// On-disk format of the cache files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheFormat {
+    /// `{symbol}_historical.json`, a pretty-printed `CacheEntry`.
+    Json,
+    /// `{symbol}_historical.parquet`, the `FRAME_COLUMNS` plus a constant
+    /// `last_updated` column (Unix milliseconds).
+    #[default]
+    Parquet,
+}
+
+impl fmt::Display for CacheFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheFormat::Json => write!(f, "json"),
+            CacheFormat::Parquet => write!(f, "parquet"),
+        }
+    }
+}
+
+impl FromStr for CacheFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(CacheFormat::Json),
+            "parquet" => Ok(CacheFormat::Parquet),
+            other => Err(format!("Unknown cache format '{}'", other)),
+        }
+    }
+}
+
+impl CacheFormat {
+    /// Format of `CACHE_FORMAT`, Parquet by default.
+    pub fn from_env() -> Self {
+        match env::var("CACHE_FORMAT") {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                eprintln!("{}, using {}", e, CacheFormat::default());
+                CacheFormat::default()
+            }),
+            Err(_) => CacheFormat::default(),
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            CacheFormat::Json => CacheFormat::Parquet,
+            CacheFormat::Parquet => CacheFormat::Json,
+        }
+    }
+}
+
+fn cache_path(dir: &Path, symbol: &str, format: CacheFormat) -> PathBuf {
+    dir.join(format!("{}_historical.{}", symbol.to_lowercase(), format))
+}
+
+/// Reads a JSON cache file, either a `CacheEntry` or a bare array of data points (then
+/// dated by the file's modification time).
+fn read_json(path: &Path) -> Result<CacheEntry> {
+    let file = File::open(path)?;
+    let value: serde_json::Value = serde_json::from_reader(BufReader::new(file))?;
+
+    if value.is_array() {
+        let modified = std::fs::metadata(path)?.modified()?;
+        return Ok(CacheEntry {
+            last_updated: modified.into(),
+            data: serde_json::from_value(value)?,
+        });
+    }
+
+    Ok(serde_json::from_value(value)?)
+}
+
+fn read_parquet(path: &Path) -> Result<CacheEntry> {
+    let frame = ParquetReader::new(File::open(path)?).finish()?;
+
+    let last_updated = frame
+        .column("last_updated")?
+        .i64()?
+        .get(0)
+        .and_then(DateTime::from_timestamp_millis)
+        .context("Missing last_updated")?;
+
+    Ok(CacheEntry {
+        last_updated,
+        data: data_point::from_frame(&frame)?,
+    })
+}
+
+fn write(path: &Path, entry: &CacheEntry, format: CacheFormat) -> Result<()> {
+    match format {
+        CacheFormat::Json => {
+            std::fs::write(path, serde_json::to_string_pretty(entry)?)?;
+        }
+        CacheFormat::Parquet => {
+            let mut frame = data_point::to_frame(&entry.data)?;
+            let last_updated = vec![entry.last_updated.timestamp_millis(); frame.height()];
+            frame.with_column(Column::new("last_updated".into(), last_updated))?;
+
+            ParquetWriter::new(File::create(path)?).finish(&mut frame)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes to a temporary file first and renames it over the old one, so an interrupted
+/// write never leaves a truncated cache behind.
+fn write_atomically(dir: &Path, symbol: &str, entry: &CacheEntry, format: CacheFormat) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+
+    let path = cache_path(dir, symbol, format);
+    let temp_path = dir.join(format!(".{}_historical.{}.tmp", symbol.to_lowercase(), format));
+    write(&temp_path, entry, format)?;
+    std::fs::rename(&temp_path, &path)?;
+
+    Ok(path)
+}
+
+/// Reads the cache of `symbol` in `dir`, in `format` or else in the other format.
+pub fn load_from(dir: &Path, symbol: &str, format: CacheFormat) -> Option<CacheEntry> {
+    for format in [format, format.other()] {
+        let path = cache_path(dir, symbol, format);
+        if !path.exists() {
+            continue;
+        }
+
+        let entry = match format {
+            CacheFormat::Json => read_json(&path),
+            CacheFormat::Parquet => read_parquet(&path),
+        };
+        match entry {
+            Ok(entry) => return Some(entry),
+            Err(e) => eprintln!("Cache file {} couldn't be read: {}", path.display(), e),
+        }
+    }
+
+    None
+}
+
+pub fn load(symbol: &str) -> Option<CacheEntry> {
+    let config = config();
+    load_from(&config.dir, symbol, config.format)
+}
+
+pub fn save(symbol: &str, data: &[DataPoint]) -> Result<()> {
+    let entry = CacheEntry {
+        last_updated: Utc::now(),
+        data: data.to_vec(),
+    };
+    let config = config();
+    write_atomically(&config.dir, symbol, &entry, config.format)?;
+
+    println!("Data saved to cache for {}", symbol);
+    Ok(())
+}
+
+/// The cache of `symbol` in the configured directory, scanned lazily, when it is stored
+/// as Parquet. A JSON cache gives `None`: it is cheaper to build the frame from the
+/// data points already in memory than to parse the file again.
+pub fn scan(symbol: &str) -> Option<LazyFrame> {
+    let config = config();
+    if config.format != CacheFormat::Parquet || !cache_path(&config.dir, symbol, CacheFormat::Parquet).exists() {
+        return None;
+    }
+
+    scan_from(&config.dir, symbol)
+}
+
+/// The cached history of `symbol` as a price frame with the `FRAME_COLUMNS`, scanned
+/// lazily from Parquet without going through `DataPoint`s.
+pub fn scan_from(dir: &Path, symbol: &str) -> Option<LazyFrame> {
+    let path = cache_path(dir, symbol, CacheFormat::Parquet);
+    if path.exists() {
+        if let Ok(frame) = LazyFrame::scan_parquet(&path, Default::default()) {
+            return Some(frame.select(FRAME_COLUMNS.map(col)));
+        }
+    }
+
+    let entry = load_from(dir, symbol, CacheFormat::Json)?;
+    data_point::to_frame(&entry.data).ok().map(|frame| frame.lazy())
+}
+
+/// Merges freshly fetched candles into the cached ones. A fetched candle replaces the
+/// cached one of the same day, since the last cached day may have been incomplete.
+pub fn merge_history(cached: Vec<DataPoint>, fetched: Vec<DataPoint>) -> Vec<DataPoint> {
+    let mut merged: BTreeMap<DateTime<Utc>, DataPoint> = BTreeMap::new();
+    for point in cached.into_iter().chain(fetched) {
+        merged.insert(point.datetime, point);
+    }
+    merged.into_values().collect()
+}
+
+/// Converts every JSON cache file of `dir` to Parquet, keeping its `last_updated`. Each
+/// new file is checked by scanning it back into `Indicators` before the JSON file is
+/// removed. Returns the migrated symbols with their row count.
+pub fn migrate_to_parquet(dir: &Path) -> Result<Vec<(String, usize)>> {
+    let mut migrated = Vec::new();
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.to_string_lossy().ends_with("_historical.json"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let symbol = file_name.trim_end_matches("_historical.json").to_string();
+
+        let entry = read_json(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let parquet_path = write_atomically(dir, &symbol, &entry, CacheFormat::Parquet)?;
+
+        let expected = Indicators::new(entry.data.clone()).data.collect()?.height();
+        let scanned = scan_from(dir, &symbol).context("Failed to scan the Parquet cache")?;
+        let rows = Indicators::from_frame(scanned).data.collect()?.height();
+        if rows != expected {
+            return Err(anyhow::anyhow!(
+                "{} has {} rows instead of {}, keeping {}",
+                parquet_path.display(), rows, expected, path.display()
+            ));
+        }
+
+        let json_size = std::fs::metadata(&path)?.len();
+        let parquet_size = std::fs::metadata(&parquet_path)?.len();
+        println!(
+            "{}: {} rows, {:.1} KB -> {:.1} KB",
+            symbol.to_uppercase(), entry.data.len(), json_size as f64 / 1024.0, parquet_size as f64 / 1024.0
+        );
+
+        std::fs::remove_file(&path)?;
+        migrated.push((symbol.to_uppercase(), entry.data.len()));
+    }
+
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use seyeon_trading_engine::data_point::DailySeries;
+
+    /// An empty directory of its own under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("seyeon-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entry(days: i64) -> CacheEntry {
+        CacheEntry {
+            last_updated: Utc.with_ymd_and_hms(2025, 3, 1, 8, 30, 15).unwrap(),
+            data: (0..days).map(|day| DailySeries::new().candle(day, 100.0 + day as f64 * 1.5)).collect(),
+        }
+    }
+
+    fn rows(data: &[DataPoint]) -> Vec<(DateTime<Utc>, f64, f64, f64, f64, f64)> {
+        data.iter()
+            .map(|p| (p.datetime, p.price, p.high, p.low, p.open, p.volume))
+            .collect()
+    }
+
+    #[test]
+    fn parquet_round_trip_keeps_every_field() {
+        let dir = temp_dir("round-trip");
+        let original = entry(30);
+
+        let path = write_atomically(&dir, "ETH", &original, CacheFormat::Parquet).unwrap();
+        assert_eq!(path, dir.join("eth_historical.parquet"));
+        let read = read_parquet(&path).unwrap();
+
+        assert_eq!(read.last_updated, original.last_updated);
+        assert_eq!(rows(&read.data), rows(&original.data));
+
+        let scanned = scan_from(&dir, "eth").unwrap().collect().unwrap();
+        assert_eq!(scanned.height(), 30);
+        assert_eq!(scanned.get_column_names_str(), FRAME_COLUMNS.to_vec());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn load_from_falls_back_to_the_other_format() {
+        let dir = temp_dir("fallback");
+        write_atomically(&dir, "btc", &entry(5), CacheFormat::Json).unwrap();
+
+        let loaded = load_from(&dir, "BTC", CacheFormat::Parquet).unwrap();
+
+        assert_eq!(rows(&loaded.data), rows(&entry(5).data));
+        assert!(scan_from(&dir, "BTC").is_some());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn migrate_to_parquet_replaces_json_files() {
+        let dir = temp_dir("migrate");
+        let original = entry(20);
+        std::fs::write(dir.join("btc_historical.json"), serde_json::to_string(&original).unwrap()).unwrap();
+        // A bare array of data points, as older caches were written.
+        std::fs::write(dir.join("eth_historical.json"), serde_json::to_string(&entry(3).data).unwrap()).unwrap();
+        std::fs::write(dir.join("notes.json"), "{}").unwrap();
+
+        let migrated = migrate_to_parquet(&dir).unwrap();
+
+        assert_eq!(migrated, vec![("BTC".to_string(), 20), ("ETH".to_string(), 3)]);
+        assert!(!dir.join("btc_historical.json").exists());
+        assert!(!dir.join("eth_historical.json").exists());
+        assert!(dir.join("notes.json").exists());
+
+        let btc = read_parquet(&dir.join("btc_historical.parquet")).unwrap();
+        assert_eq!(btc.last_updated, original.last_updated);
+        assert_eq!(rows(&btc.data), rows(&original.data));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn merge_history_prefers_fetched_candles() {
+        let series = DailySeries::new();
+        let cached = vec![series.candle(0, 100.0), series.candle(1, 101.0), series.candle(2, 99.0)];
+        // Day 2 was cached mid-day; the fetch brings its final close and day 3.
+        let fetched = vec![series.candle(2, 102.0), series.candle(3, 103.0)];
+
+        let merged = merge_history(cached, fetched);
+
+        let prices: Vec<f64> = merged.iter().map(|point| point.price).collect();
+        assert_eq!(prices, vec![100.0, 101.0, 102.0, 103.0]);
+    }
+
+    #[test]
+    fn merge_history_sorts_and_deduplicates() {
+        let series = DailySeries::new();
+        let cached = vec![series.candle(3, 103.0), series.candle(1, 101.0)];
+        let fetched = vec![series.candle(0, 100.0), series.candle(1, 101.5), series.candle(1, 101.5)];
+
+        let merged = merge_history(cached, fetched);
+
+        let days: Vec<DateTime<Utc>> = merged.iter().map(|point| point.datetime).collect();
+        assert_eq!(days, vec![series.day(0), series.day(1), series.day(3)]);
+        assert_eq!(merged[1].price, 101.5);
+    }
+}
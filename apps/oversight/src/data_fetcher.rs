@@ -1,66 +1,87 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc, Duration};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use serde_json;
-use seyeon_cryptocompare::CryptocompareClient;
-use seyeon_cryptocompare::histoday::{Histoday, HistodayParams};
+use crate::cache;
+use crate::executor::ExecutionConfig;
+use crate::guardrails::GuardrailConfig;
+use crate::key_pool::KeyPool;
+use crate::market_data::ProviderChain;
+use crate::rebalance::RebalanceConfig;
 use seyeon_rapidapi::RapidApiClient;
 use seyeon_rapidapi::fgi::{FearAndGreedIndex, FearAndGreedIndexResponse};
 use seyeon_trading_engine::data_point::DataPoint;
+use seyeon_trading_engine::validation::{self, RepairStrategy, ValidationConfig};
+use std::collections::HashMap;
 use std::env;
+use std::fmt;
 use std::fs::File;
 use std::io::BufReader;
 use std::io::{Write, stdout};
-use std::path::Path;
-use thiserror::Error;
-use rand::seq::SliceRandom;
-
-fn get_random_api_key(env_var_name: &str) -> anyhow::Result<String> {
-    let api_keys = std::env::var(env_var_name)?
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<String>>();
-    
-    if api_keys.is_empty() {
-        return Err(anyhow::anyhow!("No valid API keys found in {}", env_var_name));
-    }
-    
-    let mut rng = rand::thread_rng();
-    let selected_key = api_keys.choose(&mut rng)
-        .ok_or_else(|| anyhow::anyhow!("Failed to select a random API key"))?;
-    
-    println!("Selected a random API key from {} ({} keys available)", 
-        env_var_name, api_keys.len());
-    
-    Ok(selected_key.clone())
-}
-
-#[derive(Error, Debug)]
-pub enum FetchHistoricalDataError {
-    #[error("Error decoding response body: {0}")]
-    ResponseDecodeError(#[from] serde_json::Error),
-    #[error("API error: {0}")]
-    ApiError(String),
-    #[error("Rate limit exceeded: {0}")]
-    RateLimitError(String),
-}
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
 #[derive(Debug, Deserialize)]
 pub struct Portfolio {
     pub portfolio: Vec<String>,
+    /// Target allocation weights per symbol, used by the rebalancing advisor.
+    #[serde(default)]
+    pub targets: HashMap<String, f64>,
+    #[serde(default)]
+    pub rebalance: RebalanceConfig,
+    /// Order size limits of the executor (`--execute`).
+    #[serde(default)]
+    pub execution: ExecutionConfig,
+    /// Daily caps, price check and approval threshold applied before any order.
+    #[serde(default)]
+    pub guardrails: GuardrailConfig,
+}
+
+/// How current the price history of a `FetchedData` is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// Fetched now, or cached within its TTL.
+    Fresh,
+    /// Updating failed; the history is that old (since the cache was written, or since
+    /// the last candle of a local file).
+    Stale(Duration),
+    /// Offline mode: read from the cache without trying to update it.
+    CacheOnly,
 }
 
-#[derive(Debug)]
+impl Freshness {
+    pub fn is_fresh(&self) -> bool {
+        matches!(self, Freshness::Fresh)
+    }
+}
+
+impl fmt::Display for Freshness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Freshness::Fresh => write!(f, "fresh"),
+            Freshness::Stale(age) if age.num_hours() >= 48 => write!(f, "stale ({} days old)", age.num_days()),
+            Freshness::Stale(age) => write!(f, "stale ({} hours old)", age.num_hours()),
+            Freshness::CacheOnly => write!(f, "cache only (offline)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct FetchedData {
     pub historical: Vec<DataPoint>,
-    pub fgi: Option<FearAndGreedIndexResponse>,
+    pub freshness: Freshness,
+    /// Whether the cache file of the symbol holds exactly `historical`, so it can be
+    /// scanned instead.
+    pub in_cache: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CacheEntry {
-    pub last_updated: DateTime<Utc>,
-    pub data: Vec<DataPoint>,
+/// Outcome of one fetch of `fetch_concurrently`.
+pub struct TimedFetch {
+    pub symbol: String,
+    pub result: anyhow::Result<FetchedData>,
+    pub elapsed: std::time::Duration,
 }
 
 pub async fn portfolio_fetcher() -> Result<Vec<Portfolio>> {
@@ -90,209 +111,254 @@ pub async fn portfolio_fetcher() -> Result<Vec<Portfolio>> {
     Ok(portfolios)
 }
 
-fn check_cache(symbol: &str, max_age_days: i64) -> Option<Vec<DataPoint>> {
-    let cache_dir = Path::new("apps/oversight/cache");
-    if !cache_dir.exists() {
-        std::fs::create_dir_all(cache_dir).ok()?;
-    }
-
-    let cache_file = cache_dir.join(format!("{}_historical.json", symbol.to_lowercase()));
-    if !cache_file.exists() {
-        return None;
-    }
-
-    let file = File::open(cache_file).ok()?;
-    let reader = BufReader::new(file);
+/// Repairs the fetched series before it is cached. The strategy comes from
+/// `DATA_REPAIR_STRATEGY` (drop, forward-fill or interpolate; forward-fill by default).
+fn validate_historical_data(symbol: &str, historical: Vec<DataPoint>) -> Vec<DataPoint> {
+    let strategy = match env::var("DATA_REPAIR_STRATEGY") {
+        Ok(value) => value.parse().unwrap_or_else(|e| {
+            eprintln!("{}, using {}", e, RepairStrategy::default());
+            RepairStrategy::default()
+        }),
+        Err(_) => RepairStrategy::default(),
+    };
+    let config = ValidationConfig {
+        strategy,
+        ..Default::default()
+    };
 
-    let cache_entry: CacheEntry = serde_json::from_reader(reader).ok()?;
-    
-    let now = Utc::now();
-    let age = now.signed_duration_since(cache_entry.last_updated);
-    
-    if age <= Duration::days(max_age_days) {
-        println!("Using cached data for {} from {}", symbol, cache_entry.last_updated);
-        Some(cache_entry.data)
+    let (repaired, report) = validation::repair(historical, &config);
+    if report.is_clean() && report.leading_invalid == 0 {
+        println!("Data quality for {}: OK ({} rows)", symbol, report.output_rows);
     } else {
-        println!("Cache for {} is too old ({} days), fetching new data", 
-             symbol, age.num_days());
-        None
+        println!("Data quality for {}: {}", symbol, report);
+        for gap in report.gaps.iter().take(5) {
+            println!("  gap of {} days after {}", gap.missing_days, gap.from.format("%Y-%m-%d"));
+        }
+        for anomaly in report.anomalies.iter().take(5) {
+            println!("  {:?} on {}", anomaly.kind, anomaly.datetime.format("%Y-%m-%d"));
+        }
     }
+
+    repaired
 }
 
-fn save_to_cache(symbol: &str, data: &Vec<DataPoint>) -> std::io::Result<()> {
-    // Use the same directory with guaranteed permissions
-    let cache_dir = Path::new("apps/oversight/cache");
-    if !cache_dir.exists() {
-        std::fs::create_dir_all(cache_dir)?;
+/// The current Fear & Greed Index, `None` when it can't be fetched or in offline mode.
+pub async fn fetch_fgi() -> Option<FearAndGreedIndexResponse> {
+    if cache::config().offline {
+        return None;
     }
 
-    let cache_file = cache_dir.join(format!("{}_historical.json", symbol.to_lowercase()));
-    
-    let cache_entry = CacheEntry {
-        last_updated: Utc::now(),
-        data: data.clone(),
+
+    let pool = match KeyPool::from_env("RAPIDAPI_KEY") {
+        Ok(pool) => pool.with_limits(&[]),
+        Err(e) => {
+            eprintln!("Failed to fetch FGI: {}", e);
+            return None;
+        }
     };
-    
-    let json = serde_json::to_string_pretty(&cache_entry)?;
-    std::fs::write(cache_file, json)?;
-    
-    println!("Data saved to cache for {}", symbol);
-    Ok(())
-}
 
-pub async fn fetch_historical_data(symbol: String, days: u32) -> anyhow::Result<FetchedData> {
-    let symbol = symbol.trim_matches(|c| c == '"' || c == '\'' || c == ' ').to_string();
-    println!("Symbol being fetched: '{}'", symbol);
-    
-    if let Some(cached_data) = check_cache(&symbol, 1) {
-        println!("Using cached data for {}", symbol);
-        
-        let rapid_api_key = get_random_api_key("RAPIDAPI_KEY")?;
-        let fgi_client = RapidApiClient::new(&rapid_api_key);
-        let fgi_data = match fgi_client.call0::<FearAndGreedIndex>().await {
-            Ok(data) => Some(data),
+    for _ in 0..pool.len() {
+        let key = match pool.select().await {
+            Ok(key) => key,
             Err(e) => {
                 eprintln!("Failed to fetch FGI: {}", e);
-                None
+                return None;
             }
         };
-        
-        return Ok(FetchedData {
-            historical: cached_data,
-            fgi: fgi_data,
-        });
-    }
 
-    print!("Fetching historical data of {} (please, wait!)...", symbol);
-    stdout().flush()?;
+        let result = RapidApiClient::new(&key.value).call0::<FearAndGreedIndex>().await;
+        pool.record_call(&key).await;
 
-    let api_key = get_random_api_key("CRYPTOCOMPARE_API_KEY")?;
-    println!("Using API key: {}...", &api_key.chars().take(5).collect::<String>());
-    
-    let cc_client = CryptocompareClient::new(&api_key);
-
-    let days_to_request = days; // std::cmp::min(days, 60);
-    
-    let params = HistodayParams::builder()
-        .source_sym(symbol.clone())
-        .target_sym("USD")
-        .limit(days_to_request)
-        .build();
-        
-    println!("Calling API with reduced params: source_sym={}, target_sym=USD, limit={} (reduced from {})", 
-             symbol, days_to_request, days);
-
-    let data = match cc_client.call::<Histoday>(params).await {
-        Ok(data) => {
-            if data.response == "Error" {
-                if data.message.contains("rate limit") {
-                    return Err(FetchHistoricalDataError::RateLimitError(data.message).into());
-                } else {
-                    return Err(FetchHistoricalDataError::ApiError(data.message).into());
-                }
+        match result {
+            Ok(data) => {
+                pool.report(&key, None, None).await;
+                return Some(data);
             }
-            
-            if data.data.is_none() {
-                return Err(FetchHistoricalDataError::ApiError("No data returned by API".to_string()).into());
+            Err(e) if e.status().is_some_and(|status| status.as_u16() == 429) => {
+                pool.quarantine(&key, None).await;
             }
-            
-            data
-        }
-        Err(err) => {
-            eprintln!("\nAPI call failed: {}", err);
-            
-            println!("Attempting to get raw response...");
-            
-            let url = format!(
-                "https://min-api.cryptocompare.com/data/v2/histoday?fsym={}&tsym=USD&limit={}",
-                symbol, days_to_request
-            );
-            
-            let client = reqwest::Client::new();
-            let response = match client
-                .get(&url)
-                .header("Authorization", format!("Apikey {}", api_key))
-                .send()
-                .await {
-                    Ok(resp) => resp,
-                    Err(e) => {
-                        eprintln!("Raw HTTP request failed: {}", e);
-                        return Err(FetchHistoricalDataError::ApiError(format!("HTTP request failed: {}", e)).into());
-                    }
-                };
-                
-            if !response.status().is_success() {
-                let status = response.status();
-                let body = match response.text().await {
-                    Ok(body) => body,
-                    Err(_) => String::from("Failed to read response body")
-                };
-                eprintln!("API returned error status {}: {}", status, body);
-                
-                if body.contains("rate limit") {
-                    return Err(FetchHistoricalDataError::RateLimitError(body).into());
-                }
-                
-                return Err(FetchHistoricalDataError::ApiError(format!("API returned status {}: {}", status, body)).into());
+            Err(e) => {
+                eprintln!("Failed to fetch FGI: {}", e);
+                return None;
             }
-            
-            let body = match response.text().await {
-                Ok(body) => body,
-                Err(e) => {
-                    eprintln!("Failed to read response body: {}", e);
-                    return Err(FetchHistoricalDataError::ApiError(format!("Failed to read response body: {}", e)).into());
-                }
+        }
+    }
+
+    eprintln!("Failed to fetch FGI: every RAPIDAPI_KEY key is rate limited");
+    None
+}
+
+/// Start of the fetch for a window of `days` ending at `to`, and whether the fetched days
+/// continue the cache. The fetch resumes at the last cached candle, so that its possibly
+/// incomplete day is fetched again. Without a cache, or when it ends before the window,
+/// the whole window is fetched instead and replaces the cache: merging would leave a gap
+/// of missing days, which the repair would fill with made-up candles.
+fn fetch_from(cached: Option<&[DataPoint]>, to: DateTime<Utc>, days: u32) -> (DateTime<Utc>, bool) {
+    let window_start = to - Duration::days(days as i64);
+    match cached.and_then(|data| data.last()) {
+        Some(last) if last.datetime >= window_start => (last.datetime, true),
+        _ => (window_start, false),
+    }
+}
+
+/// Fetches the days missing from the cache and merges them into it. When every provider
+/// fails, the cache is returned as stale instead. The flag tells whether the cache now
+/// holds the returned history.
+async fn update_history(
+    symbol: &str,
+    days: u32,
+    cached: Option<cache::CacheEntry>,
+) -> anyhow::Result<(Vec<DataPoint>, Freshness, bool)> {
+    let to = Utc::now();
+    let (from, resumes_cache) = fetch_from(cached.as_ref().map(|entry| entry.data.as_slice()), to, days);
+    if cached.is_some() && !resumes_cache {
+        println!("The cache of {} ends before the {}-day window, replacing it", symbol, days);
+    }
+
+    print!("Fetching {} days of historical data of {} (please, wait!)...", (to - from).num_days().max(1), symbol);
+    stdout().flush()?;
+
+    let (provider, fetched) = match ProviderChain::from_env().fetch_ohlcv(symbol, from, to).await {
+        Ok(fetched) => fetched,
+        Err(e) => {
+            println!();
+            let Some(cache_entry) = cached else {
+                return Err(anyhow::anyhow!("Failed to fetch {} and no cache available: {}", symbol, e));
             };
-            
-            println!("Raw API response (first 200 chars): {}", &body.chars().take(200).collect::<String>());
-            
-            if body.contains("rate limit") {
-                return Err(FetchHistoricalDataError::RateLimitError(body).into());
-            }
-            
-            return Err(FetchHistoricalDataError::ApiError(err.to_string()).into());
+            eprintln!("Failed to update {}, using the cache from {}: {}", symbol, cache_entry.last_updated, e);
+            return Ok((cache_entry.data, Freshness::Stale(to - cache_entry.last_updated), true));
         }
     };
 
-    print!(" CC \n\n");
+    let historical = match cached {
+        Some(cache_entry) if resumes_cache => cache::merge_history(cache_entry.data, fetched),
+        _ => fetched,
+    };
+
+    print!(" {} \n\n", provider);
     stdout().flush()?;
 
-    let historical = match &data.data {
-        Some(data_container) => {
-            data_container.data
-                .iter()
-                .map(|d| DataPoint {
-                    datetime: DateTime::from_timestamp(d.time as i64, 0).unwrap(),
-                    price: d.close,
-                    high: d.high,
-                    low: d.low,
-                    open: d.open,
-                    volume: d.volumefrom,
-                })
-                .collect()
-        },
-        None => return Err(FetchHistoricalDataError::ApiError("No data available".to_string()).into()),
-    };
+    let historical = validate_historical_data(symbol, historical);
 
-    if let Err(e) = save_to_cache(&symbol, &historical) {
-        eprintln!("Warning: Failed to save data to cache: {}", e);
+    // Local files are not fresh data, caching them would hide their age.
+    if provider == "local" {
+        let age = historical.last().map_or(Duration::zero(), |last| to - last.datetime);
+        return Ok((historical, Freshness::Stale(age), false));
     }
 
-    let rapid_api_key = get_random_api_key("RAPIDAPI_KEY")?;
-    let fgi_client = RapidApiClient::new(&rapid_api_key);
-
-    let fgi_data = match fgi_client.call0::<FearAndGreedIndex>().await {
-        Ok(data) => Some(data),
+    let saved = match cache::save(symbol, &historical) {
+        Ok(()) => true,
         Err(e) => {
-            eprintln!("Failed to fetch FGI: {}", e);
-            None
+            eprintln!("Warning: Failed to save data to cache: {}", e);
+            false
         }
     };
 
-    println!(" FGI ");
+    Ok((historical, Freshness::Fresh, saved))
+}
+
+/// Price history of `symbol` over the last `days`. The cache
+/// is used as is within its TTL, updated once older, and used whatever its age when the
+/// update fails or in offline mode; `freshness` tells which.
+pub async fn fetch_historical_data(symbol: String, days: u32) -> anyhow::Result<FetchedData> {
+    let symbol = symbol.trim_matches(|c| c == '"' || c == '\'' || c == ' ').to_string();
+    println!("Symbol being fetched: '{}'", symbol);
+    
+    let config = cache::config();
+    let cached = cache::load(&symbol);
+
+    if config.offline {
+        let cache_entry = cached.ok_or_else(|| {
+            anyhow::anyhow!("Offline mode: no cached price history for {} in {}", symbol, config.dir.display())
+        })?;
+        println!("Offline mode: using cached data for {} from {}", symbol, cache_entry.last_updated);
+
+        return Ok(FetchedData {
+            historical: cache_entry.data,
+            freshness: Freshness::CacheOnly,
+            in_cache: true,
+        });
+    }
+
+    let (historical, freshness, in_cache) = match cached {
+        Some(cache_entry) if config.is_fresh(&symbol, &cache_entry) => {
+            println!("Using cached data for {} from {}", symbol, cache_entry.last_updated);
+            (cache_entry.data, Freshness::Fresh, true)
+        }
+        cached => update_history(&symbol, days, cached).await?,
+    };
 
     Ok(FetchedData {
         historical,
-        fgi: fgi_data,
+        freshness,
+        in_cache,
     })
 }
+
+/// Fetches the history of every symbol, at most `concurrency` at a time. Results come
+/// back in the order of `symbols`.
+pub async fn fetch_concurrently(symbols: &[String], days: u32, concurrency: usize) -> Vec<TimedFetch> {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for (index, symbol) in symbols.iter().enumerate() {
+        let symbol = symbol.clone();
+        let semaphore = semaphore.clone();
+
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.expect("Fetch semaphore closed");
+            let started = Instant::now();
+            let result = fetch_historical_data(symbol.clone(), days).await;
+
+            (index, TimedFetch { symbol, result, elapsed: started.elapsed() })
+        });
+    }
+
+    let mut fetches = Vec::with_capacity(symbols.len());
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(fetch) => fetches.push(fetch),
+            Err(e) => eprintln!("Fetch task failed: {}", e),
+        }
+    }
+    fetches.sort_by_key(|(index, _)| *index);
+
+    fetches.into_iter().map(|(_, fetch)| fetch).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use seyeon_trading_engine::data_point::DailySeries;
+
+    #[test]
+    fn fetch_from_resumes_at_the_last_cached_candle() {
+        let series = DailySeries::new();
+        let to = series.day(180) + Duration::hours(12);
+        let cached = [series.candle(177, 100.0), series.candle(178, 100.0)];
+
+        assert_eq!(fetch_from(Some(&cached), to, 365), (series.day(178), true));
+    }
+
+    #[test]
+    fn fetch_from_starts_at_the_window_without_a_cache() {
+        let to = DailySeries::new().day(180) + Duration::hours(12);
+        let window_start = to - Duration::days(30);
+
+        assert_eq!(fetch_from(Some(&[]), to, 30), (window_start, false));
+        assert_eq!(fetch_from(None, to, 30), (window_start, false));
+    }
+
+    #[test]
+    fn cache_older_than_the_window_is_replaced_instead_of_merged() {
+        let series = DailySeries::new();
+        let to = series.day(180) + Duration::hours(12);
+        let window_start = to - Duration::days(30);
+        // Cached up to two months ago, before the 30-day window.
+        let old = [series.candle(119, 100.0), series.candle(120, 100.0)];
+
+        assert_eq!(fetch_from(Some(&old), to, 30), (window_start, false));
+        // A cache ending on the first day of the window is still continued.
+        assert_eq!(fetch_from(Some(&[series.candle(151, 100.0)]), to, 30), (series.day(151), true));
+    }
+}
@@ -0,0 +1,186 @@
+use serde::Deserialize;
+use seyeon_exchange::{Exchange, ExchangeError, Order, OrderRequest, QUOTE_ASSET, Side};
+use seyeon_redis::TradeAction;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ExecutionConfig {
+    /// Largest notional (USD) of a single order.
+    pub max_order_usd: f64,
+    /// Largest value (USD) a buy may bring a single asset to.
+    pub max_position_usd: f64,
+    /// Orders below this notional (USD) are skipped.
+    pub min_order_usd: f64,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            max_order_usd: 100.0,
+            max_position_usd: 1_000.0,
+            min_order_usd: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Plan {
+    Order(OrderRequest),
+    Skipped(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Execution {
+    Placed(Order),
+    /// The order that would have been placed outside of dry-run mode.
+    DryRun(OrderRequest),
+}
+
+/// Sizes a market order whenever the signal of an asset changes to Buy or Sell, within
+/// `config`, and sends it to `exchange`.
+pub struct Executor<E: Exchange> {
+    exchange: E,
+    config: ExecutionConfig,
+    fee_rate: f64,
+    dry_run: bool,
+}
+
+impl<E: Exchange> Executor<E> {
+    pub fn new(exchange: E, config: ExecutionConfig, fee_rate: f64, dry_run: bool) -> Self {
+        Self {
+            exchange,
+            config,
+            fee_rate,
+            dry_run,
+        }
+    }
+
+    pub fn exchange_mut(&mut self) -> &mut E {
+        &mut self.exchange
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Sizes the order for a change of signal: a buy of up to `max_order_usd` (without going
+    /// over `max_position_usd` or the free cash) when it turns to Buy, and a sale of up to
+    /// `max_order_usd` of the free balance when it turns to Sell. Any other change is skipped.
+    pub async fn plan_order(
+        &self,
+        symbol: &str,
+        previous: &TradeAction,
+        current: &TradeAction,
+    ) -> Result<Plan, ExchangeError> {
+        if previous == current {
+            return Ok(Plan::Skipped("signal unchanged".to_string()));
+        }
+
+        let side = match current {
+            TradeAction::Buy => Side::Buy,
+            TradeAction::Sell => Side::Sell,
+            other => return Ok(Plan::Skipped(format!("nothing to execute for {}", other))),
+        };
+
+        let price = self.exchange.price(symbol).await?;
+        let balances = self.exchange.balances().await?;
+        let balance = |asset: &str| balances.iter().find(|balance| balance.asset.eq_ignore_ascii_case(asset));
+
+        let request = match side {
+            Side::Buy => {
+                let held = balance(symbol).map_or(0.0, |balance| balance.total()) * price;
+                let cash = balance(QUOTE_ASSET).map_or(0.0, |balance| balance.free) / (1.0 + self.fee_rate);
+                let notional = self.config.max_order_usd.min(self.config.max_position_usd - held).min(cash);
+
+                if notional < self.config.min_order_usd {
+                    return Ok(Plan::Skipped(format!(
+                        "buy of ${:.2} below the ${:.2} minimum (position ${:.2}, cash ${:.2})",
+                        notional.max(0.0), self.config.min_order_usd, held, cash
+                    )));
+                }
+
+                OrderRequest::market(symbol, Side::Buy, notional / price)
+            }
+            Side::Sell => {
+                let held = balance(symbol).map_or(0.0, |balance| balance.free);
+                let quantity = held.min(self.config.max_order_usd / price);
+
+                if quantity * price < self.config.min_order_usd {
+                    return Ok(Plan::Skipped(format!(
+                        "sell of ${:.2} below the ${:.2} minimum",
+                        quantity * price, self.config.min_order_usd
+                    )));
+                }
+
+                OrderRequest::market(symbol, Side::Sell, quantity)
+            }
+        };
+
+        Ok(Plan::Order(request))
+    }
+
+    /// Places `request`, unless in dry-run mode.
+    pub async fn submit(&mut self, request: OrderRequest) -> Result<Execution, ExchangeError> {
+        if self.dry_run {
+            return Ok(Execution::DryRun(request));
+        }
+
+        Ok(Execution::Placed(self.exchange.place_order(request).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use seyeon_exchange::MockExchange;
+    use seyeon_trading_engine::data_point::DailySeries;
+
+    fn executor(cash: f64, held: f64, fee_rate: f64) -> Executor<MockExchange> {
+        let candle = DailySeries::new().candle(0, 100.0);
+        let mut exchange = MockExchange::new(cash, fee_rate).with_candles("BTC", vec![candle]);
+        exchange.deposit("BTC", held);
+
+        Executor::new(exchange, ExecutionConfig::default(), fee_rate, false)
+    }
+
+    async fn quantity(executor: &Executor<MockExchange>, current: TradeAction) -> Option<f64> {
+        match executor.plan_order("BTC", &TradeAction::Hold, &current).await.unwrap() {
+            Plan::Order(request) => Some(request.quantity),
+            Plan::Skipped(_) => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn buys_are_clamped_by_order_position_and_cash() {
+        // $100 per order at most.
+        assert_eq!(quantity(&executor(10_000.0, 0.0, 0.0), TradeAction::Buy).await, Some(1.0));
+        // $950 already held of the $1,000 position limit.
+        assert_eq!(quantity(&executor(10_000.0, 9.5, 0.0), TradeAction::Buy).await, Some(0.5));
+        // $50.50 of cash has to pay for the 1% fee as well.
+        let by_cash = quantity(&executor(50.5, 0.0, 0.01), TradeAction::Buy).await.unwrap();
+        assert!((by_cash - 0.5).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn orders_below_the_minimum_are_skipped() {
+        // Only $5 left before the position limit, and $5 of BTC to sell.
+        assert_eq!(quantity(&executor(10_000.0, 9.95, 0.0), TradeAction::Buy).await, None);
+        assert_eq!(quantity(&executor(10_000.0, 0.05, 0.0), TradeAction::Sell).await, None);
+    }
+
+    #[tokio::test]
+    async fn sells_are_clamped_by_order_size_and_balance() {
+        assert_eq!(quantity(&executor(0.0, 3.0, 0.0), TradeAction::Sell).await, Some(1.0));
+        assert_eq!(quantity(&executor(0.0, 0.4, 0.0), TradeAction::Sell).await, Some(0.4));
+    }
+
+    #[tokio::test]
+    async fn only_changes_to_buy_or_sell_are_executed() {
+        let executor = executor(10_000.0, 1.0, 0.0);
+
+        let unchanged = executor.plan_order("BTC", &TradeAction::Buy, &TradeAction::Buy).await.unwrap();
+        assert!(matches!(unchanged, Plan::Skipped(_)));
+        let to_hold = executor.plan_order("BTC", &TradeAction::Buy, &TradeAction::Hold).await.unwrap();
+        assert!(matches!(to_hold, Plan::Skipped(_)));
+    }
+}
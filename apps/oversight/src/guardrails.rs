@@ -0,0 +1,395 @@
+use crate::executor::{Execution, Executor, Plan};
+use crate::market_data::{ProviderChain, ProviderKind};
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::Deserialize;
+use seyeon_email::EmailConfig;
+use seyeon_exchange::{Exchange, OrderRequest, Side};
+use seyeon_redis::{
+    OrderUsage, PendingApproval, TradeAction, add_pending_approval, get_order_usage, get_pending_approvals,
+    is_kill_switch_active, record_order, remove_pending_approval,
+};
+use std::collections::HashMap;
+
+/// Pending approvals are dropped after this many hours.
+pub const APPROVAL_TTL_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GuardrailConfig {
+    /// Largest notional (USD) traded per asset per day, buys and sells alike.
+    pub max_asset_notional_usd: f64,
+    /// Largest notional (USD) traded across all assets per day.
+    pub max_total_notional_usd: f64,
+    pub max_orders_per_day: u32,
+    /// Largest relative difference tolerated between the order price (CryptoCompare close)
+    /// and the reference ticker, Coinlore's when available (0.03 = 3%).
+    pub max_price_deviation: f64,
+    /// Orders above this notional (USD) wait for approval by email token.
+    pub approval_threshold_usd: f64,
+}
+
+impl Default for GuardrailConfig {
+    fn default() -> Self {
+        Self {
+            max_asset_notional_usd: 500.0,
+            max_total_notional_usd: 1_000.0,
+            max_orders_per_day: 5,
+            max_price_deviation: 0.03,
+            approval_threshold_usd: 250.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Executed(Execution),
+    /// No order was needed, or it was too small.
+    Skipped(String),
+    /// Blocked by a guardrail.
+    Rejected(String),
+    /// Held back until approved with this token.
+    AwaitingApproval(String),
+}
+
+/// Checks an order of `notional` at `price` against the daily limits and the reference
+/// price. Returns the first violated limit. A missing reference price fails the check.
+pub fn check_limits(
+    config: &GuardrailConfig,
+    symbol: &str,
+    notional: f64,
+    price: f64,
+    reference_price: Option<f64>,
+    usage: &OrderUsage,
+) -> Result<(), String> {
+    if usage.orders >= config.max_orders_per_day {
+        return Err(format!("daily limit of {} orders reached", config.max_orders_per_day));
+    }
+
+    if usage.total_notional + notional > config.max_total_notional_usd {
+        return Err(format!(
+            "${:.2} would exceed the ${:.2} daily total (${:.2} traded today)",
+            notional, config.max_total_notional_usd, usage.total_notional
+        ));
+    }
+
+    let asset_notional = usage.asset_notional.get(&symbol.to_uppercase()).copied().unwrap_or(0.0);
+    if asset_notional + notional > config.max_asset_notional_usd {
+        return Err(format!(
+            "${:.2} would exceed the ${:.2} daily limit of {} (${:.2} traded today)",
+            notional, config.max_asset_notional_usd, symbol, asset_notional
+        ));
+    }
+
+    let Some(reference_price) = reference_price.filter(|p| *p > 0.0) else {
+        return Err(format!("no reference price for {} to check ${:.4} against", symbol, price));
+    };
+    let deviation = (price - reference_price).abs() / reference_price;
+    if deviation > config.max_price_deviation {
+        return Err(format!(
+            "price ${:.4} is {:.2}% away from the reference price ${:.4} (limit {:.2}%)",
+            price, deviation * 100.0, reference_price, config.max_price_deviation * 100.0
+        ));
+    }
+
+    Ok(())
+}
+
+/// What the guardrails make of an order, before anything is placed or stored.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    Place,
+    Reject(String),
+    RequestApproval,
+}
+
+/// Decides on `request` at `price`: rejected while the kill switch is active or when a
+/// limit of `check_limits` is violated, held for approval above `approval_threshold_usd`
+/// unless already `approved`, placed otherwise.
+pub fn decide(
+    config: &GuardrailConfig,
+    request: &OrderRequest,
+    price: f64,
+    reference_price: Option<f64>,
+    usage: &OrderUsage,
+    kill_switch_active: bool,
+    approved: bool,
+) -> Decision {
+    if kill_switch_active {
+        return Decision::Reject("kill switch is active".to_string());
+    }
+
+    let notional = request.quantity * price;
+    if let Err(reason) = check_limits(config, &request.symbol, notional, price, reference_price, usage) {
+        return Decision::Reject(reason);
+    }
+
+    if !approved && notional > config.approval_threshold_usd {
+        return Decision::RequestApproval;
+    }
+
+    Decision::Place
+}
+
+/// Whether `outcome` uses up the approval of its order: only once the order is placed.
+fn uses_approval(outcome: &Outcome) -> bool {
+    matches!(outcome, Outcome::Executed(Execution::Placed(_)))
+}
+
+fn new_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(24)
+        .map(char::from)
+        .collect()
+}
+
+/// Runs every order of an `Executor` through the guardrails: the Redis kill switch, the
+/// daily caps, the price sanity check and, above `approval_threshold_usd`, an approval
+/// requested by email.
+pub struct GuardedExecutor<E: Exchange> {
+    executor: Executor<E>,
+    config: GuardrailConfig,
+    reference_prices: HashMap<String, f64>,
+}
+
+impl<E: Exchange> GuardedExecutor<E> {
+    pub fn new(executor: Executor<E>, config: GuardrailConfig) -> Self {
+        Self {
+            executor,
+            config,
+            reference_prices: HashMap::new(),
+        }
+    }
+
+    pub fn exchange_mut(&mut self) -> &mut E {
+        self.executor.exchange_mut()
+    }
+
+    /// Loads the reference prices of `symbols` for this cycle from the provider chain,
+    /// Coinlore first since the order price comes from the history provider. Orders on
+    /// symbols without a price are rejected.
+    pub async fn refresh_reference_prices(&mut self, symbols: &[String]) {
+        self.reference_prices.clear();
+        let chain = ProviderChain::from_env().prefer(ProviderKind::Coinlore);
+
+        for symbol in symbols {
+            match chain.latest_ticker(symbol).await {
+                Ok((provider, ticker)) => {
+                    if provider != "coinlore" {
+                        println!("Reference price of {} taken from {}", symbol, provider);
+                    }
+                    self.reference_prices.insert(symbol.to_uppercase(), ticker.price);
+                }
+                Err(e) => eprintln!("No reference price for {}, its orders will be rejected: {}", symbol, e),
+            }
+        }
+    }
+
+    pub async fn on_signal_change(
+        &mut self,
+        symbol: &str,
+        previous: &TradeAction,
+        current: &TradeAction,
+        email_config: &EmailConfig,
+    ) -> Result<Outcome> {
+        match self.executor.plan_order(symbol, previous, current).await? {
+            Plan::Order(request) => self.submit(request, Some(email_config)).await,
+            Plan::Skipped(reason) => Ok(Outcome::Skipped(reason)),
+        }
+    }
+
+    /// Submits the orders approved since the last cycle and drops the expired ones.
+    /// Approved orders go through every guardrail again except the approval, which is
+    /// only used up by a placed order: a rejected order or a dry run keeps it for the next
+    /// cycle, until it expires.
+    pub async fn execute_approved(&mut self) -> Result<Vec<(PendingApproval, Outcome)>> {
+        let mut outcomes = Vec::new();
+
+        for approval in get_pending_approvals().await? {
+            if approval.created_at + Duration::hours(APPROVAL_TTL_HOURS) < Utc::now() {
+                println!("Approval {} for {} {} expired", approval.token, approval.action, approval.symbol);
+                if let Err(e) = remove_pending_approval(&approval.token).await {
+                    eprintln!("Failed to drop expired approval {}: {}", approval.token, e);
+                }
+                continue;
+            }
+            if !approval.approved {
+                continue;
+            }
+
+            let side = match approval.action {
+                TradeAction::Buy => Side::Buy,
+                TradeAction::Sell => Side::Sell,
+                _ => continue,
+            };
+
+            // Removed first so an order can never be submitted twice, and put back unless
+            // the order is placed so the approval is not lost.
+            if let Err(e) = remove_pending_approval(&approval.token).await {
+                eprintln!("Failed to claim approval {}: {}", approval.token, e);
+                continue;
+            }
+
+            let request = OrderRequest::market(&approval.symbol, side, approval.quantity);
+            match self.submit(request, None).await {
+                Ok(outcome) => {
+                    if !uses_approval(&outcome) {
+                        println!("Approved {} of {} not placed, keeping it for the next cycle", approval.action, approval.symbol);
+                        if let Err(e) = add_pending_approval(&approval).await {
+                            eprintln!("Failed to restore approval {}: {}", approval.token, e);
+                        }
+                    }
+                    outcomes.push((approval, outcome));
+                }
+                Err(e) => {
+                    eprintln!("Approved {} of {} failed, keeping it for the next cycle: {}", approval.action, approval.symbol, e);
+                    if let Err(e) = add_pending_approval(&approval).await {
+                        eprintln!("Failed to restore approval {}: {}", approval.token, e);
+                    }
+                }
+            }
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Applies the guardrails to `request` and places it if they all pass. Without
+    /// `email_config` the order is treated as already approved.
+    async fn submit(&mut self, request: OrderRequest, email_config: Option<&EmailConfig>) -> Result<Outcome> {
+        let kill_switch_active = is_kill_switch_active().await?;
+        let price = self.executor.exchange_mut().price(&request.symbol).await?;
+        let notional = request.quantity * price;
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        let usage = get_order_usage(&today).await?;
+
+        let reference_price = self.reference_prices.get(&request.symbol.to_uppercase()).copied();
+        let decision = decide(
+            &self.config,
+            &request,
+            price,
+            reference_price,
+            &usage,
+            kill_switch_active,
+            email_config.is_none(),
+        );
+        if let Decision::Reject(reason) = decision {
+            return Ok(Outcome::Rejected(reason));
+        }
+
+        if let Some(email_config) = email_config.filter(|_| decision == Decision::RequestApproval) {
+            if self.executor.is_dry_run() {
+                return Ok(Outcome::Skipped(format!(
+                    "${:.2} order above the ${:.2} approval threshold (dry run, no approval requested)",
+                    notional, self.config.approval_threshold_usd
+                )));
+            }
+
+            let approval = PendingApproval {
+                token: new_token(),
+                symbol: request.symbol.clone(),
+                action: match request.side {
+                    Side::Buy => TradeAction::Buy,
+                    Side::Sell => TradeAction::Sell,
+                },
+                quantity: request.quantity,
+                price,
+                notional,
+                created_at: Utc::now(),
+                approved: false,
+            };
+            add_pending_approval(&approval).await?;
+
+            if let Err(e) = email_config.send_approval_request(&approval, APPROVAL_TTL_HOURS).await {
+                eprintln!("Failed to send approval request: {}", e);
+            }
+
+            return Ok(Outcome::AwaitingApproval(approval.token));
+        }
+
+        let execution = self.executor.submit(request).await?;
+        if let Execution::Placed(order) = &execution {
+            record_order(&today, &order.request.symbol, notional).await?;
+        }
+
+        Ok(Outcome::Executed(execution))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(orders: u32, total_notional: f64, btc_notional: f64) -> OrderUsage {
+        OrderUsage {
+            orders,
+            total_notional,
+            asset_notional: HashMap::from([("BTC".to_string(), btc_notional)]),
+        }
+    }
+
+    fn check(notional: f64, reference_price: Option<f64>, usage: &OrderUsage) -> Result<(), String> {
+        check_limits(&GuardrailConfig::default(), "btc", notional, 100.0, reference_price, usage)
+    }
+
+    #[test]
+    fn check_limits_enforces_the_daily_caps() {
+        let fresh = OrderUsage::default();
+
+        assert!(check(500.0, Some(100.0), &fresh).is_ok());
+        assert!(check(501.0, Some(100.0), &fresh).unwrap_err().contains("daily limit of btc"));
+        assert!(check(100.0, Some(100.0), &usage(5, 0.0, 0.0)).unwrap_err().contains("5 orders"));
+        assert!(check(100.0, Some(100.0), &usage(1, 950.0, 0.0)).unwrap_err().contains("daily total"));
+        assert!(check(100.0, Some(100.0), &usage(1, 450.0, 450.0)).unwrap_err().contains("daily limit of btc"));
+    }
+
+    #[test]
+    fn check_limits_compares_the_price_with_the_reference() {
+        let fresh = OrderUsage::default();
+
+        assert!(check(100.0, Some(97.5), &fresh).is_ok());
+        assert!(check(100.0, Some(96.0), &fresh).unwrap_err().contains("away from the reference price"));
+        assert!(check(100.0, None, &fresh).unwrap_err().contains("no reference price"));
+        assert!(check(100.0, Some(0.0), &fresh).unwrap_err().contains("no reference price"));
+    }
+
+    #[test]
+    fn kill_switch_rejects_every_order() {
+        let request = OrderRequest::market("BTC", Side::Sell, 1.0);
+        let decision = decide(&GuardrailConfig::default(), &request, 100.0, Some(100.0), &OrderUsage::default(), true, true);
+
+        assert_eq!(decision, Decision::Reject("kill switch is active".to_string()));
+    }
+
+    #[test]
+    fn orders_above_the_threshold_need_approval() {
+        let config = GuardrailConfig::default();
+        let fresh = OrderUsage::default();
+        let decide_on = |quantity: f64, approved: bool| {
+            decide(&config, &OrderRequest::market("BTC", Side::Buy, quantity), 100.0, Some(100.0), &fresh, false, approved)
+        };
+
+        assert_eq!(decide_on(2.5, false), Decision::Place);
+        assert_eq!(decide_on(3.0, false), Decision::RequestApproval);
+        assert_eq!(decide_on(3.0, true), Decision::Place);
+        // The limits still apply to approved orders.
+        assert!(matches!(decide_on(6.0, true), Decision::Reject(_)));
+    }
+
+    #[test]
+    fn only_a_placed_order_uses_up_its_approval() {
+        let request = OrderRequest::market("BTC", Side::Buy, 3.0);
+        let placed = seyeon_exchange::Order {
+            id: 1,
+            request: request.clone(),
+            status: seyeon_exchange::OrderStatus::Filled,
+            created_at: Utc::now(),
+        };
+
+        assert!(uses_approval(&Outcome::Executed(Execution::Placed(placed))));
+        assert!(!uses_approval(&Outcome::Executed(Execution::DryRun(request))));
+        assert!(!uses_approval(&Outcome::Rejected("kill switch is active".to_string())));
+    }
+}
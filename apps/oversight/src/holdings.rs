@@ -0,0 +1,139 @@
+use anyhow::Result;
+use seyeon_redis::{Holding, get_holdings, set_holdings};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+const HOLDINGS_PATHS: [&str; 2] = ["assets/holdings.json", "apps/oversight/assets/holdings.json"];
+
+/// A holding valued at the latest close.
+#[derive(Debug, Clone)]
+pub struct PositionValuation {
+    pub symbol: String,
+    pub quantity: f64,
+    pub average_cost: f64,
+    pub price: f64,
+    pub market_value: f64,
+    pub unrealized_pnl: f64,
+    pub unrealized_pnl_pct: f64,
+    /// Fraction of the total market value of all valued holdings.
+    pub allocation: f64,
+}
+
+/// Loads the current holdings. When a `holdings.json` file is present it is the source
+/// of truth and is mirrored to Redis; otherwise the last holdings stored in Redis are used.
+pub async fn load_holdings() -> Result<Vec<Holding>> {
+    let file_path = HOLDINGS_PATHS.iter().map(Path::new).find(|path| path.exists());
+
+    match file_path {
+        Some(path) => {
+            let reader = BufReader::new(File::open(path)?);
+            let holdings: Vec<Holding> = serde_json::from_reader(reader)?;
+
+            if let Err(e) = set_holdings(&holdings).await {
+                eprintln!("Failed to store holdings in Redis: {}", e);
+            }
+
+            println!("Loaded {} holdings from {}", holdings.len(), path.display());
+            Ok(holdings)
+        }
+        None => Ok(get_holdings().await?),
+    }
+}
+
+/// Values each holding at `latest_prices`; holdings without a known price are skipped.
+pub fn valuate(holdings: &[Holding], latest_prices: &HashMap<String, f64>) -> Vec<PositionValuation> {
+    let mut positions: Vec<PositionValuation> = holdings
+        .iter()
+        .filter_map(|holding| {
+            let symbol = holding.symbol.to_uppercase();
+            let Some(&price) = latest_prices.get(&symbol) else {
+                eprintln!("No price available for holding {}, skipping valuation", symbol);
+                return None;
+            };
+
+            let cost_basis = holding.quantity * holding.average_cost;
+            let market_value = holding.quantity * price;
+            let unrealized_pnl = market_value - cost_basis;
+
+            Some(PositionValuation {
+                symbol,
+                quantity: holding.quantity,
+                average_cost: holding.average_cost,
+                price,
+                market_value,
+                unrealized_pnl,
+                unrealized_pnl_pct: if cost_basis > 0.0 {
+                    unrealized_pnl / cost_basis * 100.0
+                } else {
+                    0.0
+                },
+                allocation: 0.0,
+            })
+        })
+        .collect();
+
+    let total_value: f64 = positions.iter().map(|p| p.market_value).sum();
+    if total_value > 0.0 {
+        for position in &mut positions {
+            position.allocation = position.market_value / total_value;
+        }
+    }
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(symbol: &str, quantity: f64, average_cost: f64) -> Holding {
+        Holding {
+            symbol: symbol.to_string(),
+            quantity,
+            average_cost,
+            acquisition_dates: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn valuate_computes_pnl_and_allocation() {
+        let holdings = [holding("btc", 0.5, 40_000.0), holding("ETH", 10.0, 2_000.0)];
+        let prices = HashMap::from([("BTC".to_string(), 60_000.0), ("ETH".to_string(), 1_000.0)]);
+
+        let positions = valuate(&holdings, &prices);
+
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].symbol, "BTC");
+        assert_eq!(positions[0].market_value, 30_000.0);
+        assert_eq!(positions[0].unrealized_pnl, 10_000.0);
+        assert_eq!(positions[0].unrealized_pnl_pct, 50.0);
+        assert_eq!(positions[1].unrealized_pnl, -10_000.0);
+        assert_eq!(positions[1].unrealized_pnl_pct, -50.0);
+        assert_eq!(positions[0].allocation, 0.75);
+        assert_eq!(positions[1].allocation, 0.25);
+    }
+
+    #[test]
+    fn valuate_skips_holdings_without_a_price() {
+        let holdings = [holding("BTC", 1.0, 50_000.0), holding("DOGE", 1_000.0, 0.1)];
+        let prices = HashMap::from([("BTC".to_string(), 50_000.0)]);
+
+        let positions = valuate(&holdings, &prices);
+
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].allocation, 1.0);
+    }
+
+    #[test]
+    fn valuate_handles_a_zero_cost_basis() {
+        let holdings = [holding("ETH", 1.0, 0.0)];
+        let prices = HashMap::from([("ETH".to_string(), 2_000.0)]);
+
+        let positions = valuate(&holdings, &prices);
+
+        assert_eq!(positions[0].unrealized_pnl, 2_000.0);
+        assert_eq!(positions[0].unrealized_pnl_pct, 0.0);
+    }
+}
@@ -0,0 +1,388 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, DurationRound, TimeZone, Utc};
+use rand::seq::SliceRandom;
+use seyeon_redis::{ApiKeyUsage, get_api_key_calls, get_api_key_usage, record_api_key_call, set_api_key_usage};
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/// Quarantine of a key that hit a limit without saying when it resets.
+pub const DEFAULT_QUARANTINE_MINUTES: i64 = 60;
+
+/// Period of a provider quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaPeriod {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+}
+
+impl fmt::Display for QuotaPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            QuotaPeriod::Second => "second",
+            QuotaPeriod::Minute => "minute",
+            QuotaPeriod::Hour => "hour",
+            QuotaPeriod::Day => "day",
+            QuotaPeriod::Month => "month",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for QuotaPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "second" => Ok(QuotaPeriod::Second),
+            "minute" => Ok(QuotaPeriod::Minute),
+            "hour" => Ok(QuotaPeriod::Hour),
+            "day" => Ok(QuotaPeriod::Day),
+            "month" => Ok(QuotaPeriod::Month),
+            other => Err(format!("Unknown quota period '{}'", other)),
+        }
+    }
+}
+
+impl QuotaPeriod {
+    /// Start of the period containing `now`, in UTC.
+    pub fn start(self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let truncated = |unit: Duration| now.duration_trunc(unit).unwrap_or(now);
+
+        match self {
+            QuotaPeriod::Second => truncated(Duration::seconds(1)),
+            QuotaPeriod::Minute => truncated(Duration::minutes(1)),
+            QuotaPeriod::Hour => truncated(Duration::hours(1)),
+            QuotaPeriod::Day => truncated(Duration::days(1)),
+            QuotaPeriod::Month => Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0).single().unwrap_or(now),
+        }
+    }
+
+    /// Identifies the period containing `now` among the counters of a key, e.g.
+    /// `hour:1735693200`.
+    pub fn window(self, now: DateTime<Utc>) -> String {
+        format!("{}:{}", self, self.start(now).timestamp())
+    }
+
+    /// Start of the next period after `now`, in UTC.
+    pub fn next_reset(self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let truncated = |unit: Duration| now.duration_trunc(unit).unwrap_or(now) + unit;
+
+        match self {
+            QuotaPeriod::Second => truncated(Duration::seconds(1)),
+            QuotaPeriod::Minute => truncated(Duration::minutes(1)),
+            QuotaPeriod::Hour => truncated(Duration::hours(1)),
+            QuotaPeriod::Day => truncated(Duration::days(1)),
+            QuotaPeriod::Month => {
+                let (year, month) = if now.month() == 12 { (now.year() + 1, 1) } else { (now.year(), now.month() + 1) };
+                Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single().unwrap_or(now + Duration::days(31))
+            }
+        }
+    }
+}
+
+/// Stable identifier of a key in Redis and in the logs, so the key itself is never
+/// written anywhere (64-bit FNV-1a).
+fn fingerprint(key: &str) -> String {
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Parses call limits written as `hour=3000,day=7500,month=100000`.
+pub fn parse_limits(value: &str) -> Result<Vec<(QuotaPeriod, i64)>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (period, calls) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid call limit '{}', expected PERIOD=CALLS", entry))?;
+            let calls: i64 = calls
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid call limit '{}' for {}", calls.trim(), period.trim()))?;
+            Ok((period.parse()?, calls))
+        })
+        .collect()
+}
+
+/// The keys usable at `now`, most calls left first, with their calls left. Keys are left
+/// out while quarantined or once their counted calls run out; the calls left are the
+/// lower of those counted and those last reported by the provider. Keys whose calls left
+/// are unknown come first, and ties keep the order of `keys`.
+fn rank<'a>(
+    keys: &[&'a PooledKey],
+    usage: &HashMap<String, ApiKeyUsage>,
+    counted: &HashMap<String, i64>,
+    now: DateTime<Utc>,
+) -> Vec<(&'a PooledKey, Option<i64>)> {
+    let mut ranked: Vec<(&PooledKey, Option<i64>)> = keys.iter()
+        .filter(|key| !usage.get(&key.fingerprint).is_some_and(|usage| usage.is_quarantined(now)))
+        .filter(|key| counted.get(&key.fingerprint).is_none_or(|left| *left > 0))
+        .map(|key| {
+            let reported = usage.get(&key.fingerprint).and_then(|usage| usage.calls_left);
+            let calls_left = match (reported, counted.get(&key.fingerprint).copied()) {
+                (Some(reported), Some(counted)) => Some(reported.min(counted)),
+                (reported, counted) => reported.or(counted),
+            };
+            (*key, calls_left)
+        })
+        .collect();
+
+    ranked.sort_by_key(|(_, calls_left)| std::cmp::Reverse(calls_left.unwrap_or(i64::MAX)));
+    ranked
+}
+
+/// A key handed out by a `KeyPool`.
+#[derive(Debug, Clone)]
+pub struct PooledKey {
+    pub value: String,
+    fingerprint: String,
+}
+
+/// The comma-separated keys of an environment variable, with their calls counted in Redis
+/// against the pool's limits. Keys with the most calls left are preferred, and keys that
+/// hit a limit are left out until it resets. Without Redis, keys are picked at random.
+pub struct KeyPool {
+    name: String,
+    keys: Vec<PooledKey>,
+    limits: Vec<(QuotaPeriod, i64)>,
+}
+
+impl KeyPool {
+    pub fn from_env(env_var_name: &str) -> Result<Self> {
+        let keys: Vec<PooledKey> = env::var(env_var_name)
+            .map_err(|e| anyhow::anyhow!("{}: {}", env_var_name, e))?
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| PooledKey {
+                value: key.to_string(),
+                fingerprint: fingerprint(key),
+            })
+            .collect();
+
+        if keys.is_empty() {
+            return Err(anyhow::anyhow!("No valid API keys found in {}", env_var_name));
+        }
+
+        Ok(Self {
+            name: env_var_name.to_string(),
+            keys,
+            limits: Vec::new(),
+        })
+    }
+
+    /// Counts calls against the limits of every key in `{VARIABLE}_LIMITS`, or `default`
+    /// when it is unset or invalid.
+    pub fn with_limits(mut self, default: &[(QuotaPeriod, i64)]) -> Self {
+        let variable = format!("{}_LIMITS", self.name);
+        self.limits = match env::var(&variable) {
+            Ok(value) => parse_limits(&value).unwrap_or_else(|e| {
+                eprintln!("{}, using the default {}", e, variable);
+                default.to_vec()
+            }),
+            Err(_) => default.to_vec(),
+        };
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    async fn usage(&self) -> HashMap<String, ApiKeyUsage> {
+        get_api_key_usage(&self.name).await.unwrap_or_else(|e| {
+            eprintln!("Usage of the {} keys unavailable, picking one at random: {}", self.name, e);
+            HashMap::new()
+        })
+    }
+
+    /// Calls left on each key in its tightest limit, from the calls counted in the current
+    /// periods. Empty without limits or Redis.
+    async fn counted_calls_left(&self, now: DateTime<Utc>) -> HashMap<String, i64> {
+        let mut calls_left: HashMap<String, i64> = HashMap::new();
+
+        for (period, limit) in &self.limits {
+            let calls = match get_api_key_calls(&self.name, &period.window(now)).await {
+                Ok(calls) => calls,
+                Err(e) => {
+                    eprintln!("Calls of the {} keys unavailable, ignoring the limits: {}", self.name, e);
+                    return HashMap::new();
+                }
+            };
+
+            for key in &self.keys {
+                let left = limit - calls.get(&key.fingerprint).copied().unwrap_or(0);
+                calls_left
+                    .entry(key.fingerprint.clone())
+                    .and_modify(|calls_left| *calls_left = (*calls_left).min(left))
+                    .or_insert(left);
+            }
+        }
+
+        calls_left
+    }
+
+    /// The key with the most calls left among those not quarantined or out of calls.
+    /// Keys never used yet come first.
+    pub async fn select(&self) -> Result<PooledKey> {
+        let now = Utc::now();
+        let usage = self.usage().await;
+        let counted = self.counted_calls_left(now).await;
+
+        // Shuffled first so that keys with the same headroom share the load.
+        let mut keys: Vec<&PooledKey> = self.keys.iter().collect();
+        keys.shuffle(&mut rand::thread_rng());
+        let Some(&(key, calls_left)) = rank(&keys, &usage, &counted, now).first() else {
+            let until = usage.values().filter_map(|usage| usage.quarantined_until).min();
+            return Err(anyhow::anyhow!(
+                "All {} keys of {} are rate limited{}",
+                self.keys.len(),
+                self.name,
+                until.map_or(String::new(), |until| format!(" until {}", until))
+            ));
+        };
+
+        println!(
+            "Using key {} of {} ({} keys, {} calls left)",
+            key.fingerprint,
+            self.name,
+            self.keys.len(),
+            calls_left.map_or("unknown".to_string(), |calls| calls.to_string())
+        );
+
+        Ok(key.clone())
+    }
+
+    /// Counts a call made with `key` in the current period of each limit.
+    pub async fn record_call(&self, key: &PooledKey) {
+        if self.limits.is_empty() {
+            return;
+        }
+
+        let now = Utc::now();
+        let windows: Vec<(String, i64)> = self.limits.iter()
+            .map(|(period, _)| (period.window(now), (period.next_reset(now) - now).num_seconds().max(1)))
+            .collect();
+
+        if let Err(e) = record_api_key_call(&self.name, &key.fingerprint, &windows).await {
+            eprintln!("Failed to count a call of key {} of {}: {}", key.fingerprint, self.name, e);
+        }
+    }
+
+    /// Records the calls left on `key` after a successful call, quarantining it until
+    /// `reset` when it has none left.
+    pub async fn report(&self, key: &PooledKey, calls_left: Option<i64>, reset: Option<DateTime<Utc>>) {
+        let exhausted = calls_left.is_some_and(|calls| calls <= 0);
+        let usage = ApiKeyUsage {
+            calls_left,
+            quarantined_until: if exhausted { reset } else { None },
+            updated_at: Utc::now(),
+        };
+
+        if let Err(e) = set_api_key_usage(&self.name, &key.fingerprint, &usage).await {
+            eprintln!("Failed to record the usage of key {} of {}: {}", key.fingerprint, self.name, e);
+        }
+    }
+
+    /// Leaves `key` out until `until` (or `DEFAULT_QUARANTINE_MINUTES` from now) after it
+    /// hit a limit.
+    pub async fn quarantine(&self, key: &PooledKey, until: Option<DateTime<Utc>>) {
+        let until = until.unwrap_or_else(|| Utc::now() + Duration::minutes(DEFAULT_QUARANTINE_MINUTES));
+        println!("Key {} of {} is rate limited, quarantined until {}", key.fingerprint, self.name, until);
+
+        self.report(key, Some(0), Some(until)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(value: &str) -> PooledKey {
+        PooledKey { value: value.to_string(), fingerprint: fingerprint(value) }
+    }
+
+    fn usage(calls_left: Option<i64>, quarantined_until: Option<DateTime<Utc>>) -> ApiKeyUsage {
+        ApiKeyUsage { calls_left, quarantined_until, updated_at: Utc::now() }
+    }
+
+    fn values(ranked: &[(&PooledKey, Option<i64>)]) -> Vec<String> {
+        ranked.iter().map(|(key, _)| key.value.clone()).collect()
+    }
+
+    #[test]
+    fn select_prefers_unused_keys_then_the_most_calls_left() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let (a, b, c, d) = (key("a"), key("b"), key("c"), key("d"));
+        let keys = [&a, &b, &c, &d];
+
+        let usage = HashMap::from([(a.fingerprint.clone(), usage(Some(500), None))]);
+        // B was never used; C and D were counted, and A's reported calls are lower.
+        let counted = HashMap::from([
+            (a.fingerprint.clone(), 900),
+            (c.fingerprint.clone(), 10),
+            (d.fingerprint.clone(), 2_000),
+        ]);
+
+        let ranked = rank(&keys, &usage, &counted, now);
+        assert_eq!(values(&ranked), vec!["b", "d", "a", "c"]);
+        assert_eq!(ranked[2].1, Some(500));
+    }
+
+    #[test]
+    fn quarantined_and_exhausted_keys_are_left_out_until_they_reset() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let (a, b, c) = (key("a"), key("b"), key("c"));
+        let keys = [&a, &b, &c];
+
+        let usage = HashMap::from([
+            (a.fingerprint.clone(), usage(Some(0), Some(now + Duration::minutes(5)))),
+            (b.fingerprint.clone(), usage(Some(0), Some(now - Duration::minutes(5)))),
+        ]);
+        let counted = HashMap::from([(c.fingerprint.clone(), 0)]);
+
+        // B's quarantine has expired.
+        assert_eq!(values(&rank(&keys, &usage, &counted, now)), vec!["b"]);
+        // Once A's quarantine expires too, C's calls left are unknown again and it comes first.
+        assert_eq!(values(&rank(&keys, &usage, &HashMap::new(), now + Duration::minutes(10))), vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn periods_reset_at_the_next_boundary() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 14, 9, 30, 15).unwrap();
+
+        assert_eq!(QuotaPeriod::Minute.next_reset(now), Utc.with_ymd_and_hms(2025, 6, 14, 9, 31, 0).unwrap());
+        assert_eq!(QuotaPeriod::Hour.next_reset(now), Utc.with_ymd_and_hms(2025, 6, 14, 10, 0, 0).unwrap());
+        assert_eq!(QuotaPeriod::Day.next_reset(now), Utc.with_ymd_and_hms(2025, 6, 15, 0, 0, 0).unwrap());
+        assert_eq!(QuotaPeriod::Month.next_reset(now), Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap());
+        assert_eq!(QuotaPeriod::Month.start(now), Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap());
+
+        let december = Utc.with_ymd_and_hms(2025, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(QuotaPeriod::Month.next_reset(december), Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(QuotaPeriod::Day.next_reset(december), Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(QuotaPeriod::Month.window(december), QuotaPeriod::Month.window(december - Duration::days(30)));
+    }
+
+    #[test]
+    fn limits_are_parsed_by_period() {
+        assert_eq!(
+            parse_limits(" hour=3000, Month=100000 ").unwrap(),
+            vec![(QuotaPeriod::Hour, 3_000), (QuotaPeriod::Month, 100_000)]
+        );
+        assert!(parse_limits("week=10").is_err());
+        assert!(parse_limits("day").is_err());
+    }
+}
@@ -0,0 +1,13 @@
+//! The modules of the monitoring loop, shared by the `oversight` binary and its
+//! integration tests.
+
+pub mod cache;
+pub mod data_fetcher;
+pub mod executor;
+pub mod guardrails;
+pub mod holdings;
+pub mod key_pool;
+pub mod market_data;
+pub mod outcomes;
+pub mod paper;
+pub mod rebalance;
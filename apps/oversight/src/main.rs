@@ -1,15 +1,25 @@
 use anyhow::Context;
 use data_fetcher::Portfolio;
-use data_fetcher::{fetch_historical_data, portfolio_fetcher};
+use data_fetcher::{FetchedData, fetch_concurrently, fetch_fgi, fetch_historical_data, portfolio_fetcher};
 use seyeon_rapidapi::fgi::FearAndGreedIndexResponse;
 use seyeon_coinlore::global_market;
-use seyeon_redis::{CryptoStatus, TradeAction, get_status, set_status, get_report_status, update_report_status};
-use seyeon_trading_engine::{engine, indicators::Indicators};
-use seyeon_email::EmailConfig;
-use chrono::Local;
+use seyeon_redis::{CryptoStatus, DominanceSnapshot, TradeAction, get_status, set_status, get_report_status, update_report_status, record_btc_dominance, get_btc_dominance_history, get_last_rebalance, set_last_rebalance, get_paper_ledger, approve_pending, SignalRecord, record_signal, get_signal_records};
+use seyeon_trading_engine::{correlation, data_point::DataPoint, engine, indicators::Indicators, risk};
+use seyeon_trading_engine::error::TradingEngineError;
+use seyeon_trading_engine::correlation::CorrelationMethod;
+use seyeon_trading_engine::benchmark::{self, Benchmark};
+use seyeon_trading_engine::robustness::{self, MonteCarloConfig, MonteCarloReport, ResamplingMethod};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use seyeon_email::{DailyReport, EmailConfig};
+use seyeon_exchange::MockExchange;
+use executor::{Execution, Executor};
+use guardrails::{GuardedExecutor, Outcome};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use std::collections::HashMap;
 use std::thread::sleep;
-use std::time::Duration;
-mod data_fetcher;
+use std::time::{Duration, Instant};
+use oversight::{cache, data_fetcher, executor, guardrails, holdings, outcomes, paper, rebalance};
 use dotenv::dotenv;
 use polars::prelude::*;
 use clap::Parser;
@@ -32,22 +42,162 @@ struct Args {
     /// Days to use for simulation (default: 365)
     #[arg(long, default_value = "365")]
     days: u32,
+
+    /// Trailing windows (in days) for the correlation analysis (e.g. 30,90,365)
+    #[arg(long, value_delimiter = ',', default_value = "30,90,365")]
+    correlation_windows: Vec<usize>,
+
+    /// Correlation method used on daily log returns: pearson or spearman
+    #[arg(long, default_value = "pearson")]
+    correlation_method: CorrelationMethod,
+
+    /// Average pairwise correlation above which the daily report warns about diversification
+    #[arg(long, default_value = "0.8")]
+    max_avg_correlation: f64,
+
+    /// Trailing window (in days) of daily returns for the risk analysis of the daily report
+    #[arg(long, default_value = "365")]
+    risk_window: usize,
+
+    /// Number of Monte Carlo runs per asset in simulation mode (disabled when omitted)
+    #[arg(long)]
+    monte_carlo: Option<usize>,
+
+    /// Monte Carlo resampling: bootstrap (block bootstrap of daily returns) or shuffle (trade order)
+    #[arg(long, default_value = "bootstrap")]
+    resampling: ResamplingMethod,
+
+    /// Block length (in days) used by the block bootstrap
+    #[arg(long, default_value = "10")]
+    block_size: usize,
+
+    /// Seed for the Monte Carlo runs, for reproducible results
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Benchmark strategies to compare against in simulation mode:
+    /// buy-and-hold, dca, sma-crossover, rsi-mean-reversion (comma separated)
+    #[arg(long, value_delimiter = ',')]
+    strategy: Vec<Benchmark>,
+
+    /// Window (in days) of the signal scorecard in the daily report
+    #[arg(long, default_value = "180")]
+    scorecard_days: i64,
+
+    /// Follow the live Buy/Sell signals with paper accounts stored in Redis
+    #[arg(long)]
+    paper_trading: bool,
+
+    /// Turn signal changes into orders on the in-process mock exchange, within the
+    /// `execution` limits of options.json
+    #[arg(long)]
+    execute: bool,
+
+    /// With --execute, log the orders instead of placing them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Assets whose price history is fetched at the same time in each cycle
+    #[arg(long, default_value = "8")]
+    fetch_concurrency: usize,
+
+    /// Convert the JSON price cache to Parquet and exit
+    #[arg(long)]
+    migrate_cache: bool,
+
+    /// Directory of the price cache (overrides CACHE_DIR)
+    #[arg(long)]
+    cache_dir: Option<std::path::PathBuf>,
+
+    /// Hours after which a cached price history is updated (overrides CACHE_TTL_HOURS)
+    #[arg(long)]
+    cache_ttl_hours: Option<i64>,
+
+    /// Per-symbol cache TTLs in hours, e.g. BTC=6,SHIB=48 (overrides CACHE_TTL_OVERRIDES)
+    #[arg(long)]
+    cache_ttl_overrides: Option<String>,
+
+    /// Never hit the network: use the price cache whatever its age and fail for the
+    /// symbols without one (also set with OFFLINE=true)
+    #[arg(long)]
+    offline: bool,
+
+    /// Approve the pending order with this token (sent by email) and exit; it is placed
+    /// on the next run with --execute
+    #[arg(long, value_name = "TOKEN")]
+    approve: Option<String>,
 }
 
 fn fgi_value(response: &FearAndGreedIndexResponse) -> Option<u8> {
     Some(response.fgi.now.value as u8)
 }
 
+/// Converts the recorded dominance snapshots into daily (midnight UTC) points.
+fn dominance_points(history: &[DominanceSnapshot]) -> Vec<(DateTime<Utc>, f64)> {
+    history
+        .iter()
+        .filter_map(|snapshot| {
+            let day = NaiveDate::parse_from_str(&snapshot.date, "%Y-%m-%d").ok()?;
+            Some((day.and_hms_opt(0, 0, 0)?.and_utc(), snapshot.btc_dominance))
+        })
+        .collect()
+}
+
+/// Builds the indicators for an asset, attaching the BTC-relative features to altcoins.
+fn asset_indicators(
+    symbol: &str,
+    indicators: Indicators,
+    btc: Option<&[DataPoint]>,
+    dominance: &[(DateTime<Utc>, f64)],
+) -> Indicators {
+    if symbol == "BTC" {
+        return indicators;
+    }
+
+    let indicators = match btc {
+        Some(btc) => indicators.with_btc(btc),
+        None => indicators,
+    };
+
+    if dominance.is_empty() {
+        indicators
+    } else {
+        indicators.with_btc_dominance(dominance)
+    }
+}
+
+/// Fetches the BTC history used as the cross-asset reference for altcoins.
+async fn fetch_btc_reference() -> Option<Vec<DataPoint>> {
+    match fetch_historical_data("BTC".to_string(), 2000).await {
+        Ok(data) => Some(data.historical),
+        Err(e) => {
+            eprintln!("Failed to fetch BTC reference data, cross-asset features disabled: {}", e);
+            None
+        }
+    }
+}
+
 /// Run simulation only without sending emails or updating status
-async fn run_simulation(crypto_symbol: Option<String>, days: u32) -> anyhow::Result<()> {
+async fn run_simulation(args: &Args) -> anyhow::Result<()> {
     dotenv().ok();
+
+    let days = args.days;
+    let monte_carlo = args.monte_carlo.map(|runs| MonteCarloConfig {
+        method: args.resampling,
+        runs,
+        block_size: args.block_size,
+    });
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
     
     let fetched_portfolio: Vec<Portfolio> = portfolio_fetcher().await?;
     let mut cryptos_to_simulate = Vec::new();
     
     // If a specific symbol was provided, simulate only that one
-    if let Some(symbol) = crypto_symbol {
-        cryptos_to_simulate.push(symbol);
+    if let Some(symbol) = &args.crypto {
+        cryptos_to_simulate.push(symbol.clone());
     } else {
         // Otherwise, simulate all cryptos in the portfolio
         for field in fetched_portfolio.iter() {
@@ -61,9 +211,15 @@ async fn run_simulation(crypto_symbol: Option<String>, days: u32) -> anyhow::Res
     println!("\n===== Simulation Mode =====");
     println!("Running Simulation for {} Cryptocurrencies Using {} Days of Data", 
              cryptos_to_simulate.len(), days);
+
+    let btc_reference = fetch_btc_reference().await;
+    let dominance = dominance_points(&get_btc_dominance_history().await.unwrap_or_default());
+    let fgi_value = fetch_fgi().await.as_ref().and_then(fgi_value);
     
     // Table to store results
     let mut simulation_results = Vec::new();
+    let mut monte_carlo_results: Vec<(String, MonteCarloReport)> = Vec::new();
+    let mut strategy_results: Vec<(String, String, engine::Summary)> = Vec::new();
     
     for crypto_symbol in cryptos_to_simulate {
         println!("\n--- Simulating {} ---", crypto_symbol);
@@ -72,48 +228,21 @@ async fn run_simulation(crypto_symbol: Option<String>, days: u32) -> anyhow::Res
         let fetched_data = match fetch_historical_data(crypto_symbol.clone(), 2000).await {
             Ok(data) => data,
             Err(e) => {
-                let error_msg = e.to_string();
-                if error_msg.contains("rate limit") {
-                    eprintln!("Rate limit exceeded for {}, checking cache...", crypto_symbol);
-                    
-                    let cache_path = format!("apps/oversight/cache/{}_historical.json", crypto_symbol.to_lowercase());
-                    if std::path::Path::new(&cache_path).exists() {
-                        match std::fs::read_to_string(&cache_path) {
-                            Ok(cache_content) => {
-                                match serde_json::from_str::<data_fetcher::CacheEntry>(&cache_content) {
-                                    Ok(cache_entry) => {
-                                        eprintln!("Using cached data from {} for {}", 
-                                                 cache_entry.last_updated, 
-                                                 crypto_symbol);
-                                                 
-                                        data_fetcher::FetchedData {
-                                            historical: cache_entry.data,
-                                            fgi: None,
-                                        }
-                                    },
-                                    Err(e) => {
-                                        eprintln!("Cache exists but cannot be parsed: {}", e);
-                                        continue;
-                                    }
-                                }
-                            },
-                            Err(e) => {
-                                eprintln!("Cache exists but cannot be read: {}", e);
-                                continue;
-                            }
-                        }
-                    } else {
-                        eprintln!("No cache available for {}", crypto_symbol);
-                        continue;
-                    }
-                } else {
-                    eprintln!("Failed to fetch data for {}: {}", crypto_symbol, error_msg);
-                    continue;
-                }
+                eprintln!("Failed to fetch data for {}: {}", crypto_symbol, e);
+                continue;
             }
         };
+        if !fetched_data.freshness.is_fresh() {
+            println!("Simulating {} on {} data", crypto_symbol, fetched_data.freshness);
+        }
         
-        let indicators = Indicators::new(fetched_data.historical);
+        let historical = fetched_data.historical;
+        let indicators = asset_indicators(
+            &crypto_symbol,
+            Indicators::new(historical.clone()),
+            btc_reference.as_deref(),
+            &dominance,
+        );
         let df = match indicators.calculate() {
             Ok(df) => df,
             Err(e) => {
@@ -122,38 +251,118 @@ async fn run_simulation(crypto_symbol: Option<String>, days: u32) -> anyhow::Res
             }
         };
         
-        let fgi_value = fetched_data.fgi.as_ref().and_then(fgi_value);
-        let mut engine = engine::TradingEngine::new(crypto_symbol.clone(), df, fgi_value, engine::Params::default());
+        let mut engine = match engine::TradingEngine::new(crypto_symbol.clone(), df, fgi_value, engine::Params::default()) {
+            Ok(engine) => engine,
+            Err(e) => {
+                eprintln!("Failed to create trading engine for {}: {}", crypto_symbol, e);
+                continue;
+            }
+        };
         
         println!("Running Simulation Trading for {} with {} Days of Data...", crypto_symbol, days);
         
-        engine.run_simulation(Some(days as usize));
-        
-        let summary = engine.get_summary();
+        let summary = match engine.run_simulation(Some(days as usize)).and_then(|_| engine.get_summary()) {
+            Ok(summary) => summary,
+            Err(e) => {
+                eprintln!("Simulation failed for {}: {}", crypto_symbol, e);
+                continue;
+            }
+        };
         println!("Results for {}:", crypto_symbol);
         println!("  Initial Capital: ${:.2}", summary.initial_capital);
         println!("  Final Value: ${:.2}", summary.final_portfolio_value);
         println!("  ROI: {:.2}%", summary.roi);
         println!("  Total Trades: {}", summary.num_trades);
         println!("  Total Fees Paid: ${:.2}", summary.estimated_fees_paid);
+        println!("  Max Drawdown: {:.2}%", summary.max_drawdown);
+
+        if !args.strategy.is_empty() {
+            strategy_results.push((crypto_symbol.clone(), "Default".to_string(), summary.clone()));
+
+            for strategy in &args.strategy {
+                match benchmark::run(*strategy, &engine.final_df, &engine.params, days as usize) {
+                    Ok(benchmark_summary) => {
+                        println!("  {} ROI: {:.2}%", strategy, benchmark_summary.roi);
+                        strategy_results.push((crypto_symbol.clone(), strategy.to_string(), benchmark_summary));
+                    },
+                    Err(e) => eprintln!("Failed to run {} for {}: {}", strategy, crypto_symbol, e),
+                }
+            }
+        }
+
+        if let Some(config) = &monte_carlo {
+            println!("Running {} {} Simulations for {}...", config.runs, config.method, crypto_symbol);
+
+            let report = match config.method {
+                ResamplingMethod::Bootstrap => robustness::bootstrap_simulations(
+                    &crypto_symbol,
+                    &historical,
+                    fgi_value,
+                    &engine.params,
+                    days as usize,
+                    config,
+                    &mut rng,
+                ),
+                ResamplingMethod::Shuffle => Ok(robustness::shuffle_trades(&engine, config.runs, &mut rng)),
+            };
+
+            match report {
+                Ok(Some(report)) => {
+                    println!("  ROI p5/p50/p95: {:.2}% / {:.2}% / {:.2}%", report.roi.p5, report.roi.p50, report.roi.p95);
+                    println!("  Max Drawdown p50/p95: {:.2}% / {:.2}%", report.max_drawdown.p50, report.max_drawdown.p95);
+                    println!("  Probability of Loss: {:.1}%", report.probability_of_loss * 100.0);
+                    monte_carlo_results.push((crypto_symbol.clone(), report));
+                },
+                Ok(None) => eprintln!("Not enough data for Monte Carlo simulations of {}", crypto_symbol),
+                Err(e) => eprintln!("Monte Carlo simulations failed for {}: {}", crypto_symbol, e),
+            }
+        }
         
         simulation_results.push((
             crypto_symbol.clone(),
             summary.roi,
             summary.final_portfolio_value,
-            summary.num_trades
+            summary.num_trades,
+            summary.max_drawdown,
         ));
     }
     
     simulation_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
     
     println!("\n===== Simulation Results =====");
-    println!("{:<5} {:<10} {:<15} {:<15} {:<10}", "Rank", "Crypto", "ROI", "Final Value", "# Trades");
-    println!("{:-<60}", "");
+    println!("{:<5} {:<10} {:<15} {:<15} {:<10} {:<10}", "Rank", "Crypto", "ROI", "Final Value", "# Trades", "Max DD");
+    println!("{:-<72}", "");
     
-    for (i, (symbol, roi, final_value, num_trades)) in simulation_results.iter().enumerate() {
-        println!("{:<5} {:<10} {:<15.2}% {:<15.2}$ {:<10}", 
-                 i+1, symbol, roi, final_value, num_trades);
+    for (i, (symbol, roi, final_value, num_trades, max_drawdown)) in simulation_results.iter().enumerate() {
+        println!("{:<5} {:<10} {:<15.2}% {:<15.2}$ {:<10} {:<9.2}%", 
+                 i+1, symbol, roi, final_value, num_trades, max_drawdown);
+    }
+
+    if !strategy_results.is_empty() {
+        println!("\n===== Strategy Comparison =====");
+        println!("{:<10} {:<15} {:<12} {:<15} {:<10} {:<12} {:<10}",
+                 "Crypto", "Strategy", "ROI", "Final Value", "# Trades", "Fees", "Max DD");
+        println!("{:-<90}", "");
+
+        for (symbol, strategy, summary) in &strategy_results {
+            println!("{:<10} {:<15} {:<11.2}% {:<14.2}$ {:<10} {:<11.2}$ {:<9.2}%",
+                     symbol, strategy, summary.roi, summary.final_portfolio_value,
+                     summary.num_trades, summary.estimated_fees_paid, summary.max_drawdown);
+        }
+    }
+
+    if let Some(config) = &monte_carlo {
+        println!("\n===== Monte Carlo Robustness ({}, {} runs) =====", config.method, config.runs);
+        println!("{:<10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+                 "Crypto", "ROI p5", "ROI p50", "ROI p95", "DD p50", "DD p95", "P(loss)");
+        println!("{:-<76}", "");
+
+        for (symbol, report) in &monte_carlo_results {
+            println!("{:<10} {:>9.2}% {:>9.2}% {:>9.2}% {:>9.2}% {:>9.2}% {:>9.1}%",
+                     symbol, report.roi.p5, report.roi.p50, report.roi.p95,
+                     report.max_drawdown.p50, report.max_drawdown.p95,
+                     report.probability_of_loss * 100.0);
+        }
     }
     
     println!("\nSimulation completed successfully!");
@@ -161,11 +370,52 @@ async fn run_simulation(crypto_symbol: Option<String>, days: u32) -> anyhow::Res
     Ok(())
 }
 
+fn log_outcome(symbol: &str, outcome: &Outcome) {
+    match outcome {
+        Outcome::Executed(Execution::Placed(order)) => println!("Order {} placed on the mock exchange: {} {:.6} {}", 
+                                                                 order.id, order.request.side, order.request.quantity, order.request.symbol),
+        Outcome::Executed(Execution::DryRun(request)) => println!("Dry run, order not placed: {} {:.6} {}", 
+                                                                   request.side, request.quantity, request.symbol),
+        Outcome::Skipped(reason) => println!("No order for {}: {}", symbol, reason),
+        Outcome::Rejected(reason) => println!("Order for {} rejected by guardrails: {}", symbol, reason),
+        Outcome::AwaitingApproval(token) => println!("Order for {} awaiting approval, run with --approve {}", symbol, token),
+    }
+}
+
+/// Logs where the time of a cycle went: fetching (with the slowest assets), computing
+/// the signals, and the whole cycle including the daily report.
+fn print_cycle_timing(
+    fetch_times: &[(String, Duration)],
+    concurrency: usize,
+    fetch_elapsed: Duration,
+    signals_elapsed: Duration,
+    cycle_elapsed: Duration,
+) {
+    let mut slowest = fetch_times.to_vec();
+    slowest.sort_by_key(|(_, elapsed)| std::cmp::Reverse(*elapsed));
+    let slowest: Vec<String> = slowest.iter()
+        .take(5)
+        .map(|(symbol, elapsed)| format!("{} {:.1}s", symbol, elapsed.as_secs_f64()))
+        .collect();
+    let fetch_total: Duration = fetch_times.iter().map(|(_, elapsed)| *elapsed).sum();
+
+    println!("\n===== Cycle Timing =====");
+    println!("Fetched {} assets in {:.1}s ({} at a time, {:.1}s of fetching in total)", 
+             fetch_times.len(), fetch_elapsed.as_secs_f64(), concurrency, fetch_total.as_secs_f64());
+    if !slowest.is_empty() {
+        println!("Slowest: {}", slowest.join(", "));
+    }
+    println!("Signals computed in {:.1}s", signals_elapsed.as_secs_f64());
+    println!("Cycle completed in {:.1}s", cycle_elapsed.as_secs_f64());
+}
+
 async fn startup(
     daily_report: bool,
-    days: u32,
+    args: &Args,
+    mut executor: Option<&mut GuardedExecutor<MockExchange>>,
 ) -> anyhow::Result<()> {
     dotenv().ok();
+    let cycle_started = Instant::now();
 
     let email_config = match EmailConfig::new() {
         Ok(cfg) => cfg,
@@ -179,10 +429,111 @@ async fn startup(
 
     let mut portfolio_signals: Vec<(String, TradeAction)> = Vec::new();
     
-    let mut symbols: Vec<String> = Vec::new();
-    let mut prices: Vec<Vec<f64>> = Vec::new();
+    let mut histories: Vec<(String, Vec<DataPoint>)> = Vec::new();
+    let mut latest_prices: HashMap<String, f64> = HashMap::new();
     let mut assets_data: Vec<(String, DataFrame)> = Vec::new();
+    let mut paper_accounts: Vec<paper::PaperValuation> = Vec::new();
+    let mut stale_data: Vec<seyeon_email::StaleData> = Vec::new();
+
+    let offline = cache::config().offline;
+
+    println!("\n===== Fetching Global Market Data =====");
+    let global_market_data = if offline {
+        println!("Offline mode: global market data skipped");
+        None
+    } else {
+        match global_market::get_global_data().await {
+            Ok(data) => {
+                println!("Global market data fetched successfully");
+                Some(data)
+            },
+            Err(e) => {
+                eprintln!("Failed to fetch global market data: {}", e);
+                None
+            }
+        }
+    };
+
+    if let Some(market_data) = &global_market_data {
+        match market_data.btc_d.parse::<f64>() {
+            Ok(btc_d) => {
+                let today = Utc::now().format("%Y-%m-%d").to_string();
+                if let Err(e) = record_btc_dominance(&today, btc_d).await {
+                    eprintln!("Failed to record BTC dominance: {}", e);
+                }
+            },
+            Err(e) => eprintln!("Invalid BTC dominance '{}': {}", market_data.btc_d, e),
+        }
+    }
+
+    let dominance = match get_btc_dominance_history().await {
+        Ok(history) => dominance_points(&history),
+        Err(e) => {
+            eprintln!("Failed to load BTC dominance history: {}", e);
+            Vec::new()
+        }
+    };
+
+    println!("\n===== Fetching Price History =====");
+    let mut symbols: Vec<String> = Vec::new();
+    for crypto in fetched_portfolio.iter().flat_map(|field| field.portfolio.iter()) {
+        let symbol = crypto.trim_matches('"').trim().to_string();
+        if !symbols.contains(&symbol) {
+            symbols.push(symbol);
+        }
+    }
+    // BTC is the cross-asset reference of every altcoin, fetched along with the portfolio.
+    let btc_symbol = match symbols.iter().find(|symbol| symbol.eq_ignore_ascii_case("BTC")) {
+        Some(symbol) => symbol.clone(),
+        None => {
+            symbols.push("BTC".to_string());
+            "BTC".to_string()
+        }
+    };
+
+    let fetch_started = Instant::now();
+    let (fgi, fetches) = tokio::join!(
+        fetch_fgi(),
+        fetch_concurrently(&symbols, 2000, args.fetch_concurrency),
+    );
+    let fetch_elapsed = fetch_started.elapsed();
+
+    let fgi_value = fgi.as_ref().and_then(fgi_value);
+    let fetch_times: Vec<(String, Duration)> = fetches.iter()
+        .map(|fetch| (fetch.symbol.clone(), fetch.elapsed))
+        .collect();
+    // Kept by symbol since a symbol may be listed in several portfolios.
+    let fetched: HashMap<String, Result<FetchedData, String>> = fetches.into_iter()
+        .map(|fetch| (fetch.symbol, fetch.result.map_err(|e| format!("{:#}", e))))
+        .collect();
+
+    let btc_reference = match fetched.get(&btc_symbol) {
+        Some(Ok(data)) => Some(data.historical.clone()),
+        Some(Err(e)) => {
+            eprintln!("Failed to fetch BTC reference data, cross-asset features disabled: {}", e);
+            None
+        }
+        None => None,
+    };
+
+    let mut signal_records = get_signal_records().await.unwrap_or_else(|e| {
+        eprintln!("Failed to load signal records: {}", e);
+        Vec::new()
+    });
+
+    if let Some(executor) = executor.as_mut() {
+        let symbols: Vec<String> = fetched_portfolio.iter()
+            .flat_map(|field| field.portfolio.iter())
+            .map(|crypto| crypto.trim_matches('"').trim().to_string())
+            .collect();
+        if offline {
+            println!("Offline mode: no reference prices, orders will be rejected");
+        } else {
+            executor.refresh_reference_prices(&symbols).await;
+        }
+    }
 
+    let signals_started = Instant::now();
     for field in fetched_portfolio.iter() {
         for crypto in field.portfolio.iter() {
             let crypto_symbol = crypto.trim_matches('"').trim().to_string();
@@ -197,63 +548,59 @@ async fn startup(
                     });
             println!("Current status: {:#?}", current_status);
 
-            let fetched_data = match fetch_historical_data(crypto_symbol.clone(), 2000).await {
-                Ok(data) => data,
-                Err(e) => {
-                    let error_msg = e.to_string();
-                    if error_msg.contains("rate limit") {
-                        eprintln!("Rate limit exceeded for {}, checking cache...", crypto_symbol);
-                        
-                        let cache_path = format!("apps/oversight/cache/{}_historical.json", crypto_symbol.to_lowercase());
-                        if std::path::Path::new(&cache_path).exists() {
-                            eprintln!("Using cache as fallback for {}", crypto_symbol);
-                            
-                            // Manually deserialize the cache to work around the error
-                            match std::fs::read_to_string(&cache_path) {
-                                Ok(cache_content) => {
-                                    match serde_json::from_str::<data_fetcher::CacheEntry>(&cache_content) {
-                                        Ok(cache_entry) => {
-                                            eprintln!("Using cached data from {} for {}", 
-                                                     cache_entry.last_updated, 
-                                                     crypto_symbol);
-                                                     
-                                            data_fetcher::FetchedData {
-                                                historical: cache_entry.data,
-                                                fgi: None,
-                                            }
-                                        },
-                                        Err(e) => {
-                                            eprintln!("Cache file exists but couldn't be parsed: {}", e);
-                                            return Err(anyhow::anyhow!("API rate limit exceeded and cache fallback failed: {}", e));
-                                        }
-                                    }
-                                },
-                                Err(e) => {
-                                    eprintln!("Cache file exists but couldn't be read: {}", e);
-                                    return Err(anyhow::anyhow!("API rate limit exceeded and cache fallback failed: {}", e));
-                                }
-                            }
-                        } else {
-                            eprintln!("No cache available for {}", crypto_symbol);
-                            return Err(anyhow::anyhow!("API rate limit exceeded and no cache available: {}", error_msg));
-                        }
-                    } else {
-                        return Err(anyhow::anyhow!("Failed to fetch data for {}: {}", crypto_symbol, error_msg));
-                    }
+            let fetched_data = match fetched.get(&crypto_symbol) {
+                Some(Ok(data)) => data.clone(),
+                Some(Err(e)) => {
+                    eprintln!("Failed to fetch data for {}, skipping: {}", crypto_symbol, e);
+                    continue;
+                }
+                None => {
+                    eprintln!("No data fetched for {}, skipping", crypto_symbol);
+                    continue;
                 }
             };
+            if !fetched_data.freshness.is_fresh() {
+                println!("Signal of {} computed on {} data", crypto_symbol, fetched_data.freshness);
+                stale_data.push(seyeon_email::StaleData {
+                    symbol: crypto_symbol.clone(),
+                    freshness: fetched_data.freshness.to_string(),
+                });
+            }
+
+            if let Some(latest) = fetched_data.historical.last() {
+                latest_prices.insert(crypto_symbol.to_uppercase(), latest.price);
+            }
 
             if daily_report {
-                let asset_prices: Vec<f64> = fetched_data.historical
-                    .iter()
-                    .map(|dp| dp.price)
-                    .collect();
-                
-                symbols.push(crypto_symbol.clone());
-                prices.push(asset_prices);
+                histories.push((crypto_symbol.clone(), fetched_data.historical.clone()));
+            }
+
+            for record in signal_records.iter_mut()
+                .filter(|record| record.symbol.eq_ignore_ascii_case(&crypto_symbol))
+                .filter(|record| record.forward_returns.len() < outcomes::HORIZONS.len())
+            {
+                if outcomes::evaluate(record, &fetched_data.historical, Utc::now()) {
+                    if let Err(e) = record_signal(record).await {
+                        eprintln!("Failed to update signal record {}: {}", record.key(), e);
+                    }
+                }
+            }
+
+            if let Some(executor) = executor.as_mut() {
+                executor.exchange_mut().set_candles(&crypto_symbol, fetched_data.historical.clone());
             }
 
-            let indicators = Indicators::new(fetched_data.historical);
+            // When the cache file holds this history, scan it straight into the indicators.
+            let base = match fetched_data.in_cache.then(|| cache::scan(&crypto_symbol)).flatten() {
+                Some(frame) => Indicators::from_frame(frame),
+                None => Indicators::new(fetched_data.historical),
+            };
+            let indicators = asset_indicators(
+                &crypto_symbol,
+                base,
+                btc_reference.as_deref(),
+                &dominance,
+            );
             let df = indicators
                 .calculate()
                 .context("Failed to calculate indicators")?;
@@ -262,11 +609,40 @@ async fn startup(
                 assets_data.push((crypto_symbol.clone(), df.clone()));
             }
             
-            let fgi_value = fetched_data.fgi.as_ref().and_then(fgi_value);
-            
-            let engine = engine::TradingEngine::new(crypto_symbol.clone(), df, fgi_value, engine::Params::default());
-            
-            let last_event = engine.poll_event();
+            let last_event = match engine::TradingEngine::new(crypto_symbol.clone(), df, fgi_value, engine::Params::default())
+                .and_then(|engine| engine.poll_event())
+            {
+                Ok(event) => event,
+                Err(e @ TradingEngineError::WarmUp { .. }) => {
+                    // Too little history for a signal yet: hold, but keep the asset in the report.
+                    println!("{} is warming up, holding: {}", crypto_symbol, e);
+                    let status = CryptoStatus {
+                        symbol: crypto_symbol.clone(),
+                        action: TradeAction::Hold,
+                        sent: false,
+                    };
+                    set_status(&status).await?;
+                    portfolio_signals.push((status.symbol, status.action));
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("Skipping signal for {}: {}", crypto_symbol, e);
+                    continue;
+                }
+            };
+
+            if args.paper_trading {
+                match paper::follow_signal(&crypto_symbol, &last_event, &engine::Params::default()).await {
+                    Ok((account, trade)) => {
+                        if let Some(trade) = trade {
+                            println!("Paper trade for {}: {} {:.6} @ ${:.4} (fee ${:.2})", 
+                                     crypto_symbol, trade.action, trade.quantity, trade.price, trade.fee);
+                        }
+                        paper_accounts.push(paper::valuate(&account, last_event.price));
+                    },
+                    Err(e) => eprintln!("Failed to update paper account for {}: {}", crypto_symbol, e),
+                }
+            }
 
             let action = match last_event.signal {
                 engine::Signal::Buy => TradeAction::Buy,
@@ -283,11 +659,34 @@ async fn startup(
             if &current_status.action != &status.action {
                 println!("Signal changed for {}: {:?}", status.symbol, status.action);
                 
-                if let Err(e) = email_config.report_sender(&status).await {
+                let stale = stale_data.iter().find(|data| data.symbol == crypto_symbol);
+                if let Err(e) = email_config.report_sender(&status, stale).await {
                     eprintln!("Failed to send email report: {}", e);
                 } else {
                     println!("Email report sent successfully!");
                 }
+
+                if matches!(status.action, TradeAction::Buy | TradeAction::Sell) {
+                    let record = SignalRecord {
+                        symbol: status.symbol.clone(),
+                        action: status.action.clone(),
+                        price: last_event.price,
+                        confidence: last_event.confidence,
+                        datetime: last_event.datetime,
+                        forward_returns: Default::default(),
+                    };
+                    match record_signal(&record).await {
+                        Ok(()) => signal_records.push(record),
+                        Err(e) => eprintln!("Failed to record signal for {}: {}", status.symbol, e),
+                    }
+                }
+
+                if let Some(executor) = executor.as_mut() {
+                    match executor.on_signal_change(&status.symbol, &current_status.action, &status.action, &email_config).await {
+                        Ok(outcome) => log_outcome(&status.symbol, &outcome),
+                        Err(e) => eprintln!("Order for {} failed: {}", status.symbol, e),
+                    }
+                }
             } else {
                 println!("No change in signal for {}", status.symbol);
             }
@@ -297,6 +696,19 @@ async fn startup(
             portfolio_signals.push((status.symbol.clone(), status.action));
         }
     }
+    let signals_elapsed = signals_started.elapsed();
+
+    if let Some(executor) = executor.as_mut() {
+        match executor.execute_approved().await {
+            Ok(outcomes) => {
+                for (approval, outcome) in outcomes {
+                    println!("Approved {} of {} ({})", approval.action, approval.symbol, approval.token);
+                    log_outcome(&approval.symbol, &outcome);
+                }
+            }
+            Err(e) => eprintln!("Failed to execute approved orders: {}", e),
+        }
+    }
 
     if daily_report {
         println!("\n===== Daily Report =====");
@@ -304,31 +716,181 @@ async fn startup(
             println!("{}: {:?}", symbol, action);
         }
 
-        let price_data_refs: Vec<(&str, &Vec<f64>)> = symbols.iter()
-            .zip(prices.iter())
-            .map(|(sym, price_vec)| (sym.as_str(), price_vec))
+        let history_refs: Vec<(&str, &[DataPoint])> = histories.iter()
+            .map(|(sym, history)| (sym.as_str(), history.as_slice()))
             .collect();
         
-        println!("\n===== Generating Correlation Matrix =====");
+        println!("\n===== Generating Correlation Matrices =====");
         
-        let correlation_df = match engine::TradingEngine::calculate_correlation_matrix(&price_data_refs) {
-            Ok(df) => {
-                println!("Correlation matrix calculated successfully");
-                Some(df)
-            },
+        let correlation_matrices = correlation::rolling_correlations(
+            &history_refs,
+            &args.correlation_windows,
+            args.correlation_method,
+        )
+            .into_iter()
+            .zip(&args.correlation_windows)
+            .filter_map(|(result, window_days)| match result {
+                Ok(matrix) => {
+                    println!("{}-day correlation matrix calculated over {} returns", 
+                             window_days, matrix.observations);
+                    Some(matrix)
+                },
+                Err(e) => {
+                    eprintln!("Failed to calculate {}-day correlation matrix: {}", window_days, e);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut correlation_warnings = Vec::new();
+        let correlations = correlation_matrices.into_iter()
+            .map(|matrix| {
+                let average = matrix.average_pairwise();
+
+                if let Some(average) = average.filter(|avg| *avg > args.max_avg_correlation) {
+                    println!("Warning: average {}-day correlation {:.2} exceeds {:.2}", 
+                             matrix.window_days, average, args.max_avg_correlation);
+                    correlation_warnings.push(seyeon_email::CorrelationWarning {
+                        window_days: matrix.window_days,
+                        average,
+                        limit: args.max_avg_correlation,
+                    });
+                }
+
+                seyeon_email::CorrelationTable {
+                    window_days: matrix.window_days,
+                    observations: matrix.observations,
+                    method: matrix.method.to_string(),
+                    average,
+                    matrix: matrix.matrix,
+                }
+            })
+            .collect::<Vec<_>>();
+        
+        println!("\n===== Valuing Holdings =====");
+
+        let positions = match holdings::load_holdings().await {
+            Ok(holdings) => holdings::valuate(&holdings, &latest_prices),
             Err(e) => {
-                eprintln!("Failed to calculate correlation matrix: {}", e);
-                None
+                eprintln!("Failed to load holdings: {}", e);
+                Vec::new()
             }
         };
-        
+
+        for position in &positions {
+            println!("{}: {:.6} @ ${:.4} - value ${:.2} ({:.2}%) - P&L ${:.2} ({:+.2}%)", 
+                     position.symbol, position.quantity, position.price, position.market_value, 
+                     position.allocation * 100.0, position.unrealized_pnl, position.unrealized_pnl_pct);
+        }
+
+        println!("\n===== Checking Target Allocation =====");
+
+        let targets: HashMap<String, f64> = fetched_portfolio.iter()
+            .flat_map(|field| field.targets.iter())
+            .map(|(symbol, weight)| (symbol.to_uppercase(), *weight))
+            .collect();
+        let rebalance_config = fetched_portfolio.first()
+            .map(|field| field.rebalance.clone())
+            .unwrap_or_default();
+
+        let today = Utc::now().date_naive();
+        let last_rebalance = get_last_rebalance().await
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to read last rebalance date: {}", e);
+                None
+            })
+            .and_then(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok());
+
+        let rebalance_plan = rebalance::plan_rebalance(
+            &positions,
+            &targets,
+            &latest_prices,
+            &rebalance_config,
+            engine::Params::default().generic_fee,
+            today,
+            last_rebalance,
+        );
+
+        match &rebalance_plan {
+            Some(plan) => {
+                println!("{}", plan.reason);
+                for trade in &plan.trades {
+                    println!("  {} {}: ${:.2} ({:.6} units)", trade.action, trade.symbol, trade.notional, trade.quantity);
+                }
+
+                // Only the scheduled daily report starts a new period; a forced report
+                // just previews the plan.
+                if rebalance_config.mode == rebalance::RebalanceMode::Calendar && !args.force_report {
+                    if let Err(e) = set_last_rebalance(&today.format("%Y-%m-%d").to_string()).await {
+                        eprintln!("Failed to record rebalance date: {}", e);
+                    }
+                }
+            },
+            None => println!("No rebalance needed"),
+        }
+
+        println!("\n===== Analyzing Portfolio Risk =====");
+
+        // Weight the risk by actual holdings when we have them, equally otherwise.
+        let holding_weights: Vec<f64> = history_refs.iter()
+            .map(|(symbol, _)| {
+                positions.iter()
+                    .find(|p| p.symbol.eq_ignore_ascii_case(symbol))
+                    .map_or(0.0, |p| p.market_value)
+            })
+            .collect();
+        let risk_weights = holding_weights.iter()
+            .any(|w| *w > 0.0)
+            .then_some(holding_weights.as_slice());
+
+        let risk_data = risk::analyze(&history_refs, risk_weights, args.risk_window, &[0.95, 0.99])
+            .map(|report| {
+                if !report.excluded.is_empty() {
+                    println!("Excluded from the {}-day risk window (not enough history): {}", 
+                             args.risk_window, report.excluded.join(", "));
+                }
+                for var in &report.var {
+                    println!("{:.0}% one-day VaR: {:.2}% (historical) / {:.2}% (parametric)", 
+                             var.confidence * 100.0, var.historical_var * 100.0, var.parametric_var * 100.0);
+                }
+
+                seyeon_email::RiskData {
+                    window_days: args.risk_window,
+                    observations: report.observations,
+                    excluded: report.excluded,
+                    portfolio_volatility: report.portfolio_volatility,
+                    var: report.var.into_iter()
+                        .map(|var| seyeon_email::ValueAtRisk {
+                            confidence: var.confidence,
+                            historical_var: var.historical_var,
+                            historical_cvar: var.historical_cvar,
+                            parametric_var: var.parametric_var,
+                            parametric_cvar: var.parametric_cvar,
+                        })
+                        .collect(),
+                    assets: report.assets.into_iter()
+                        .map(|asset| seyeon_email::AssetRiskData {
+                            symbol: asset.symbol,
+                            weight: asset.weight,
+                            annualized_volatility: asset.annualized_volatility,
+                            marginal_contribution: asset.marginal_contribution,
+                            risk_contribution: asset.risk_contribution,
+                        })
+                        .collect(),
+                }
+            });
+
+        if risk_data.is_none() {
+            eprintln!("Not enough aligned history to analyze portfolio risk");
+        }
+
         let assets_data_refs: Vec<(&str, DataFrame)> = assets_data.iter()
             .map(|(sym, df)| (sym.as_str(), df.clone()))
             .collect();
         
         println!("\n===== Analyzing Asset Performance =====");
         
-        let performance_results = engine::TradingEngine::compare_assets_performance(&assets_data_refs, days as usize);
+        let performance_results = engine::TradingEngine::compare_assets_performance(&assets_data_refs, args.days as usize);
         
         let performance_data = performance_results.into_iter()
             .map(|result| seyeon_email::AssetPerformance {
@@ -344,55 +906,110 @@ async fn startup(
             }
         }
 
-        let fgi_data = match fetch_historical_data("BTC".to_string(), 1).await {
-            Ok(data) => {
-                if let Some(fgi_response) = data.fgi {
-                    println!("\nFGI data fetched successfully: {} ({})", 
-                             fgi_response.fgi.now.value, 
-                             fgi_response.fgi.now.value_text);
-                    
-                    Some(seyeon_email::FearAndGreedData {
-                        value: fgi_response.fgi.now.value as u8,
-                        classification: fgi_response.fgi.now.value_text,
-                        timestamp: fgi_response.last_updated.human_date,
-                    })
-                } else {
-                    println!("\nNo FGI data available");
-                    None
-                }
-            },
-            Err(e) => {
-                eprintln!("\nFailed to fetch FGI data: {}", e);
-                None
+        let paper_data = if args.paper_trading {
+            println!("\n===== Paper Trading =====");
+
+            for account in &paper_accounts {
+                println!("{}: equity ${:.2} - P&L ${:.2} ({:+.2}%) - {} trades", 
+                         account.symbol, account.equity, account.pnl, account.pnl_pct, account.trades);
             }
+
+            let mut recent_trades = get_paper_ledger().await.unwrap_or_else(|e| {
+                eprintln!("Failed to load paper ledger: {}", e);
+                Vec::new()
+            });
+            recent_trades.reverse();
+            recent_trades.truncate(10);
+
+            Some(seyeon_email::PaperTradingData {
+                accounts: paper_accounts.iter()
+                    .map(|account| seyeon_email::PaperAccountData {
+                        symbol: account.symbol.clone(),
+                        cash: account.cash,
+                        quantity: account.quantity,
+                        average_cost: account.average_cost,
+                        price: account.price,
+                        equity: account.equity,
+                        pnl: account.pnl,
+                        pnl_pct: account.pnl_pct,
+                        realized_pnl: account.realized_pnl,
+                        fees_paid: account.fees_paid,
+                        trades: account.trades,
+                        simulated_roi: performance_data.iter()
+                            .find(|result| result.symbol.eq_ignore_ascii_case(&account.symbol))
+                            .map(|result| result.roi),
+                    })
+                    .collect(),
+                recent_trades,
+            })
+        } else {
+            None
         };
-        
-        // Fetch global cryptocurrency market data
-        println!("\n===== Fetching Global Market Data =====");
-        let global_market_data = match global_market::get_global_data().await {
-            Ok(data) => {
-                println!("Global market data fetched successfully");
-                Some(data)
+
+        let fgi_data = match fgi {
+            Some(fgi_response) => {
+                println!("\nFGI: {} ({})", fgi_response.fgi.now.value, fgi_response.fgi.now.value_text);
+
+                Some(seyeon_email::FearAndGreedData {
+                    value: fgi_response.fgi.now.value as u8,
+                    classification: fgi_response.fgi.now.value_text,
+                    timestamp: fgi_response.last_updated.human_date,
+                })
             },
-            Err(e) => {
-                eprintln!("Failed to fetch global market data: {}", e);
+            None => {
+                println!("\nNo FGI data available");
                 None
             }
         };
 
-        if let Err(e) = email_config.send_daily_report(
-            portfolio_signals, 
-            correlation_df, 
-            if !performance_data.is_empty() { Some(performance_data) } else { None },
-            fgi_data,
-            global_market_data
-        ).await {
+        let report = DailyReport {
+            signals: portfolio_signals,
+            positions: positions.into_iter()
+                .map(|position| seyeon_email::PositionData {
+                    symbol: position.symbol,
+                    quantity: position.quantity,
+                    average_cost: position.average_cost,
+                    price: position.price,
+                    market_value: position.market_value,
+                    unrealized_pnl: position.unrealized_pnl,
+                    unrealized_pnl_pct: position.unrealized_pnl_pct,
+                    allocation: position.allocation,
+                })
+                .collect(),
+            rebalance: rebalance_plan.map(|plan| seyeon_email::RebalanceData {
+                reason: plan.reason,
+                trades: plan.trades.into_iter()
+                    .map(|trade| seyeon_email::RebalanceTradeData {
+                        symbol: trade.symbol,
+                        action: trade.action,
+                        current_weight: trade.current_weight,
+                        target_weight: trade.target_weight,
+                        notional: trade.notional,
+                        quantity: trade.quantity,
+                        estimated_fee: trade.estimated_fee,
+                    })
+                    .collect(),
+            }),
+            paper: paper_data,
+            scorecard: Some(outcomes::scorecard(&signal_records, args.scorecard_days, Utc::now())),
+            correlations,
+            correlation_warnings,
+            performance: if !performance_data.is_empty() { Some(performance_data) } else { None },
+            risk: risk_data,
+            stale_data,
+            fgi: fgi_data,
+            global_market: global_market_data,
+        };
+
+        if let Err(e) = email_config.send_daily_report(report).await {
             eprintln!("Failed to send email report: {}", e);
         } else {
             println!("\nDaily report with correlation, performance analysis, market sentiment, and global cryptocurrency market data sent successfully by email!");
         }
     }
 
+    print_cycle_timing(&fetch_times, args.fetch_concurrency, fetch_elapsed, signals_elapsed, cycle_started.elapsed());
+
     Ok(())
 }
 
@@ -402,10 +1019,66 @@ fn main() -> anyhow::Result<()> {
     let args = Args::parse();
     
     let rt = tokio::runtime::Runtime::new().unwrap();
+
+    let mut cache_config = cache::CacheConfig::from_env();
+    if let Some(dir) = &args.cache_dir {
+        cache_config.dir = dir.clone();
+    }
+    if let Some(hours) = args.cache_ttl_hours {
+        cache_config.ttl = chrono::Duration::hours(hours);
+    }
+    if let Some(overrides) = &args.cache_ttl_overrides {
+        cache_config.symbol_ttl = cache::parse_symbol_ttl(overrides).map_err(anyhow::Error::msg)?;
+    }
+    cache_config.offline |= args.offline;
+    if cache_config.offline {
+        println!("Offline mode: using the price cache in {}", cache_config.dir.display());
+    }
+    cache::configure(cache_config);
+
+    if args.migrate_cache {
+        println!("\n===== Migrating Cache to Parquet =====");
+        let migrated = cache::migrate_to_parquet(&cache::config().dir)?;
+        println!("{} cache files migrated", migrated.len());
+        return Ok(());
+    }
+
+    if let Some(token) = &args.approve {
+        return match rt.block_on(approve_pending(token))? {
+            Some(approval) => {
+                println!("Approved {} of {:.6} {} (${:.2}), it will be placed on the next run", 
+                         approval.action, approval.quantity, approval.symbol, approval.notional);
+                Ok(())
+            }
+            None => Err(anyhow::anyhow!("No pending order with token {}", token)),
+        };
+    }
+
+    let mut executor = if args.execute && !args.simulate {
+        let params = engine::Params::default();
+        let (config, guardrails) = rt.block_on(portfolio_fetcher())?
+            .first()
+            .map(|field| (field.execution.clone(), field.guardrails.clone()))
+            .unwrap_or_default();
+
+        println!("Order execution enabled on the mock exchange{} with ${:.2}, limits {:?} and guardrails {:?}", 
+                 if args.dry_run { " (dry run)" } else { "" }, params.initial_capital, config, guardrails);
+        Some(GuardedExecutor::new(
+            Executor::new(
+                MockExchange::new(params.initial_capital, params.generic_fee),
+                config,
+                params.generic_fee,
+                args.dry_run,
+            ),
+            guardrails,
+        ))
+    } else {
+        None
+    };
     
     if args.simulate {
         if let Err(e) = rt.block_on(async {
-            run_simulation(args.crypto, args.days).await
+            run_simulation(&args).await
         }) {
             eprintln!("Error during simulation: {}", e);
             return Err(e);
@@ -419,7 +1092,7 @@ fn main() -> anyhow::Result<()> {
         println!("\n===== Forcing daily report generation =====");
         
         if let Err(e) = rt.block_on(async {
-            startup(true, args.days).await
+            startup(true, &args, executor.as_mut()).await
         }) {
             eprintln!("Error during forced report generation: {}", e);
             return Err(e);
@@ -457,7 +1130,7 @@ fn main() -> anyhow::Result<()> {
         };
         
         if let Err(e) = rt.block_on(async {
-            startup(daily_report, args.days).await
+            startup(daily_report, &args, executor.as_mut()).await
         }) {
             eprintln!("Error during startup: {}", e);
         }
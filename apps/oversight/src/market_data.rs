@@ -0,0 +1,682 @@
+use chrono::{DateTime, Duration, Utc};
+use seyeon_coinlore::CoinloreClient;
+use seyeon_cryptocompare::CryptocompareClient;
+use seyeon_cryptocompare::histoday::{CallsInfo, CryptoCompareHistodayResponse, Histoday, HistodayParams, RateLimit};
+use seyeon_trading_engine::data_point::DataPoint;
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::str::FromStr;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+
+use crate::cache;
+use crate::key_pool::{KeyPool, QuotaPeriod};
+
+/// Pages of 100 Coinlore tickers searched for a symbol.
+const COINLORE_PAGES: u32 = 5;
+const COINLORE_PAGE_SIZE: u32 = 100;
+/// Calls of a key counted against, unless `CRYPTOCOMPARE_API_KEY_LIMITS` says otherwise.
+/// CryptoCompare only reports the calls left on a key once it is over a limit.
+const CRYPTOCOMPARE_LIMITS: [(QuotaPeriod, i64); 3] =
+    [(QuotaPeriod::Hour, 3_000), (QuotaPeriod::Day, 7_500), (QuotaPeriod::Month, 100_000)];
+
+#[derive(Error, Debug)]
+pub enum ProviderError {
+    #[error("{provider}: rate limit exceeded: {message}")]
+    RateLimited { provider: String, message: String },
+    #[error("{provider}: {message}")]
+    Unavailable { provider: String, message: String },
+    #[error("{provider}: no data for {symbol}")]
+    NotFound { provider: String, symbol: String },
+    #[error("{provider} does not provide {what}")]
+    Unsupported { provider: String, what: &'static str },
+    #[error("every market data provider failed: {}", .0.join("; "))]
+    Exhausted(Vec<String>),
+}
+
+/// Latest price of a symbol in USD.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticker {
+    pub symbol: String,
+    pub price: f64,
+    pub datetime: DateTime<Utc>,
+}
+
+/// A source of daily OHLCV history and latest prices, in USD.
+pub trait MarketDataProvider {
+    fn name(&self) -> &str;
+
+    /// Daily candles of `symbol` from `from` to `to` (both inclusive), oldest first.
+    fn fetch_ohlcv(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<DataPoint>, ProviderError>> + Send;
+
+    fn latest_ticker(&self, symbol: &str) -> impl Future<Output = Result<Ticker, ProviderError>> + Send;
+}
+
+/// Daily history from CryptoCompare's `histoday`, with the keys of `CRYPTOCOMPARE_API_KEY`
+/// drawn from a `KeyPool`.
+pub struct CryptoCompareProvider;
+
+/// Calls left in each quota of a CryptoCompare response. Rate-limit errors report the
+/// calls made and the maximum instead of the calls left.
+fn calls_left(rate_limit: &RateLimit) -> [(QuotaPeriod, Option<i64>); 5] {
+    let left = |period: fn(&CallsInfo) -> Option<i32>| {
+        let made_and_max = || {
+            let made = period(rate_limit.calls_made.as_ref()?)?;
+            let max = period(rate_limit.max_calls.as_ref()?)?;
+            Some(i64::from(max) - i64::from(made))
+        };
+        rate_limit.calls_left.as_ref().and_then(period).map(i64::from).or_else(made_and_max)
+    };
+
+    [
+        (QuotaPeriod::Second, left(|calls| calls.second)),
+        (QuotaPeriod::Minute, left(|calls| calls.minute)),
+        (QuotaPeriod::Hour, left(|calls| calls.hour)),
+        (QuotaPeriod::Day, left(|calls| calls.day)),
+        (QuotaPeriod::Month, left(|calls| calls.month)),
+    ]
+}
+
+/// Calls left in the tightest of the hourly, daily and monthly quotas of a CryptoCompare
+/// response, and when the last exhausted quota resets.
+fn quota(rate_limit: &RateLimit, now: DateTime<Utc>) -> (Option<i64>, Option<DateTime<Utc>>) {
+    let periods = calls_left(rate_limit);
+
+    let tightest = periods.iter()
+        .filter(|(period, _)| matches!(period, QuotaPeriod::Hour | QuotaPeriod::Day | QuotaPeriod::Month))
+        .filter_map(|(_, left)| *left)
+        .min();
+    let reset = periods.iter()
+        .filter(|(_, left)| left.is_some_and(|left| left <= 0))
+        .map(|(period, _)| period.next_reset(now))
+        .max();
+
+    (tightest, reset)
+}
+
+/// Whether a CryptoCompare response is an error because a quota of the key ran out, going
+/// by its `RateLimit` rather than the wording of its message.
+fn is_rate_limited(response: &CryptoCompareHistodayResponse) -> bool {
+    response.response == "Error"
+        && response.rate_limit.as_ref().is_some_and(|rate_limit| {
+            calls_left(rate_limit).iter().any(|(_, left)| left.is_some_and(|left| left <= 0))
+        })
+}
+
+impl CryptoCompareProvider {
+    fn unavailable(message: impl Into<String>) -> ProviderError {
+        ProviderError::Unavailable { provider: "cryptocompare".to_string(), message: message.into() }
+    }
+
+    fn rate_limited(message: impl Into<String>) -> ProviderError {
+        ProviderError::RateLimited { provider: "cryptocompare".to_string(), message: message.into() }
+    }
+
+    async fn histoday(&self, symbol: &str, limit: u32, to: DateTime<Utc>) -> Result<Vec<DataPoint>, ProviderError> {
+        let pool = KeyPool::from_env("CRYPTOCOMPARE_API_KEY")
+            .map_err(|e| Self::unavailable(e.to_string()))?
+            .with_limits(&CRYPTOCOMPARE_LIMITS);
+
+        // A key that hits its limit is quarantined and the call retried with the next one.
+        let mut attempts = 0;
+        let response = loop {
+            attempts += 1;
+            // Only fails when every key is quarantined.
+            let key = pool.select().await.map_err(|e| Self::rate_limited(e.to_string()))?;
+
+            let params = HistodayParams::builder()
+                .source_sym(symbol)
+                .target_sym("USD")
+                .limit(limit)
+                .to_ts(to.timestamp() as u32)
+                .build();
+
+            let result = CryptocompareClient::new(&key.value).call::<Histoday>(params).await;
+            pool.record_call(&key).await;
+
+            let error = match result {
+                Ok(response) => {
+                    let (calls_left, reset) = response.rate_limit.as_ref()
+                        .map_or((None, None), |rate_limit| quota(rate_limit, Utc::now()));
+
+                    if response.response != "Error" {
+                        pool.report(&key, calls_left, reset).await;
+                        break response;
+                    }
+                    if !is_rate_limited(&response) {
+                        return Err(Self::unavailable(response.message));
+                    }
+                    pool.quarantine(&key, reset).await;
+                    Self::rate_limited(response.message)
+                }
+                Err(e) if e.status().is_some_and(|status| status.as_u16() == 429) => {
+                    pool.quarantine(&key, None).await;
+                    Self::rate_limited(e.to_string())
+                }
+                Err(e) => return Err(Self::unavailable(e.to_string())),
+            };
+
+            if attempts >= pool.len() {
+                return Err(error);
+            }
+        };
+
+        let data = response.data.ok_or_else(|| ProviderError::NotFound {
+            provider: self.name().to_string(),
+            symbol: symbol.to_string(),
+        })?;
+
+        Ok(data
+            .data
+            .iter()
+            .filter_map(|d| {
+                Some(DataPoint {
+                    datetime: DateTime::from_timestamp(d.time, 0)?,
+                    price: d.close,
+                    high: d.high,
+                    low: d.low,
+                    open: d.open,
+                    volume: d.volumefrom,
+                })
+            })
+            .collect())
+    }
+}
+
+impl MarketDataProvider for CryptoCompareProvider {
+    fn name(&self) -> &str {
+        "cryptocompare"
+    }
+
+    async fn fetch_ohlcv(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<DataPoint>, ProviderError> {
+        let limit = (to - from).num_days().max(1) as u32;
+        let mut candles = self.histoday(symbol, limit, to).await?;
+        candles.retain(|c| c.datetime >= from - Duration::days(1) && c.datetime <= to);
+        Ok(candles)
+    }
+
+    async fn latest_ticker(&self, symbol: &str) -> Result<Ticker, ProviderError> {
+        let candle = self
+            .histoday(symbol, 1, Utc::now())
+            .await?
+            .pop()
+            .ok_or_else(|| ProviderError::NotFound {
+                provider: self.name().to_string(),
+                symbol: symbol.to_string(),
+            })?;
+
+        Ok(Ticker {
+            symbol: symbol.to_uppercase(),
+            price: candle.price,
+            datetime: candle.datetime,
+        })
+    }
+}
+
+/// Latest prices from the Coinlore tickers. Coinlore has no price history.
+///
+/// The ticker pages are fetched on the first lookup and shared by every later one, so
+/// a provider is meant to live for a single cycle.
+#[derive(Default)]
+pub struct CoinloreProvider {
+    tickers: OnceCell<HashMap<String, Ticker>>,
+}
+
+impl CoinloreProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn tickers(&self) -> Result<&HashMap<String, Ticker>, ProviderError> {
+        self.tickers
+            .get_or_try_init(|| async {
+                let client = CoinloreClient::new();
+                let mut tickers = HashMap::new();
+
+                for page in 0..COINLORE_PAGES {
+                    let response = match client
+                        .get_tickers(Some(page * COINLORE_PAGE_SIZE), Some(COINLORE_PAGE_SIZE))
+                        .await
+                    {
+                        Ok(response) => response,
+                        Err(e) if page == 0 => {
+                            return Err(ProviderError::Unavailable {
+                                provider: self.name().to_string(),
+                                message: e.to_string(),
+                            })
+                        }
+                        Err(e) => {
+                            eprintln!("Coinlore tickers page {} failed, using the first {}: {}", page, page, e);
+                            break;
+                        }
+                    };
+
+                    let datetime = DateTime::from_timestamp(response.info.time, 0).unwrap_or_else(Utc::now);
+                    let last_page = response.data.len() < COINLORE_PAGE_SIZE as usize;
+
+                    // Tickers come by rank, so a shared symbol resolves to the largest coin.
+                    for ticker in response.data {
+                        let Ok(price) = ticker.price_usd.parse::<f64>() else {
+                            continue;
+                        };
+                        let symbol = ticker.symbol.to_uppercase();
+                        tickers.entry(symbol.clone()).or_insert(Ticker { symbol, price, datetime });
+                    }
+
+                    if last_page {
+                        break;
+                    }
+                }
+
+                Ok(tickers)
+            })
+            .await
+    }
+}
+
+impl MarketDataProvider for CoinloreProvider {
+    fn name(&self) -> &str {
+        "coinlore"
+    }
+
+    async fn fetch_ohlcv(
+        &self,
+        _symbol: &str,
+        _from: DateTime<Utc>,
+        _to: DateTime<Utc>,
+    ) -> Result<Vec<DataPoint>, ProviderError> {
+        Err(ProviderError::Unsupported {
+            provider: self.name().to_string(),
+            what: "price history",
+        })
+    }
+
+    async fn latest_ticker(&self, symbol: &str) -> Result<Ticker, ProviderError> {
+        self.tickers()
+            .await?
+            .get(&symbol.to_uppercase())
+            .cloned()
+            .ok_or_else(|| ProviderError::NotFound {
+                provider: self.name().to_string(),
+                symbol: symbol.to_string(),
+            })
+    }
+}
+
+/// Daily history read from the cache files of a directory, `{symbol}_historical.parquet`
+/// or `{symbol}_historical.json` (in the cache format or as a bare array of data points).
+pub struct LocalFileProvider {
+    dir: PathBuf,
+}
+
+impl LocalFileProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn load(&self, symbol: &str) -> Result<Vec<DataPoint>, ProviderError> {
+        cache::load_from(&self.dir, symbol, cache::config().format)
+            .map(|entry| entry.data)
+            .ok_or_else(|| ProviderError::NotFound {
+                provider: self.name().to_string(),
+                symbol: symbol.to_string(),
+            })
+    }
+}
+
+impl MarketDataProvider for LocalFileProvider {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    async fn fetch_ohlcv(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<DataPoint>, ProviderError> {
+        let mut data = self.load(symbol)?;
+        data.retain(|point| point.datetime >= from - Duration::days(1) && point.datetime <= to);
+
+        if data.is_empty() {
+            return Err(ProviderError::NotFound {
+                provider: self.name().to_string(),
+                symbol: symbol.to_string(),
+            });
+        }
+        Ok(data)
+    }
+
+    async fn latest_ticker(&self, symbol: &str) -> Result<Ticker, ProviderError> {
+        let point = self.load(symbol)?.pop().ok_or_else(|| ProviderError::NotFound {
+            provider: self.name().to_string(),
+            symbol: symbol.to_string(),
+        })?;
+
+        Ok(Ticker {
+            symbol: symbol.to_uppercase(),
+            price: point.price,
+            datetime: point.datetime,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    CryptoCompare,
+    Coinlore,
+    Local,
+}
+
+impl fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderKind::CryptoCompare => write!(f, "cryptocompare"),
+            ProviderKind::Coinlore => write!(f, "coinlore"),
+            ProviderKind::Local => write!(f, "local"),
+        }
+    }
+}
+
+impl FromStr for ProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "cryptocompare" => Ok(ProviderKind::CryptoCompare),
+            "coinlore" => Ok(ProviderKind::Coinlore),
+            "local" => Ok(ProviderKind::Local),
+            other => Err(format!("Unknown market data provider '{}'", other)),
+        }
+    }
+}
+
+/// Any of the providers above, so they can be chained.
+pub enum Provider {
+    CryptoCompare(CryptoCompareProvider),
+    Coinlore(CoinloreProvider),
+    Local(LocalFileProvider),
+}
+
+impl Provider {
+    pub fn new(kind: ProviderKind, local_dir: &str) -> Self {
+        match kind {
+            ProviderKind::CryptoCompare => Provider::CryptoCompare(CryptoCompareProvider),
+            ProviderKind::Coinlore => Provider::Coinlore(CoinloreProvider::new()),
+            ProviderKind::Local => Provider::Local(LocalFileProvider::new(local_dir)),
+        }
+    }
+}
+
+impl Provider {
+    pub fn kind(&self) -> ProviderKind {
+        match self {
+            Provider::CryptoCompare(_) => ProviderKind::CryptoCompare,
+            Provider::Coinlore(_) => ProviderKind::Coinlore,
+            Provider::Local(_) => ProviderKind::Local,
+        }
+    }
+}
+
+impl MarketDataProvider for Provider {
+    fn name(&self) -> &str {
+        match self {
+            Provider::CryptoCompare(provider) => provider.name(),
+            Provider::Coinlore(provider) => provider.name(),
+            Provider::Local(provider) => provider.name(),
+        }
+    }
+
+    async fn fetch_ohlcv(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<DataPoint>, ProviderError> {
+        match self {
+            Provider::CryptoCompare(provider) => provider.fetch_ohlcv(symbol, from, to).await,
+            Provider::Coinlore(provider) => provider.fetch_ohlcv(symbol, from, to).await,
+            Provider::Local(provider) => provider.fetch_ohlcv(symbol, from, to).await,
+        }
+    }
+
+    async fn latest_ticker(&self, symbol: &str) -> Result<Ticker, ProviderError> {
+        match self {
+            Provider::CryptoCompare(provider) => provider.latest_ticker(symbol).await,
+            Provider::Coinlore(provider) => provider.latest_ticker(symbol).await,
+            Provider::Local(provider) => provider.latest_ticker(symbol).await,
+        }
+    }
+}
+
+/// Providers tried in order until one answers.
+pub struct ProviderChain {
+    providers: Vec<Provider>,
+}
+
+impl ProviderChain {
+    pub const DEFAULT_ORDER: &'static str = "cryptocompare,coinlore,local";
+
+    pub fn new(providers: Vec<Provider>) -> Self {
+        Self { providers }
+    }
+
+    /// Chain of `MARKET_DATA_PROVIDERS` (comma separated, `DEFAULT_ORDER` by default).
+    /// The local provider reads `LOCAL_DATA_DIR`, the cache directory by default.
+    pub fn from_env() -> Self {
+        let order = env::var("MARKET_DATA_PROVIDERS").unwrap_or_else(|_| Self::DEFAULT_ORDER.to_string());
+        let local_dir = env::var("LOCAL_DATA_DIR")
+            .unwrap_or_else(|_| cache::config().dir.to_string_lossy().to_string());
+
+        Self::new(
+            Self::parse_order(&order)
+                .into_iter()
+                .map(|kind| Provider::new(kind, &local_dir))
+                .collect(),
+        )
+    }
+
+    /// Provider kinds of a comma-separated list, skipping unknown names. Falls back to
+    /// `DEFAULT_ORDER` when none is valid.
+    pub fn parse_order(order: &str) -> Vec<ProviderKind> {
+        let kinds: Vec<ProviderKind> = order
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .filter_map(|name| {
+                name.parse()
+                    .map_err(|e| eprintln!("{}, skipping it", e))
+                    .ok()
+            })
+            .collect();
+        if !kinds.is_empty() {
+            return kinds;
+        }
+
+        eprintln!("No valid provider in MARKET_DATA_PROVIDERS, using {}", Self::DEFAULT_ORDER);
+        vec![ProviderKind::CryptoCompare, ProviderKind::Coinlore, ProviderKind::Local]
+    }
+
+    /// Moves the providers of `kind` to the front, keeping the others in order.
+    pub fn prefer(mut self, kind: ProviderKind) -> Self {
+        self.providers.sort_by_key(|provider| provider.kind() != kind);
+        self
+    }
+
+    /// History from the first provider that has it, with the name of that provider.
+    pub async fn fetch_ohlcv(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<(String, Vec<DataPoint>), ProviderError> {
+        let mut errors = Vec::new();
+
+        for provider in &self.providers {
+            match provider.fetch_ohlcv(symbol, from, to).await {
+                Ok(data) if !data.is_empty() => return Ok((provider.name().to_string(), data)),
+                Ok(_) => errors.push(format!("{}: no data for {}", provider.name(), symbol)),
+                Err(ProviderError::Unsupported { .. }) => {}
+                Err(e) => {
+                    eprintln!("Market data provider {} failed for {}: {}", provider.name(), symbol, e);
+                    errors.push(e.to_string());
+                }
+            }
+        }
+
+        Err(ProviderError::Exhausted(errors))
+    }
+
+    /// Latest price from the first provider that has it, with the name of that provider.
+    pub async fn latest_ticker(&self, symbol: &str) -> Result<(String, Ticker), ProviderError> {
+        let mut errors = Vec::new();
+
+        for provider in &self.providers {
+            match provider.latest_ticker(symbol).await {
+                Ok(ticker) => return Ok((provider.name().to_string(), ticker)),
+                Err(ProviderError::Unsupported { .. }) => {}
+                Err(e) => {
+                    eprintln!("Market data provider {} has no price for {}: {}", provider.name(), symbol, e);
+                    errors.push(e.to_string());
+                }
+            }
+        }
+
+        Err(ProviderError::Exhausted(errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use seyeon_trading_engine::data_point::DailySeries;
+    use std::path::Path;
+
+    /// An empty directory of its own under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("seyeon-market-data-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_history(dir: &Path, symbol: &str, prices: &[f64]) {
+        let points = DailySeries::new().prices(prices);
+
+        let path = dir.join(format!("{}_historical.json", symbol.to_lowercase()));
+        std::fs::write(path, serde_json::to_string(&points).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn parse_order_skips_unknown_providers() {
+        assert_eq!(
+            ProviderChain::parse_order(" local , CoinLore"),
+            vec![ProviderKind::Local, ProviderKind::Coinlore]
+        );
+        assert_eq!(ProviderChain::parse_order("binance,local,"), vec![ProviderKind::Local]);
+
+        let default = vec![ProviderKind::CryptoCompare, ProviderKind::Coinlore, ProviderKind::Local];
+        assert_eq!(ProviderChain::parse_order(""), default);
+        assert_eq!(ProviderChain::parse_order("binance"), default);
+    }
+
+    #[test]
+    fn prefer_moves_a_provider_to_the_front() {
+        let chain = ProviderChain::new(
+            ProviderChain::parse_order(ProviderChain::DEFAULT_ORDER)
+                .into_iter()
+                .map(|kind| Provider::new(kind, "unused"))
+                .collect(),
+        )
+        .prefer(ProviderKind::Coinlore);
+
+        let kinds: Vec<ProviderKind> = chain.providers.iter().map(Provider::kind).collect();
+        assert_eq!(kinds, vec![ProviderKind::Coinlore, ProviderKind::CryptoCompare, ProviderKind::Local]);
+    }
+
+    #[tokio::test]
+    async fn chain_falls_back_to_the_next_provider() {
+        let empty = temp_dir("empty");
+        let filled = temp_dir("filled");
+        write_history(&filled, "ETH", &[100.0, 101.0, 102.0]);
+
+        // Coinlore has no history and is skipped without a request.
+        let chain = ProviderChain::new(vec![
+            Provider::Coinlore(CoinloreProvider::new()),
+            Provider::Local(LocalFileProvider::new(&empty)),
+            Provider::Local(LocalFileProvider::new(&filled)),
+        ]);
+        let from = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let to = from + Duration::days(2);
+
+        let (provider, data) = chain.fetch_ohlcv("eth", from, to).await.unwrap();
+        assert_eq!(provider, "local");
+        assert_eq!(data.len(), 3);
+
+        let chain = ProviderChain::new(vec![
+            Provider::Local(LocalFileProvider::new(&empty)),
+            Provider::Local(LocalFileProvider::new(&filled)),
+        ]);
+        let (_, ticker) = chain.latest_ticker("ETH").await.unwrap();
+        assert_eq!(ticker.price, 102.0);
+        assert_eq!(ticker.datetime, to);
+
+        match chain.fetch_ohlcv("BTC", from, to).await {
+            Err(ProviderError::Exhausted(errors)) => assert_eq!(errors.len(), 2),
+            other => panic!("expected every provider to fail, got {:?}", other),
+        }
+
+        std::fs::remove_dir_all(empty).unwrap();
+        std::fs::remove_dir_all(filled).unwrap();
+    }
+
+    fn response(json: &str) -> CryptoCompareHistodayResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn rate_limits_are_read_from_the_quotas_not_the_message() {
+        let now = Utc.with_ymd_and_hms(2025, 12, 14, 9, 30, 0).unwrap();
+
+        // Successful calls come with an empty RateLimit.
+        let ok = response(r#"{"Response":"Success","Message":"","HasWarning":false,"Type":100,"RateLimit":{},"Data":null}"#);
+        assert!(!is_rate_limited(&ok));
+        assert_eq!(quota(ok.rate_limit.as_ref().unwrap(), now), (None, None));
+
+        let limited = response(
+            r#"{"Response":"Error","Message":"You are over your rate limit please upgrade your account!","HasWarning":false,"Type":99,
+                "RateLimit":{"calls_made":{"second":1,"hour":20,"day":40,"month":100000},
+                             "max_calls":{"second":20,"hour":250000,"day":250000,"month":100000}},"Data":null}"#,
+        );
+        assert!(is_rate_limited(&limited));
+        assert_eq!(
+            quota(limited.rate_limit.as_ref().unwrap(), now),
+            (Some(0), Some(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()))
+        );
+
+        let worded = response(r#"{"Response":"Error","Message":"rate limit of the fsym param","HasWarning":false,"Type":1,"RateLimit":{},"Data":null}"#);
+        assert!(!is_rate_limited(&worded));
+
+        let left = response(
+            r#"{"Response":"Error","Message":"","HasWarning":false,"Type":99,"RateLimit":{"calls_left":{"hour":0,"day":12}},"Data":null}"#,
+        );
+        assert!(is_rate_limited(&left));
+        assert_eq!(
+            quota(left.rate_limit.as_ref().unwrap(), now),
+            (Some(0), Some(Utc.with_ymd_and_hms(2025, 12, 14, 10, 0, 0).unwrap()))
+        );
+    }
+}
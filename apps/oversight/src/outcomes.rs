@@ -0,0 +1,212 @@
+use chrono::{DateTime, Duration, Utc};
+use seyeon_email::{HorizonStats, ScorecardRow, SignalScorecardData};
+use seyeon_redis::{SignalRecord, TradeAction};
+use seyeon_trading_engine::data_point::DataPoint;
+
+/// Days after a signal its forward return is measured at.
+pub const HORIZONS: [u32; 3] = [1, 7, 30];
+
+/// Fills in the forward returns of `record` that `history` (daily closes, oldest first)
+/// now covers. Only candles that have closed by `now` count, so the partial candle of the
+/// current day is never scored. Returns true if any was added.
+pub fn evaluate(record: &mut SignalRecord, history: &[DataPoint], now: DateTime<Utc>) -> bool {
+    if record.price <= 0.0 {
+        return false;
+    }
+
+    let mut updated = false;
+    for days in HORIZONS {
+        if record.forward_returns.contains_key(&days) {
+            continue;
+        }
+
+        let target = record.datetime + Duration::days(days as i64);
+        let idx = history.partition_point(|point| point.datetime < target);
+        // The close of the target day, or the first one after it if the day is missing.
+        if let Some(point) = history
+            .get(idx)
+            .filter(|point| point.price > 0.0 && point.datetime + Duration::days(1) <= now)
+        {
+            record.forward_returns.insert(days, point.price / record.price - 1.0);
+            updated = true;
+        }
+    }
+
+    updated
+}
+
+fn is_hit(action: &TradeAction, forward_return: f64) -> bool {
+    match action {
+        TradeAction::Sell => forward_return < 0.0,
+        _ => forward_return > 0.0,
+    }
+}
+
+fn row(symbol: Option<String>, action: TradeAction, records: &[&SignalRecord]) -> ScorecardRow {
+    let horizons = HORIZONS
+        .iter()
+        .map(|&days| {
+            let returns: Vec<f64> = records
+                .iter()
+                .filter_map(|record| record.forward_returns.get(&days).copied())
+                .collect();
+            let evaluated = returns.len();
+            let (hit_rate, average_return) = if evaluated == 0 {
+                (None, None)
+            } else {
+                let hits = returns.iter().filter(|&&r| is_hit(&action, r)).count();
+                (
+                    Some(hits as f64 / evaluated as f64),
+                    Some(returns.iter().sum::<f64>() / evaluated as f64),
+                )
+            };
+
+            HorizonStats {
+                days,
+                evaluated,
+                hit_rate,
+                average_return,
+            }
+        })
+        .collect();
+
+    ScorecardRow {
+        symbol,
+        action,
+        signals: records.len(),
+        average_confidence: records.iter().map(|record| record.confidence).sum::<f64>() / records.len() as f64,
+        horizons,
+    }
+}
+
+/// Hit rate and average forward return of the Buy and Sell signals emitted in the
+/// `window_days` before `now`: one row per signal type across all assets, then one per
+/// asset and signal type.
+pub fn scorecard(records: &[SignalRecord], window_days: i64, now: DateTime<Utc>) -> SignalScorecardData {
+    let since = now - Duration::days(window_days);
+    let recent: Vec<&SignalRecord> = records.iter().filter(|record| record.datetime >= since).collect();
+
+    let mut symbols: Vec<String> = recent.iter().map(|record| record.symbol.to_uppercase()).collect();
+    symbols.sort();
+    symbols.dedup();
+
+    let mut rows = Vec::new();
+    for action in [TradeAction::Buy, TradeAction::Sell] {
+        let of_action: Vec<&SignalRecord> = recent.iter().copied().filter(|record| record.action == action).collect();
+        if !of_action.is_empty() {
+            rows.push(row(None, action.clone(), &of_action));
+        }
+    }
+    for symbol in symbols {
+        for action in [TradeAction::Buy, TradeAction::Sell] {
+            let of_asset: Vec<&SignalRecord> = recent
+                .iter()
+                .copied()
+                .filter(|record| record.action == action && record.symbol.eq_ignore_ascii_case(&symbol))
+                .collect();
+            if !of_asset.is_empty() {
+                rows.push(row(Some(symbol.clone()), action.clone(), &of_asset));
+            }
+        }
+    }
+
+    SignalScorecardData { window_days, rows }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use seyeon_trading_engine::data_point::DailySeries;
+    use std::collections::BTreeMap;
+
+    fn record(symbol: &str, action: TradeAction, day_emitted: i64, forward_returns: &[(u32, f64)]) -> SignalRecord {
+        SignalRecord {
+            symbol: symbol.to_string(),
+            action,
+            price: 100.0,
+            confidence: 0.5,
+            datetime: DailySeries::new().day(day_emitted),
+            forward_returns: forward_returns.iter().copied().collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn evaluate_fills_the_horizons_history_covers() {
+        let series = DailySeries::new();
+        let mut prices = vec![100.0; 31];
+        prices[1] = 110.0;
+        prices[7] = 90.0;
+        let mut signal = record("BTC", TradeAction::Buy, 0, &[]);
+
+        assert!(evaluate(&mut signal, &series.prices(&prices[..10]), series.day(10)));
+        assert_eq!(signal.forward_returns.len(), 2);
+        assert!((signal.forward_returns[&1] - 0.1).abs() < 1e-12);
+        assert!((signal.forward_returns[&7] + 0.1).abs() < 1e-12);
+
+        assert!(!evaluate(&mut signal, &series.prices(&prices[..10]), series.day(10)));
+        assert!(evaluate(&mut signal, &series.prices(&prices), series.day(31)));
+        assert_eq!(signal.forward_returns[&30], 0.0);
+    }
+
+    #[test]
+    fn evaluate_skips_the_partial_last_candle() {
+        let series = DailySeries::new();
+        let prices = [100.0, 105.0, 120.0];
+        let mut signal = record("BTC", TradeAction::Buy, 1, &[]);
+
+        // Day 2 is still trading at noon on day 2.
+        assert!(!evaluate(&mut signal, &series.prices(&prices), series.day(2) + Duration::hours(12)));
+        assert!(signal.forward_returns.is_empty());
+
+        assert!(evaluate(&mut signal, &series.prices(&prices), series.day(3)));
+        assert!((signal.forward_returns[&1] - (120.0 / 100.0 - 1.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn evaluate_uses_the_next_close_when_the_target_day_is_missing() {
+        let series = DailySeries::new();
+        let mut candles = series.prices(&[100.0, 101.0, 102.0, 103.0]);
+        candles.remove(1);
+        let mut signal = record("ETH", TradeAction::Sell, 0, &[]);
+
+        assert!(evaluate(&mut signal, &candles, series.day(4)));
+        assert!((signal.forward_returns[&1] - 0.02).abs() < 1e-12);
+    }
+
+    #[test]
+    fn scorecard_counts_hits_per_action_and_asset() {
+        let records = [
+            record("btc", TradeAction::Buy, 20, &[(1, 0.02), (7, -0.01)]),
+            record("BTC", TradeAction::Buy, 25, &[(1, -0.04)]),
+            record("ETH", TradeAction::Sell, 26, &[(1, -0.03)]),
+            // Outside the 30-day window.
+            record("ETH", TradeAction::Buy, 0, &[(1, 0.5)]),
+        ];
+
+        let card = scorecard(&records, 30, DailySeries::new().day(40));
+
+        let rows: Vec<(Option<&str>, &TradeAction)> =
+            card.rows.iter().map(|row| (row.symbol.as_deref(), &row.action)).collect();
+        assert_eq!(
+            rows,
+            vec![
+                (None, &TradeAction::Buy),
+                (None, &TradeAction::Sell),
+                (Some("BTC"), &TradeAction::Buy),
+                (Some("ETH"), &TradeAction::Sell),
+            ]
+        );
+
+        let buys = &card.rows[0];
+        assert_eq!(buys.signals, 2);
+        assert_eq!(buys.horizons[0].evaluated, 2);
+        assert_eq!(buys.horizons[0].hit_rate, Some(0.5));
+        assert!((buys.horizons[0].average_return.unwrap() + 0.01).abs() < 1e-12);
+        assert_eq!(buys.horizons[1].hit_rate, Some(0.0));
+        assert_eq!(buys.horizons[2].evaluated, 0);
+        assert_eq!(buys.horizons[2].hit_rate, None);
+
+        // A Sell is a hit when the price falls.
+        assert_eq!(card.rows[1].horizons[0].hit_rate, Some(1.0));
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::Result;
+use seyeon_redis::{PaperAccount, PaperTrade, TradeAction, get_paper_account, record_paper_trade, set_paper_account};
+use seyeon_trading_engine::engine::{Event, Params, Signal};
+
+/// A paper account valued at the latest close.
+#[derive(Debug, Clone)]
+pub struct PaperValuation {
+    pub symbol: String,
+    pub cash: f64,
+    pub quantity: f64,
+    pub average_cost: f64,
+    pub price: f64,
+    pub equity: f64,
+    /// Realized plus unrealized P&L since the account was opened.
+    pub pnl: f64,
+    pub pnl_pct: f64,
+    pub realized_pnl: f64,
+    pub fees_paid: f64,
+    pub trades: usize,
+}
+
+/// Executes `event` on the stored paper account of `symbol` (opening one with
+/// `params.initial_capital` on first use) and persists the result. Returns the account
+/// and the trade, if the signal led to one.
+pub async fn follow_signal(symbol: &str, event: &Event, params: &Params) -> Result<(PaperAccount, Option<PaperTrade>)> {
+    let mut account = get_paper_account(symbol)
+        .await?
+        .unwrap_or_else(|| PaperAccount::new(symbol, params.initial_capital));
+
+    let trade = execute(&mut account, event, params);
+    if let Some(trade) = &trade {
+        record_paper_trade(trade).await?;
+    }
+    set_paper_account(&account).await?;
+
+    Ok((account, trade))
+}
+
+/// Fills a Buy with `initial_investment_fraction` of the cash when flat and a Sell of the
+/// whole position when holding, at the event's close and paying `generic_fee`, the same
+/// way the backtest enters and exits. A bar is only traded once, so repeated polls of the
+/// same close do nothing.
+pub fn execute(account: &mut PaperAccount, event: &Event, params: &Params) -> Option<PaperTrade> {
+    if account.last_trade_at.is_some_and(|last| last >= event.datetime) || event.price <= 0.0 {
+        return None;
+    }
+
+    let trade = match event.signal {
+        Signal::Buy if account.quantity == 0.0 && account.cash > 50.0 => {
+            let notional = account.cash * params.initial_investment_fraction;
+            let fee = notional * params.generic_fee;
+            let quantity = (notional - fee) / event.price;
+
+            account.cash -= notional;
+            account.quantity = quantity;
+            account.average_cost = event.price;
+            account.invested = notional;
+
+            PaperTrade {
+                symbol: account.symbol.clone(),
+                action: TradeAction::Buy,
+                datetime: event.datetime,
+                price: event.price,
+                quantity,
+                notional,
+                fee,
+                realized_pnl: 0.0,
+            }
+        }
+        Signal::Sell if account.quantity > 0.0 => {
+            let notional = account.quantity * event.price;
+            let fee = notional * params.generic_fee;
+            let realized_pnl = notional - fee - account.invested;
+
+            let trade = PaperTrade {
+                symbol: account.symbol.clone(),
+                action: TradeAction::Sell,
+                datetime: event.datetime,
+                price: event.price,
+                quantity: account.quantity,
+                notional,
+                fee,
+                realized_pnl,
+            };
+
+            account.cash += notional - fee;
+            account.quantity = 0.0;
+            account.average_cost = 0.0;
+            account.invested = 0.0;
+            account.realized_pnl += realized_pnl;
+
+            trade
+        }
+        _ => return None,
+    };
+
+    account.fees_paid += trade.fee;
+    account.trades += 1;
+    account.last_trade_at = Some(event.datetime);
+
+    Some(trade)
+}
+
+pub fn valuate(account: &PaperAccount, price: f64) -> PaperValuation {
+    let equity = account.cash + account.quantity * price;
+    let pnl = equity - account.initial_capital;
+
+    PaperValuation {
+        symbol: account.symbol.clone(),
+        cash: account.cash,
+        quantity: account.quantity,
+        average_cost: account.average_cost,
+        price,
+        equity,
+        pnl,
+        pnl_pct: if account.initial_capital > 0.0 {
+            pnl / account.initial_capital * 100.0
+        } else {
+            0.0
+        },
+        realized_pnl: account.realized_pnl,
+        fees_paid: account.fees_paid,
+        trades: account.trades,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn event(day: i64, price: f64, signal: Signal) -> Event {
+        Event {
+            datetime: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap() + Duration::days(day),
+            price,
+            signal,
+            confidence: 1.0,
+        }
+    }
+
+    fn params() -> Params {
+        Params {
+            initial_capital: 1_000.0,
+            initial_investment_fraction: 0.5,
+            generic_fee: 0.01,
+            ..Params::default()
+        }
+    }
+
+    #[test]
+    fn buy_then_sell_nets_fees_and_realizes_pnl() {
+        let mut account = PaperAccount::new("ETH", 1_000.0);
+
+        let buy = execute(&mut account, &event(0, 100.0, Signal::Buy), &params()).unwrap();
+        assert_eq!(buy.notional, 500.0);
+        assert_eq!(buy.fee, 5.0);
+        assert!((buy.quantity - 4.95).abs() < 1e-12);
+        assert_eq!(account.cash, 500.0);
+        assert_eq!(account.average_cost, 100.0);
+
+        let sell = execute(&mut account, &event(1, 200.0, Signal::Sell), &params()).unwrap();
+        assert!((sell.notional - 990.0).abs() < 1e-9);
+        assert!((sell.fee - 9.9).abs() < 1e-9);
+        assert!((sell.realized_pnl - 480.1).abs() < 1e-9);
+        assert!((account.cash - 1_480.1).abs() < 1e-9);
+        assert_eq!(account.quantity, 0.0);
+        assert!((account.fees_paid - 14.9).abs() < 1e-9);
+        assert_eq!(account.trades, 2);
+    }
+
+    #[test]
+    fn ignores_repeated_bars_and_redundant_signals() {
+        let mut account = PaperAccount::new("ETH", 1_000.0);
+
+        assert!(execute(&mut account, &event(0, 100.0, Signal::Sell), &params()).is_none());
+        assert!(execute(&mut account, &event(0, 100.0, Signal::Hold), &params()).is_none());
+        assert!(execute(&mut account, &event(0, 100.0, Signal::Buy), &params()).is_some());
+        // Same close polled again, then a second Buy while already holding.
+        assert!(execute(&mut account, &event(0, 100.0, Signal::Sell), &params()).is_none());
+        assert!(execute(&mut account, &event(1, 110.0, Signal::Buy), &params()).is_none());
+        assert_eq!(account.trades, 1);
+    }
+
+    #[test]
+    fn valuate_marks_the_position_to_market() {
+        let mut account = PaperAccount::new("ETH", 1_000.0);
+        execute(&mut account, &event(0, 100.0, Signal::Buy), &params());
+
+        let valuation = valuate(&account, 120.0);
+
+        assert!((valuation.equity - (500.0 + 4.95 * 120.0)).abs() < 1e-9);
+        assert!((valuation.pnl - 94.0).abs() < 1e-9);
+        assert!((valuation.pnl_pct - 9.4).abs() < 1e-9);
+        assert_eq!(valuation.fees_paid, 5.0);
+    }
+}
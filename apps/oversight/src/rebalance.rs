@@ -0,0 +1,261 @@
+use crate::holdings::PositionValuation;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use seyeon_redis::TradeAction;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RebalanceMode {
+    /// Rebalance as soon as any asset drifts more than `band` from its target weight.
+    Threshold,
+    /// Rebalance every `interval_days`, whatever the drift.
+    Calendar,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RebalanceConfig {
+    pub mode: RebalanceMode,
+    /// Absolute weight drift tolerated in threshold mode (0.05 = 5 percentage points).
+    pub band: f64,
+    pub interval_days: i64,
+    /// Trades below this notional (USD) are not worth their fees and are dropped.
+    pub min_trade_usd: f64,
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            mode: RebalanceMode::Threshold,
+            band: 0.05,
+            interval_days: 30,
+            min_trade_usd: 25.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RebalanceTrade {
+    pub symbol: String,
+    pub action: TradeAction,
+    pub current_weight: f64,
+    pub target_weight: f64,
+    pub notional: f64,
+    pub quantity: f64,
+    pub estimated_fee: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RebalancePlan {
+    pub reason: String,
+    pub trades: Vec<RebalanceTrade>,
+}
+
+/// Proposes the trades that bring the targeted holdings back to their target weights.
+///
+/// Only symbols listed in `targets` take part: their weights are normalized to sum to 1
+/// and compared with the share of each one in the value of the targeted holdings.
+/// `last_rebalance` is used by the calendar mode. Returns `None` when no rebalance is due.
+pub fn plan_rebalance(
+    positions: &[PositionValuation],
+    targets: &HashMap<String, f64>,
+    latest_prices: &HashMap<String, f64>,
+    config: &RebalanceConfig,
+    fee: f64,
+    today: NaiveDate,
+    last_rebalance: Option<NaiveDate>,
+) -> Option<RebalancePlan> {
+    let total_target: f64 = targets.values().filter(|w| **w > 0.0).sum();
+    if total_target <= 0.0 {
+        return None;
+    }
+
+    let value_of = |symbol: &str| {
+        positions
+            .iter()
+            .find(|p| p.symbol.eq_ignore_ascii_case(symbol))
+            .map_or(0.0, |p| p.market_value)
+    };
+    let total_value: f64 = targets.keys().map(|symbol| value_of(symbol)).sum();
+    if total_value <= 0.0 {
+        return None;
+    }
+
+    let mut symbols: Vec<&String> = targets.keys().collect();
+    symbols.sort();
+
+    let drifts: Vec<(&String, f64, f64)> = symbols
+        .into_iter()
+        .map(|symbol| {
+            let current = value_of(symbol) / total_value;
+            let target = targets[symbol].max(0.0) / total_target;
+            (symbol, current, target)
+        })
+        .collect();
+
+    let reason = match config.mode {
+        RebalanceMode::Threshold => {
+            let (symbol, current, target) = drifts
+                .iter()
+                .max_by(|a, b| (a.1 - a.2).abs().total_cmp(&(b.1 - b.2).abs()))?;
+
+            if (current - target).abs() <= config.band {
+                return None;
+            }
+
+            format!(
+                "{} is {:.2}% from its target weight (band: ±{:.2}%)",
+                symbol,
+                (current - target) * 100.0,
+                config.band * 100.0
+            )
+        }
+        RebalanceMode::Calendar => {
+            if let Some(last) = last_rebalance {
+                let elapsed = (today - last).num_days();
+                if elapsed < config.interval_days {
+                    return None;
+                }
+            }
+
+            format!("Scheduled rebalance (every {} days)", config.interval_days)
+        }
+    };
+
+    let trades = drifts
+        .into_iter()
+        .filter_map(|(symbol, current, target)| {
+            let notional = (target - current) * total_value;
+            if notional.abs() < config.min_trade_usd {
+                return None;
+            }
+
+            let Some(&price) = latest_prices.get(&symbol.to_uppercase()) else {
+                eprintln!("No price available for {}, cannot size its rebalance trade", symbol);
+                return None;
+            };
+
+            let estimated_fee = notional.abs() * fee;
+            let (action, quantity) = if notional > 0.0 {
+                (TradeAction::Buy, (notional - estimated_fee) / price)
+            } else {
+                (TradeAction::Sell, notional.abs() / price)
+            };
+
+            Some(RebalanceTrade {
+                symbol: symbol.to_uppercase(),
+                action,
+                current_weight: current,
+                target_weight: target,
+                notional: notional.abs(),
+                quantity,
+                estimated_fee,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if trades.is_empty() {
+        return None;
+    }
+
+    Some(RebalancePlan { reason, trades })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(symbol: &str, market_value: f64) -> PositionValuation {
+        PositionValuation {
+            symbol: symbol.to_string(),
+            quantity: market_value / 100.0,
+            average_cost: 100.0,
+            price: 100.0,
+            market_value,
+            unrealized_pnl: 0.0,
+            unrealized_pnl_pct: 0.0,
+            allocation: 0.0,
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, day).unwrap()
+    }
+
+    fn targets() -> HashMap<String, f64> {
+        HashMap::from([("BTC".to_string(), 1.0), ("ETH".to_string(), 1.0)])
+    }
+
+    fn prices() -> HashMap<String, f64> {
+        HashMap::from([("BTC".to_string(), 50_000.0), ("ETH".to_string(), 2_000.0)])
+    }
+
+    #[test]
+    fn threshold_mode_waits_for_the_band() {
+        let config = RebalanceConfig::default();
+
+        let within = [position("BTC", 5_400.0), position("ETH", 4_600.0)];
+        assert!(plan_rebalance(&within, &targets(), &prices(), &config, 0.0, date(1), None).is_none());
+
+        let drifted = [position("BTC", 6_000.0), position("ETH", 4_000.0)];
+        let plan = plan_rebalance(&drifted, &targets(), &prices(), &config, 0.0, date(1), None).unwrap();
+
+        assert_eq!(plan.trades.len(), 2);
+        assert_eq!(plan.trades[0].symbol, "BTC");
+        assert_eq!(plan.trades[0].action, TradeAction::Sell);
+        assert!((plan.trades[0].notional - 1_000.0).abs() < 1e-9);
+        assert!((plan.trades[0].quantity - 0.02).abs() < 1e-12);
+        assert_eq!(plan.trades[1].action, TradeAction::Buy);
+    }
+
+    #[test]
+    fn calendar_mode_waits_for_the_interval() {
+        let config = RebalanceConfig {
+            mode: RebalanceMode::Calendar,
+            interval_days: 30,
+            ..RebalanceConfig::default()
+        };
+        let positions = [position("BTC", 5_100.0), position("ETH", 4_900.0)];
+        let plan = |today, last| plan_rebalance(&positions, &targets(), &prices(), &config, 0.0, today, last);
+
+        assert!(plan(date(30), Some(date(1))).is_none());
+        assert!(plan(date(1), None).is_some());
+
+        let due = plan(NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(), Some(date(1))).unwrap();
+        assert_eq!(due.reason, "Scheduled rebalance (every 30 days)");
+        assert_eq!(due.trades.len(), 2);
+    }
+
+    #[test]
+    fn buys_are_sized_net_of_fees() {
+        let positions = [position("BTC", 6_000.0), position("ETH", 4_000.0)];
+        let plan =
+            plan_rebalance(&positions, &targets(), &prices(), &RebalanceConfig::default(), 0.01, date(1), None)
+                .unwrap();
+        let buy = plan.trades.iter().find(|t| t.symbol == "ETH").unwrap();
+        let sell = plan.trades.iter().find(|t| t.symbol == "BTC").unwrap();
+
+        assert!((buy.estimated_fee - 10.0).abs() < 1e-9);
+        assert!((buy.quantity - 990.0 / 2_000.0).abs() < 1e-12);
+        assert!((sell.estimated_fee - 10.0).abs() < 1e-9);
+        assert!((sell.quantity - 1_000.0 / 50_000.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn trades_without_a_price_or_below_the_minimum_are_dropped() {
+        let positions = [position("BTC", 6_000.0), position("ETH", 4_000.0)];
+        let only_btc = HashMap::from([("BTC".to_string(), 50_000.0)]);
+
+        let plan = plan_rebalance(&positions, &targets(), &only_btc, &RebalanceConfig::default(), 0.0, date(1), None)
+            .unwrap();
+        assert_eq!(plan.trades.len(), 1);
+        assert_eq!(plan.trades[0].symbol, "BTC");
+
+        let config = RebalanceConfig {
+            min_trade_usd: 2_000.0,
+            ..RebalanceConfig::default()
+        };
+        assert!(plan_rebalance(&positions, &targets(), &prices(), &config, 0.0, date(1), None).is_none());
+    }
+}
@@ -0,0 +1,81 @@
+use chrono::{Duration, Utc};
+use oversight::cache::{self, CacheConfig};
+use oversight::data_fetcher::{fetch_fgi, fetch_historical_data, Freshness};
+use seyeon_email::{EmailConfig, StaleData};
+use seyeon_mock_api::MockApi;
+use seyeon_redis::{CryptoStatus, TradeAction};
+use seyeon_trading_engine::data_point::DailySeries;
+use seyeon_trading_engine::engine::{Params, Signal, TradingEngine};
+use seyeon_trading_engine::indicators::Indicators;
+use std::env;
+
+/// One test, since the providers, key pools and cache are configured through the
+/// environment and a process-wide cache configuration.
+#[tokio::test]
+async fn fetch_layer_feeds_the_engine_and_the_alerts() {
+    let api = MockApi::with_fixtures().await;
+    let dir = env::temp_dir().join(format!("seyeon-pipeline-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    env::set_var("CRYPTOCOMPARE_BASE_URL", api.url());
+    env::set_var("RAPIDAPI_BASE_URL", api.url());
+    env::set_var("COINLORE_BASE_URL", api.coinlore_url());
+    env::set_var("CRYPTOCOMPARE_API_KEY", "key-a,key-b");
+    env::set_var("RAPIDAPI_KEY", "key-a");
+    env::set_var("MARKET_DATA_PROVIDERS", "cryptocompare");
+    env::set_var("HTTP_MAX_RETRIES", "0");
+    // Nothing listens there, so the key pools fall back to picking keys at random.
+    env::set_var("REDIS_URL", "redis://127.0.0.1:1/");
+    // Every cache is past its TTL, so each fetch updates it.
+    cache::configure(CacheConfig { dir: dir.clone(), ttl: Duration::zero(), ..Default::default() });
+
+    // The three days before the first candle of the BTC histoday fixture (2024-03-27).
+    let seeded = DailySeries::new().offset(-283).prices(&[68_000.0, 68_500.0, 69_000.0]);
+    cache::save("BTC", &seeded).unwrap();
+
+    let fetched = fetch_historical_data("BTC".to_string(), 1_000).await.unwrap();
+    assert_eq!(fetched.freshness, Freshness::Fresh);
+    assert!(fetched.in_cache);
+    // The 400 fixture candles are merged after the cached ones.
+    assert_eq!(fetched.historical.len(), 403);
+    assert_eq!(fetched.historical[0].datetime, seeded[0].datetime);
+
+    // The update resumes at the last cached candle instead of fetching the whole window.
+    let request = api.requests().into_iter().find(|request| request.path == "/data/v2/histoday").unwrap();
+    let limit: i64 = request.param("limit").unwrap().parse().unwrap();
+    assert!((limit - (Utc::now() - seeded[2].datetime).num_days()).abs() <= 1);
+
+    // Every key rate limited: the cache is used as stale.
+    api.route("/data/v2/histoday", &[("fsym", "BTC")], 429, "{}");
+    let fetched = fetch_historical_data("BTC".to_string(), 1_000).await.unwrap();
+    assert!(matches!(fetched.freshness, Freshness::Stale(age) if age < Duration::minutes(5)));
+    assert!(fetched.in_cache);
+    assert_eq!(fetched.historical.len(), 403);
+
+    let fgi = fetch_fgi().await.expect("FGI from the mock API");
+    let df = Indicators::from_frame(cache::scan("BTC").expect("Parquet cache of BTC"))
+        .calculate()
+        .unwrap();
+    assert_eq!(df.height(), 403);
+
+    let event = TradingEngine::new("BTC".to_string(), df, Some(fgi.fgi.now.value as u8), Params::default())
+        .unwrap()
+        .poll_event()
+        .unwrap();
+    let status = CryptoStatus {
+        symbol: "BTC".to_string(),
+        action: match event.signal {
+            Signal::Buy => TradeAction::Buy,
+            Signal::Sell => TradeAction::Sell,
+            Signal::Hold => TradeAction::Hold,
+        },
+        sent: false,
+    };
+    let stale = StaleData { symbol: "BTC".to_string(), freshness: fetched.freshness.to_string() };
+    let alert = EmailConfig::signal_alert_body(&status, Some(&stale));
+
+    assert!(alert.subject.contains("BTC"));
+    assert!(alert.plain.contains("Stale data"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use reqwest;
+use crate::CoinloreClient;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GlobalMarketData {
@@ -16,15 +16,10 @@ pub struct GlobalMarketData {
     pub mcap_ath: f64,
 }
 
-pub const GLOBAL_MARKET_ENDPOINT: &str = "https://api.coinlore.net/api/global/";
-
 /// Fetch global cryptocurrency market data from the Coinlore API
 pub async fn get_global_data() -> Result<GlobalMarketData, reqwest::Error> {
-    let client = reqwest::Client::new();
-    let response = client.get(GLOBAL_MARKET_ENDPOINT).send().await?;
-    
     // The API returns an array with a single object
-    let mut data: Vec<GlobalMarketData> = response.json().await?;
+    let mut data = CoinloreClient::new().get_global_market_data().await?;
     
     // Return the first (and only) item in the array
     Ok(data.remove(0))
@@ -5,29 +5,46 @@ pub mod markets;
 pub mod exchanges;
 pub mod social_stats;
 
-use reqwest::{Client, ClientBuilder};
+use reqwest::header::HeaderMap;
 use serde::de::DeserializeOwned;
+use seyeon_http::{HttpClient, base_url_from_env};
 use std::collections::HashMap;
 
 // Base URL for Coinlore API
-pub const BASE_URL: &str = "https://api.coinlore.net/api";
+pub const DEFAULT_BASE_URL: &str = "https://api.coinlore.net/api";
+
+#[deprecated(note = "use `DEFAULT_BASE_URL`, or `CoinloreClient::with_base_url` for another host")]
+pub const BASE_URL: &str = DEFAULT_BASE_URL;
 
 pub struct CoinloreClient {
-    reqwest: Client,
+    http: HttpClient,
+    base_url: String,
+}
+
+impl Default for CoinloreClient {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CoinloreClient {
+    /// A client of `DEFAULT_BASE_URL`, or of `COINLORE_BASE_URL` when set (e.g. a proxy or
+    /// a caching gateway).
     pub fn new() -> Self {
-        let reqwest = ClientBuilder::new()
-            .build()
-            .expect("Failed to build reqwest client");
+        Self::with_base_url(&base_url_from_env("COINLORE_BASE_URL", DEFAULT_BASE_URL))
+    }
 
-        Self { reqwest }
+    /// A client sending its requests to `base_url` instead of the public API.
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            http: HttpClient::new(HeaderMap::new()),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
     }
 
     // Global market data endpoint
     pub async fn get_global_market_data(&self) -> Result<Vec<global_market::GlobalMarketData>, reqwest::Error> {
-        self.get(&format!("{}/global/", BASE_URL)).await
+        self.get(&format!("{}/global/", self.base_url)).await
     }
 
     // Tickers endpoint (all coins with pagination)
@@ -42,7 +59,7 @@ impl CoinloreClient {
             params.insert(String::from("limit"), limit_val.to_string());
         }
         
-        self.get_with_params(&format!("{}/tickers/", BASE_URL), &params).await
+        self.get_with_params(&format!("{}/tickers/", self.base_url), &params).await
     }
     
     // Ticker endpoint (specific coin(s))
@@ -51,7 +68,7 @@ impl CoinloreClient {
         let mut params = HashMap::new();
         params.insert(String::from("id"), id_param);
         
-        self.get_with_params(&format!("{}/ticker/", BASE_URL), &params).await
+        self.get_with_params(&format!("{}/ticker/", self.base_url), &params).await
     }
     
     // Markets for a specific coin
@@ -59,12 +76,12 @@ impl CoinloreClient {
         let mut params = HashMap::new();
         params.insert(String::from("id"), coin_id.to_string());
         
-        self.get_with_params(&format!("{}/coin/markets/", BASE_URL), &params).await
+        self.get_with_params(&format!("{}/coin/markets/", self.base_url), &params).await
     }
     
     // All exchanges
     pub async fn get_exchanges(&self) -> Result<exchanges::ExchangesResponse, reqwest::Error> {
-        self.get(&format!("{}/exchanges/", BASE_URL)).await
+        self.get(&format!("{}/exchanges/", self.base_url)).await
     }
     
     // Specific exchange by ID
@@ -72,7 +89,7 @@ impl CoinloreClient {
         let mut params = HashMap::new();
         params.insert(String::from("id"), exchange_id.to_string());
         
-        self.get_with_params(&format!("{}/exchange/", BASE_URL), &params).await
+        self.get_with_params(&format!("{}/exchange/", self.base_url), &params).await
     }
     
     // Social stats for a coin
@@ -80,23 +97,17 @@ impl CoinloreClient {
         let mut params = HashMap::new();
         params.insert(String::from("id"), coin_id.to_string());
         
-        self.get_with_params(&format!("{}/coin/social_stats/", BASE_URL), &params).await
+        self.get_with_params(&format!("{}/coin/social_stats/", self.base_url), &params).await
     }
 
     // Generic GET request
     async fn get<R: DeserializeOwned>(&self, url: &str) -> Result<R, reqwest::Error> {
-        let response = self.reqwest.get(url).send().await?;
-        let response = response.error_for_status()?;
-        
-        response.json().await
+        self.http.get_json(url, &()).await
     }
     
     // GET request with query parameters
     async fn get_with_params<R: DeserializeOwned>(&self, url: &str, params: &HashMap<String, String>) -> Result<R, reqwest::Error> {
-        let response = self.reqwest.get(url).query(params).send().await?;
-        let response = response.error_for_status()?;
-        
-        response.json().await
+        self.http.get_json(url, params).await
     }
 }
 
@@ -105,6 +116,7 @@ mod tests {
     use super::*;
     
     #[tokio::test]
+    #[ignore = "calls the live Coinlore API"]
     async fn test_get_global_market_data() {
         let client = CoinloreClient::new();
         let data = client.get_global_market_data().await.expect("Failed to get global market data");
@@ -114,6 +126,7 @@ mod tests {
     }
     
     #[tokio::test]
+    #[ignore = "calls the live Coinlore API"]
     async fn test_get_tickers() {
         let client = CoinloreClient::new();
         let tickers = client.get_tickers(Some(0), Some(10)).await.expect("Failed to get tickers");
@@ -124,6 +137,7 @@ mod tests {
     }
     
     #[tokio::test]
+    #[ignore = "calls the live Coinlore API"]
     async fn test_get_ticker() {
         let client = CoinloreClient::new();
         let btc = client.get_ticker(&["90"]).await.expect("Failed to get BTC ticker");
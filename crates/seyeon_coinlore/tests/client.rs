@@ -0,0 +1,48 @@
+use seyeon_coinlore::CoinloreClient;
+use seyeon_mock_api::MockApi;
+
+#[tokio::test]
+async fn global_market_data_from_mock_api() {
+    let api = MockApi::with_fixtures().await;
+    let client = CoinloreClient::with_base_url(&api.coinlore_url());
+
+    let data = client.get_global_market_data().await.expect("Failed to get global market data");
+
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].btc_d, "62.49");
+    assert_eq!(api.requests()[0].path, "/api/global/");
+}
+
+#[tokio::test]
+async fn tickers_from_mock_api() {
+    let api = MockApi::with_fixtures().await;
+    let client = CoinloreClient::with_base_url(&api.coinlore_url());
+
+    let tickers = client.get_tickers(Some(0), Some(2)).await.expect("Failed to get tickers");
+
+    assert_eq!(tickers.data.len(), 2);
+    assert_eq!(tickers.data[0].symbol, "BTC");
+    assert_eq!(api.requests()[0].param("limit"), Some("2"));
+}
+
+#[tokio::test]
+async fn unknown_endpoints_are_not_found() {
+    let api = MockApi::start().await;
+    let client = CoinloreClient::with_base_url(&api.coinlore_url());
+
+    let error = client.get_global_market_data().await.unwrap_err();
+
+    assert_eq!(error.status(), Some(reqwest::StatusCode::NOT_FOUND));
+}
+
+#[tokio::test]
+async fn new_uses_the_base_url_of_the_environment() {
+    let api = MockApi::with_fixtures().await;
+    // No other test of this binary reads COINLORE_BASE_URL.
+    unsafe { std::env::set_var("COINLORE_BASE_URL", api.coinlore_url()) };
+
+    let data = CoinloreClient::new().get_global_market_data().await.expect("Failed to get global market data");
+
+    assert_eq!(data[0].btc_d, "62.49");
+    assert_eq!(api.requests()[0].path, "/api/global/");
+}
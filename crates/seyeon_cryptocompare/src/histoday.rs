@@ -12,7 +12,7 @@ pub struct HistodayParams {
     pub target_sym: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "toTs", skip_serializing_if = "Option::is_none")]
     pub to_ts: Option<u32>,
 }
 
@@ -49,6 +49,8 @@ pub struct RateLimit {
     pub calls_made: Option<CallsInfo>,
     #[serde(rename = "calls_left", default)]
     pub calls_left: Option<CallsInfo>,
+    #[serde(rename = "max_calls", default)]
+    pub max_calls: Option<CallsInfo>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -95,7 +97,7 @@ pub struct CryptoCompareHistodayEntry {
 pub struct Histoday;
 
 impl Method for Histoday {
-    const PATH: &'static str = "https://min-api.cryptocompare.com/data/v2/histoday";
+    const PATH: &'static str = "/data/v2/histoday";
 
     type Response = CryptoCompareHistodayResponse;
     type Params = HistodayParams;
@@ -2,16 +2,27 @@ pub mod histoday;
 pub mod method;
 
 use reqwest::header::{HeaderMap, HeaderValue};
-use reqwest::{Client, ClientBuilder};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use seyeon_http::{HttpClient, base_url_from_env};
+
+/// Public CryptoCompare API, prepended to the `Method::PATH`s.
+pub const DEFAULT_BASE_URL: &str = "https://min-api.cryptocompare.com";
 
 pub struct CryptocompareClient {
-    reqwest: Client,
+    http: HttpClient,
+    base_url: String,
 }
 
 impl CryptocompareClient {
+    /// A client of `DEFAULT_BASE_URL`, or of `CRYPTOCOMPARE_BASE_URL` when set (e.g. a proxy or a
+    /// caching gateway).
     pub fn new(api_key: &str) -> Self {
+        Self::with_base_url(api_key, &base_url_from_env("CRYPTOCOMPARE_BASE_URL", DEFAULT_BASE_URL))
+    }
+
+    /// A client sending its requests to `base_url` instead of the public API.
+    pub fn with_base_url(api_key: &str, base_url: &str) -> Self {
         let mut headers = HeaderMap::new();
         headers.insert(
             "Authorization",
@@ -19,12 +30,10 @@ impl CryptocompareClient {
                 .expect("Failed to create header value"),
         );
 
-        let reqwest = ClientBuilder::new()
-            .default_headers(headers)
-            .build()
-            .expect("Failed to build reqwest client");
-
-        Self { reqwest }
+        Self {
+            http: HttpClient::new(headers),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
     }
 
     pub(crate) async fn get<T: DeserializeOwned, P: Serialize + ?Sized>(
@@ -32,20 +41,10 @@ impl CryptocompareClient {
         url: &str,
         params: &P,
     ) -> reqwest::Result<T> {
-        let response = self
-            .reqwest
-            .get(url)
-            .query(params)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<T>()
-            .await?;
-
-        Ok(response)
+        self.http.get_json(url, params).await
     }
 
     pub async fn call<M: method::Method>(&self, params: M::Params) -> reqwest::Result<M::Response> {
-        self.get(M::PATH, &params).await
+        self.get(&format!("{}{}", self.base_url, M::PATH), &params).await
     }
 }
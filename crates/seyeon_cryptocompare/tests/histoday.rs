@@ -2,6 +2,7 @@ use seyeon_cryptocompare::histoday::{Histoday, HistodayParams};
 use tokio::test;
 
 #[test]
+#[ignore = "calls the live CryptoCompare API with $CRYPTOCOMPARE_API_KEY"]
 pub async fn fetch_histoday() {
     let client = seyeon_cryptocompare::CryptocompareClient::new(
         std::env::var("CRYPTOCOMPARE_API_KEY")
@@ -22,3 +23,74 @@ pub async fn fetch_histoday() {
 
     println!("{response:?}");
 }
+
+#[test]
+pub async fn histoday_from_mock_api() {
+    let api = seyeon_mock_api::MockApi::with_fixtures().await;
+    let client = seyeon_cryptocompare::CryptocompareClient::with_base_url("test-key", api.url());
+
+    let response = client
+        .call::<Histoday>(
+            HistodayParams::builder()
+                .source_sym("BTC")
+                .target_sym("USD")
+                .limit(399)
+                .to_ts(1_746_000_000)
+                .build(),
+        )
+        .await
+        .expect("Failed to fetch histoday");
+
+    let data = response.data.expect("Histoday without data");
+    assert_eq!(response.response, "Success");
+    assert_eq!(data.data.len(), 400);
+    assert!(data.data.windows(2).all(|pair| pair[1].time - pair[0].time == 86_400));
+
+    let request = &api.requests()[0];
+    assert_eq!(request.path, "/data/v2/histoday");
+    assert_eq!(request.param("fsym"), Some("BTC"));
+    assert_eq!(request.param("tsym"), Some("USD"));
+    assert_eq!(request.param("limit"), Some("399"));
+    assert_eq!(request.param("toTs"), Some("1746000000"));
+    assert_eq!(request.header("Authorization"), Some("Apikey test-key"));
+}
+
+#[test]
+pub async fn histoday_rate_limit_error() {
+    let api = seyeon_mock_api::MockApi::start().await;
+    api.route(
+        "/data/v2/histoday",
+        &[],
+        200,
+        seyeon_mock_api::fixture("cryptocompare/histoday_rate_limit.json"),
+    );
+    let client = seyeon_cryptocompare::CryptocompareClient::with_base_url("test-key", api.url());
+
+    let response = client
+        .call::<Histoday>(HistodayParams::builder().source_sym("BTC").target_sym("USD").build())
+        .await
+        .expect("Failed to decode the rate limit error");
+
+    assert_eq!(response.response, "Error");
+    assert!(response.data.is_none());
+    let calls_left = response.rate_limit.and_then(|limit| limit.calls_left).unwrap_or_default();
+    assert_eq!(calls_left.month, Some(0));
+}
+
+#[test]
+pub async fn new_uses_the_base_url_of_the_environment() {
+    let api = seyeon_mock_api::MockApi::with_fixtures().await;
+    // Only read by `fetch_histoday` otherwise, which is ignored by default; removed below
+    // so that running it alongside still reaches the live API.
+    unsafe { std::env::set_var("CRYPTOCOMPARE_BASE_URL", api.url()) };
+    let client = seyeon_cryptocompare::CryptocompareClient::new("test-key");
+    unsafe { std::env::remove_var("CRYPTOCOMPARE_BASE_URL") };
+
+    let response = client
+        .call::<Histoday>(HistodayParams::builder().source_sym("ETH").target_sym("USD").build())
+        .await
+        .expect("Failed to fetch histoday");
+
+    assert_eq!(response.response, "Success");
+    assert_eq!(api.requests()[0].param("fsym"), Some("ETH"));
+}
@@ -1,7 +1,7 @@
 use lettre::message::{Mailbox, MultiPart, SinglePart};
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
-use seyeon_redis::{CryptoStatus, TradeAction};
+use seyeon_redis::{CryptoStatus, PaperTrade, PendingApproval, TradeAction};
 use seyeon_coinlore::global_market::GlobalMarketData;
 use std::env;
 use std::str::FromStr;
@@ -21,6 +21,174 @@ pub struct FearAndGreedData {
     pub timestamp: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct CorrelationTable {
+    pub window_days: usize,
+    pub observations: usize,
+    pub method: String,
+    pub average: Option<f64>,
+    pub matrix: DataFrame,
+}
+
+#[derive(Debug, Clone)]
+pub struct CorrelationWarning {
+    pub window_days: usize,
+    pub average: f64,
+    pub limit: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValueAtRisk {
+    pub confidence: f64,
+    pub historical_var: f64,
+    pub historical_cvar: f64,
+    pub parametric_var: f64,
+    pub parametric_cvar: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AssetRiskData {
+    pub symbol: String,
+    pub weight: f64,
+    pub annualized_volatility: f64,
+    pub marginal_contribution: f64,
+    pub risk_contribution: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RiskData {
+    pub window_days: usize,
+    pub observations: usize,
+    /// Assets without enough history in the window, left out of the analysis.
+    pub excluded: Vec<String>,
+    pub portfolio_volatility: f64,
+    pub var: Vec<ValueAtRisk>,
+    pub assets: Vec<AssetRiskData>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PositionData {
+    pub symbol: String,
+    pub quantity: f64,
+    pub average_cost: f64,
+    pub price: f64,
+    pub market_value: f64,
+    pub unrealized_pnl: f64,
+    pub unrealized_pnl_pct: f64,
+    pub allocation: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RebalanceTradeData {
+    pub symbol: String,
+    pub action: TradeAction,
+    pub current_weight: f64,
+    pub target_weight: f64,
+    pub notional: f64,
+    pub quantity: f64,
+    pub estimated_fee: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RebalanceData {
+    pub reason: String,
+    pub trades: Vec<RebalanceTradeData>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PaperAccountData {
+    pub symbol: String,
+    pub cash: f64,
+    pub quantity: f64,
+    pub average_cost: f64,
+    pub price: f64,
+    pub equity: f64,
+    pub pnl: f64,
+    pub pnl_pct: f64,
+    pub realized_pnl: f64,
+    pub fees_paid: f64,
+    pub trades: usize,
+    /// Backtest ROI of the same asset over the report's simulation window.
+    pub simulated_roi: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PaperTradingData {
+    pub accounts: Vec<PaperAccountData>,
+    /// Most recent ledger entries, newest first.
+    pub recent_trades: Vec<PaperTrade>,
+}
+
+/// Outcome of the signals of a row after `days` days.
+#[derive(Debug, Clone)]
+pub struct HorizonStats {
+    pub days: u32,
+    /// Signals old enough to be evaluated at this horizon.
+    pub evaluated: usize,
+    /// Share of evaluated signals the price moved in favour of (up after a Buy, down after a Sell).
+    pub hit_rate: Option<f64>,
+    pub average_return: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScorecardRow {
+    /// `None` for the row covering every asset.
+    pub symbol: Option<String>,
+    pub action: TradeAction,
+    pub signals: usize,
+    pub average_confidence: f64,
+    pub horizons: Vec<HorizonStats>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SignalScorecardData {
+    pub window_days: i64,
+    pub rows: Vec<ScorecardRow>,
+}
+
+fn format_horizon(stats: &HorizonStats) -> String {
+    match (stats.hit_rate, stats.average_return) {
+        (Some(hit_rate), Some(average_return)) => format!(
+            "{:.0}% hit, {:+.2}% avg (n={})",
+            hit_rate * 100.0, average_return * 100.0, stats.evaluated
+        ),
+        _ => "-".to_string(),
+    }
+}
+
+/// An asset whose signal was computed on price history that could not be updated.
+#[derive(Debug, Clone)]
+pub struct StaleData {
+    pub symbol: String,
+    /// How old the history is, e.g. "stale (3 days old)".
+    pub freshness: String,
+}
+
+/// Everything rendered by `EmailConfig::send_daily_report`; sections left empty are omitted.
+#[derive(Debug, Default)]
+pub struct DailyReport {
+    pub signals: Vec<(String, TradeAction)>,
+    pub positions: Vec<PositionData>,
+    pub rebalance: Option<RebalanceData>,
+    pub paper: Option<PaperTradingData>,
+    pub scorecard: Option<SignalScorecardData>,
+    pub stale_data: Vec<StaleData>,
+    pub correlations: Vec<CorrelationTable>,
+    pub correlation_warnings: Vec<CorrelationWarning>,
+    pub performance: Option<Vec<AssetPerformance>>,
+    pub risk: Option<RiskData>,
+    pub fgi: Option<FearAndGreedData>,
+    pub global_market: Option<GlobalMarketData>,
+}
+
+/// Subject and plain text and HTML versions of an email, built before it is sent.
+#[derive(Debug, Clone)]
+pub struct EmailBody {
+    pub subject: String,
+    pub plain: String,
+    pub html: String,
+}
+
 pub struct EmailConfig {
     from_email: String,
     to_email: String,
@@ -53,11 +221,28 @@ impl EmailConfig {
         })
     }
 
+    /// Alerts about a signal change. `stale` is set when the signal was computed on price
+    /// history that could not be updated.
     pub async fn report_sender(
         &self,
-        crypto_status: &CryptoStatus
+        crypto_status: &CryptoStatus,
+        stale: Option<&StaleData>,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        self.send(Self::signal_alert_body(crypto_status, stale))?;
+        println!(
+            "\nStatus report sent by email to {} and {} CCs!",
+            self.to_email,
+            self.cc_emails.len()
+        );
+
+        Ok(())
+    }
+
+    /// The email of `report_sender`.
+    pub fn signal_alert_body(crypto_status: &CryptoStatus, stale: Option<&StaleData>) -> EmailBody {
         let now = Local::now().format("%d/%m/%Y %H:%M:%S").to_string();
+        let stale_note = stale
+            .map(|data| format!("Computed on {} price data of {}, the latest prices could not be fetched.", data.freshness, data.symbol));
         
         let html_body = format!(r#"
         <!DOCTYPE html>
@@ -131,6 +316,12 @@ impl EmailConfig {
                     font-size: 12px;
                     margin-bottom: 10px;
                 }}
+                .warning {{
+                    background-color: rgba(231, 76, 60, 0.1);
+                    border-left: 4px solid #e74c3c;
+                    padding: 10px;
+                    margin: 10px 0;
+                }}
             </style>
         </head>
         <body>
@@ -150,6 +341,7 @@ impl EmailConfig {
                         <td class="{}">{:?}</td>
                     </tr>
                 </table>
+                {}
             </div>
             <div class="footer">
                 © 2025 Seyeon Oversight - Cryptocurrency Monitoring System<br>
@@ -159,53 +351,163 @@ impl EmailConfig {
         </html>
         "#, crypto_status.symbol, 
         crypto_status.action.to_string().to_lowercase(), 
-        crypto_status.action);
-
-        let mut builder = Message::builder()
-            .from(self.from_email.parse()?)
-            .to(self.to_email.parse()?);
+        crypto_status.action,
+        stale_note.as_ref().map_or(String::new(), |note| format!(r#"<p class="warning"><strong>Stale data:</strong> {}</p>"#, note)));
 
-        for cc_email in &self.cc_emails {
-            builder = builder.cc(Mailbox::from_str(cc_email)?);
+        EmailBody {
+            subject: format!("🚨 Seyeon Alert: New signal for {}!", crypto_status.symbol),
+            plain: format!("New signal detected for {}:\n\n{:?}{}",
+                crypto_status.symbol, crypto_status.action,
+                stale_note.as_ref().map_or(String::new(), |note| format!("\n\nStale data: {}", note))),
+            html: html_body,
         }
+    }
 
-        let email = builder
-            .subject(format!("🚨 Seyeon Alert: New signal for {}!", crypto_status.symbol))
-            .multipart(
-                MultiPart::alternative()
-                    .singlepart(
-                        SinglePart::plain(format!("New signal detected for {}:\n\n{:?}", 
-                            crypto_status.symbol, crypto_status.action))
-                    )
-                    .singlepart(
-                        SinglePart::html(html_body)
-                    )
-            )?;
+    /// Asks for the approval of an order held back by the guardrails.
+    pub async fn send_approval_request(
+        &self,
+        approval: &PendingApproval,
+        expires_in_hours: i64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.send(Self::approval_request_body(approval, expires_in_hours))?;
+        println!("\nApproval request for {} sent by email to {}", approval.symbol, self.to_email);
 
-        let creds = Credentials::new(self.from_email.clone(), self.smtp_password.clone());
+        Ok(())
+    }
 
-        let mailer = SmtpTransport::relay("smtp.gmail.com")?
-            .credentials(creds)
-            .build();
+    /// The email of `send_approval_request`.
+    pub fn approval_request_body(approval: &PendingApproval, expires_in_hours: i64) -> EmailBody {
+        let now = Local::now().format("%d/%m/%Y %H:%M:%S").to_string();
+        let command = format!("oversight --approve {}", approval.token);
 
-        mailer.send(&email)?;
-        println!(
-            "\nStatus report sent by email to {} and {} CCs!",
-            self.to_email,
-            self.cc_emails.len()
+        let html_body = format!(r#"
+        <!DOCTYPE html>
+        <html>
+        <head>
+            <meta charset="UTF-8">
+            <style>
+                body {{
+                    font-family: Arial, sans-serif;
+                    color: #333333;
+                    max-width: 600px;
+                    margin: 0 auto;
+                    padding: 20px;
+                }}
+                .header {{
+                    background-color: #c0392b;
+                    color: white;
+                    padding: 15px;
+                    text-align: center;
+                    font-size: 24px;
+                    font-weight: bold;
+                    border-radius: 5px 5px 0 0;
+                }}
+                .content {{
+                    padding: 15px;
+                    background-color: #f9f9f9;
+                    border-left: 1px solid #ddd;
+                    border-right: 1px solid #ddd;
+                }}
+                table {{
+                    width: 100%;
+                    border-collapse: collapse;
+                }}
+                td, th {{
+                    padding: 8px;
+                    text-align: left;
+                    border-bottom: 1px solid #ddd;
+                }}
+                code {{
+                    background-color: #eeeeee;
+                    padding: 4px 6px;
+                    border-radius: 3px;
+                }}
+                .footer {{
+                    background-color: #eeeeee;
+                    padding: 15px;
+                    text-align: center;
+                    font-size: 12px;
+                    color: #666666;
+                    border-radius: 0 0 5px 5px;
+                    border: 1px solid #ddd;
+                }}
+                .time-info {{
+                    font-style: italic;
+                    color: #666666;
+                    font-size: 12px;
+                    margin-bottom: 10px;
+                }}
+            </style>
+        </head>
+        <body>
+            <div class="header">
+                세연 SEYEON OVERSIGHT - APPROVAL REQUIRED
+            </div>
+            <div class="content">
+                <p class="time-info">Generated at: {now}</p>
+                <p>This order is above the approval threshold and will not be executed until it is approved:</p>
+                <table>
+                    <tr><th>Asset</th><th>Action</th><th>Quantity</th><th>Price</th><th>Amount</th></tr>
+                    <tr>
+                        <td><strong>{}</strong></td>
+                        <td>{}</td>
+                        <td>{:.6}</td>
+                        <td>${:.4}</td>
+                        <td>${:.2}</td>
+                    </tr>
+                </table>
+                <p>To approve it, run:</p>
+                <p><code>{command}</code></p>
+                <p>The token expires in {expires_in_hours} hours. Ignore this email to reject the order.</p>
+            </div>
+            <div class="footer">
+                © 2025 Seyeon Oversight - Cryptocurrency Monitoring System<br>
+                This is an automated message. Please do not reply to this email.
+            </div>
+        </body>
+        </html>
+        "#, approval.symbol, approval.action, approval.quantity, approval.price, approval.notional);
+
+        let plain_text = format!(
+            "Order awaiting approval:\n\n{} {:.6} {} at ${:.4} (${:.2})\n\nTo approve it, run:\n{}\n\nThe token expires in {} hours. Ignore this email to reject the order.\n",
+            approval.action, approval.quantity, approval.symbol, approval.price, approval.notional,
+            command, expires_in_hours
         );
 
-        Ok(())
+        EmailBody {
+            subject: format!("⚠️ Seyeon: approval required for {} {}", approval.action, approval.symbol),
+            plain: plain_text,
+            html: html_body,
+        }
     }
 
     pub async fn send_daily_report(
         &self, 
-        status_list: Vec<(String, TradeAction)>,
-        correlation_data: Option<DataFrame>,
-        performance_data: Option<Vec<AssetPerformance>>,
-        fgi_data: Option<FearAndGreedData>,
-        global_market_data: Option<GlobalMarketData>
+        report: DailyReport,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        self.send(Self::daily_report_body(report))?;
+
+        println!("Daily report sent to {} and {} CCs!", self.to_email, self.cc_emails.len());
+
+        Ok(())
+    }
+
+    /// The email of `send_daily_report`.
+    pub fn daily_report_body(report: DailyReport) -> EmailBody {
+        let DailyReport {
+            signals: status_list,
+            positions,
+            rebalance,
+            paper,
+            scorecard,
+            stale_data,
+            correlations,
+            correlation_warnings,
+            performance: performance_data,
+            risk: risk_data,
+            fgi: fgi_data,
+            global_market: global_market_data,
+        } = report;
         let now = Local::now().format("%d/%m/%Y %H:%M:%S").to_string();
         let date_today = Local::now().format("%d/%m/%Y").to_string();
         
@@ -304,6 +606,12 @@ impl EmailConfig {
                 .correlation-neutral {{
                     background-color: rgba(189, 195, 199, 0.3);
                 }}
+                .warning {{
+                    background-color: rgba(231, 76, 60, 0.1);
+                    border-left: 4px solid #e74c3c;
+                    padding: 10px;
+                    margin: 10px 0;
+                }}
                 .performance-positive {{
                     color: #2ecc71;
                     font-weight: bold;
@@ -395,22 +703,43 @@ impl EmailConfig {
                 
                 <div class="section-header">Signal report for {date_today}:</div>
                 <table>
-                    <tr>
-                        <th>Cryptocurrency</th>
-                        <th>Signal</th>
-                    </tr>
         "#);
 
+        if positions.is_empty() {
+            html_body.push_str("<tr><th>Cryptocurrency</th><th>Signal</th></tr>");
+        } else {
+            html_body.push_str("<tr><th>Cryptocurrency</th><th>Signal</th><th>Position</th><th>Value</th><th>Unrealized P&amp;L</th></tr>");
+        }
+
         for (crypto, action) in &status_list {
             let action_str = format!("{:?}", action);
             let class = action_str.to_lowercase();
             html_body.push_str(&format!(
                 r#"<tr>
                     <td><strong>{}</strong></td>
-                    <td class="{}">{}</td>
-                </tr>"#,
+                    <td class="{}">{}</td>"#,
                 crypto, class, action_str
             ));
+
+            if !positions.is_empty() {
+                match positions.iter().find(|p| &p.symbol == crypto) {
+                    Some(position) => {
+                        let pnl_class = if position.unrealized_pnl >= 0.0 {
+                            "performance-positive"
+                        } else {
+                            "performance-negative"
+                        };
+                        html_body.push_str(&format!(
+                            r#"<td>{:.6}</td><td>${:.2}</td><td class="{}">${:.2} ({:+.2}%)</td>"#,
+                            position.quantity, position.market_value, pnl_class,
+                            position.unrealized_pnl, position.unrealized_pnl_pct
+                        ));
+                    }
+                    None => html_body.push_str("<td>-</td><td>-</td><td>-</td>"),
+                }
+            }
+
+            html_body.push_str("</tr>");
         }
 
         html_body.push_str(r#"
@@ -418,6 +747,195 @@ impl EmailConfig {
                 <p>Recommendations based on technical analysis and market indicators.</p>
         "#);
 
+        if !stale_data.is_empty() {
+            let assets: Vec<String> = stale_data.iter()
+                .map(|data| format!("{} ({})", data.symbol, data.freshness))
+                .collect();
+            html_body.push_str(&format!(
+                r#"<p class="warning"><strong>Stale data:</strong> the latest prices could not be fetched for {}. Their signals were computed on older data.</p>"#,
+                assets.join(", ")
+            ));
+        }
+
+        if !positions.is_empty() {
+            let total_value: f64 = positions.iter().map(|p| p.market_value).sum();
+            let total_pnl: f64 = positions.iter().map(|p| p.unrealized_pnl).sum();
+            let total_class = if total_pnl >= 0.0 {
+                "performance-positive"
+            } else {
+                "performance-negative"
+            };
+
+            html_body.push_str(r#"<div class="section-header">Holdings</div>"#);
+            html_body.push_str(&format!(
+                r#"<p>Total value: <strong>${:.2}</strong> - Unrealized P&amp;L: <strong class="{}">${:.2}</strong></p>"#,
+                total_value, total_class, total_pnl
+            ));
+
+            html_body.push_str(r#"<table>"#);
+            html_body.push_str(r#"<tr><th>Asset</th><th>Quantity</th><th>Avg. Cost</th><th>Price</th><th>Value</th><th>Allocation</th><th>Unrealized P&amp;L</th></tr>"#);
+
+            for position in &positions {
+                let pnl_class = if position.unrealized_pnl >= 0.0 {
+                    "performance-positive"
+                } else {
+                    "performance-negative"
+                };
+
+                html_body.push_str(&format!(
+                    r#"<tr>
+                        <td><strong>{}</strong></td>
+                        <td>{:.6}</td>
+                        <td>${:.4}</td>
+                        <td>${:.4}</td>
+                        <td>${:.2}</td>
+                        <td>{:.2}%</td>
+                        <td class="{}">${:.2} ({:+.2}%)</td>
+                    </tr>"#,
+                    position.symbol, position.quantity, position.average_cost, position.price,
+                    position.market_value, position.allocation * 100.0, pnl_class,
+                    position.unrealized_pnl, position.unrealized_pnl_pct
+                ));
+            }
+
+            html_body.push_str("</table>");
+        }
+
+        if let Some(plan) = &rebalance {
+            html_body.push_str(r#"<div class="section-header">Suggested rebalance</div>"#);
+            html_body.push_str(&format!("<p>{}. These trades bring your holdings back to their target allocation:</p>", plan.reason));
+
+            html_body.push_str(r#"<table>"#);
+            html_body.push_str(r#"<tr><th>Asset</th><th>Action</th><th>Current</th><th>Target</th><th>Amount</th><th>Quantity</th><th>Est. Fee</th></tr>"#);
+
+            for trade in &plan.trades {
+                html_body.push_str(&format!(
+                    r#"<tr>
+                        <td><strong>{}</strong></td>
+                        <td class="{}">{}</td>
+                        <td>{:.2}%</td>
+                        <td>{:.2}%</td>
+                        <td>${:.2}</td>
+                        <td>{:.6}</td>
+                        <td>${:.2}</td>
+                    </tr>"#,
+                    trade.symbol, trade.action.to_string().to_lowercase(), trade.action,
+                    trade.current_weight * 100.0, trade.target_weight * 100.0,
+                    trade.notional, trade.quantity, trade.estimated_fee
+                ));
+            }
+
+            html_body.push_str("</table>");
+            html_body.push_str("<p><em>Note: Suggestions only, nothing is traded automatically. Quantities are estimated at the latest close and net of fees.</em></p>");
+        }
+
+        if let Some(paper) = &paper {
+            let initial: f64 = paper.accounts.iter().map(|a| a.equity - a.pnl).sum();
+            let total_pnl: f64 = paper.accounts.iter().map(|a| a.pnl).sum();
+            let total_class = if total_pnl >= 0.0 {
+                "performance-positive"
+            } else {
+                "performance-negative"
+            };
+
+            html_body.push_str(r#"<div class="section-header">Paper Trading</div>"#);
+            html_body.push_str(&format!(
+                r#"<p>Live results of following every Buy/Sell signal with a separate paper account per asset. Total P&amp;L: <strong class="{}">${:.2} ({:+.2}%)</strong></p>"#,
+                total_class, total_pnl, if initial > 0.0 { total_pnl / initial * 100.0 } else { 0.0 }
+            ));
+
+            html_body.push_str(r#"<table>"#);
+            html_body.push_str(r#"<tr><th>Asset</th><th>Position</th><th>Cash</th><th>Equity</th><th>Paper P&amp;L</th><th>Realized</th><th>Fees</th><th>Trades</th><th>Simulated ROI</th></tr>"#);
+
+            for account in &paper.accounts {
+                let pnl_class = if account.pnl >= 0.0 {
+                    "performance-positive"
+                } else {
+                    "performance-negative"
+                };
+                let simulated_roi = account.simulated_roi
+                    .map_or("-".to_string(), |roi| format!("{:.2}%", roi));
+
+                html_body.push_str(&format!(
+                    r#"<tr>
+                        <td><strong>{}</strong></td>
+                        <td>{:.6} @ ${:.4}</td>
+                        <td>${:.2}</td>
+                        <td>${:.2}</td>
+                        <td class="{}">${:.2} ({:+.2}%)</td>
+                        <td>${:.2}</td>
+                        <td>${:.2}</td>
+                        <td>{}</td>
+                        <td>{}</td>
+                    </tr>"#,
+                    account.symbol, account.quantity, account.average_cost, account.cash, account.equity,
+                    pnl_class, account.pnl, account.pnl_pct, account.realized_pnl,
+                    account.fees_paid, account.trades, simulated_roi
+                ));
+            }
+
+            html_body.push_str("</table>");
+
+            if !paper.recent_trades.is_empty() {
+                html_body.push_str(r#"<table>"#);
+                html_body.push_str(r#"<tr><th>Date</th><th>Asset</th><th>Action</th><th>Price</th><th>Quantity</th><th>Amount</th><th>Fee</th><th>Realized P&amp;L</th></tr>"#);
+
+                for trade in &paper.recent_trades {
+                    html_body.push_str(&format!(
+                        r#"<tr>
+                            <td>{}</td>
+                            <td><strong>{}</strong></td>
+                            <td class="{}">{}</td>
+                            <td>${:.4}</td>
+                            <td>{:.6}</td>
+                            <td>${:.2}</td>
+                            <td>${:.2}</td>
+                            <td>${:.2}</td>
+                        </tr>"#,
+                        trade.datetime.format("%d/%m/%Y"), trade.symbol,
+                        trade.action.to_string().to_lowercase(), trade.action,
+                        trade.price, trade.quantity, trade.notional, trade.fee, trade.realized_pnl
+                    ));
+                }
+
+                html_body.push_str("</table>");
+            }
+
+            html_body.push_str("<p><em>Note: Paper trades are filled at the latest close with the simulation's trading fee; no orders are placed. The simulated ROI covers the backtest window, the paper P&amp;L the time since each account was opened.</em></p>");
+        }
+
+        if let Some(scorecard) = scorecard.as_ref().filter(|scorecard| !scorecard.rows.is_empty()) {
+            html_body.push_str(r#"<div class="section-header">Signal Scorecard</div>"#);
+            html_body.push_str(&format!(
+                r#"<p>How the price moved after the Buy/Sell alerts of the last {} days. A Buy is a hit when the price is higher after the horizon, a Sell when it is lower.</p>"#,
+                scorecard.window_days
+            ));
+
+            let horizons: Vec<u32> = scorecard.rows[0].horizons.iter().map(|h| h.days).collect();
+            html_body.push_str(r#"<table>"#);
+            html_body.push_str(r#"<tr><th>Asset</th><th>Signal</th><th>Alerts</th><th>Avg. confidence</th>"#);
+            for days in &horizons {
+                html_body.push_str(&format!("<th>{}d</th>", days));
+            }
+            html_body.push_str("</tr>");
+
+            for row in &scorecard.rows {
+                html_body.push_str(&format!(
+                    r#"<tr><td><strong>{}</strong></td><td class="{}">{}</td><td>{}</td><td>{:.0}%</td>"#,
+                    row.symbol.as_deref().unwrap_or("All assets"),
+                    row.action.to_string().to_lowercase(), row.action,
+                    row.signals, row.average_confidence * 100.0
+                ));
+                for stats in &row.horizons {
+                    html_body.push_str(&format!("<td>{}</td>", format_horizon(stats)));
+                }
+                html_body.push_str("</tr>");
+            }
+
+            html_body.push_str("</table>");
+            html_body.push_str("<p><em>Note: Returns are measured from the close of the signal's bar to the close the given number of days later, without fees.</em></p>");
+        }
+
         if let Some(perf_data) = &performance_data {
             html_body.push_str(r#"<div class="section-header">Performance Analysis</div>"#);
             html_body.push_str(r#"<p>This table shows the performance of your assets based on simulated trading using our algorithm:</p>"#);
@@ -446,6 +964,61 @@ impl EmailConfig {
             html_body.push_str("<p><em>Note: ROI (Return on Investment) is calculated using historical data and our trading algorithm. Past performance is not indicative of future results.</em></p>");
         }
 
+        if let Some(risk) = &risk_data {
+            html_body.push_str(r#"<div class="section-header">Risk Analysis</div>"#);
+            html_body.push_str(&format!(
+                r#"<p>Estimated one-day losses for the portfolio, based on the last {} days ({} observations) of daily returns. Annualized portfolio volatility: <strong>{:.2}%</strong>.</p>"#,
+                risk.window_days, risk.observations, risk.portfolio_volatility * 100.0
+            ));
+            if !risk.excluded.is_empty() {
+                html_body.push_str(&format!(
+                    "<p><em>Excluded for lack of history in this window: {}</em></p>",
+                    risk.excluded.join(", ")
+                ));
+            }
+
+            html_body.push_str(r#"<table>"#);
+            html_body.push_str(r#"<tr><th>Confidence</th><th>Historical VaR</th><th>Historical CVaR</th><th>Parametric VaR</th><th>Parametric CVaR</th></tr>"#);
+            for var in &risk.var {
+                html_body.push_str(&format!(
+                    r#"<tr>
+                        <td><strong>{:.0}%</strong></td>
+                        <td class="performance-negative">{:.2}%</td>
+                        <td class="performance-negative">{:.2}%</td>
+                        <td class="performance-negative">{:.2}%</td>
+                        <td class="performance-negative">{:.2}%</td>
+                    </tr>"#,
+                    var.confidence * 100.0,
+                    var.historical_var * 100.0,
+                    var.historical_cvar * 100.0,
+                    var.parametric_var * 100.0,
+                    var.parametric_cvar * 100.0
+                ));
+            }
+            html_body.push_str("</table>");
+
+            html_body.push_str(r#"<table>"#);
+            html_body.push_str(r#"<tr><th>Asset</th><th>Weight</th><th>Annualized Volatility</th><th>Marginal Contribution</th><th>Share of Risk</th></tr>"#);
+            for asset in &risk.assets {
+                html_body.push_str(&format!(
+                    r#"<tr>
+                        <td><strong>{}</strong></td>
+                        <td>{:.2}%</td>
+                        <td>{:.2}%</td>
+                        <td>{:.2}%</td>
+                        <td>{:.2}%</td>
+                    </tr>"#,
+                    asset.symbol,
+                    asset.weight * 100.0,
+                    asset.annualized_volatility * 100.0,
+                    asset.marginal_contribution * 100.0,
+                    asset.risk_contribution * 100.0
+                ));
+            }
+            html_body.push_str("</table>");
+            html_body.push_str("<p><em>Note: VaR is the loss not expected to be exceeded at the given confidence; CVaR is the average loss when it is. Historical figures use observed returns, parametric figures assume normally distributed returns.</em></p>");
+        }
+
         if let Some(fgi) = &fgi_data {
             let fgi_class = if fgi.value <= 20 {
                 "extreme-fear"
@@ -531,60 +1104,78 @@ impl EmailConfig {
             html_body.push_str("</table>");
         }
 
-        if let Some(ref corr_df) = correlation_data {
+        if !correlations.is_empty() {
             html_body.push_str(r#"<div class="section-header">Correlation Analysis</div>"#);
-            html_body.push_str(r#"<p>This matrix shows the correlation between different assets. Values close to 1 indicate high positive correlation, while values close to -1 indicate high negative correlation.</p>"#);
-            
-            let column_names = corr_df.get_column_names();
-            
-            html_body.push_str(r#"<table class="correlation-table">"#);
-            
-            html_body.push_str("<tr><th></th>");
-            for name in column_names.iter() {
-                html_body.push_str(&format!("<th>{}</th>", name));
+            html_body.push_str(r#"<p>These matrices show the correlation between the daily log returns of your assets over several trailing windows. Values close to 1 indicate high positive correlation, while values close to -1 indicate high negative correlation.</p>"#);
+
+            for warning in &correlation_warnings {
+                html_body.push_str(&format!(
+                    r#"<p class="warning"><strong>Diversification warning:</strong> the average pairwise correlation over the last {} days is {:.2}, above the {:.2} limit. Your assets are moving together and offer little diversification.</p>"#,
+                    warning.window_days, warning.average, warning.limit
+                ));
             }
-            html_body.push_str("</tr>");
-            
-            for (i, row_name) in column_names.iter().enumerate() {
-                html_body.push_str(&format!("<tr><th>{}</th>", row_name));
-                
-                for j in 0..column_names.len() {
-                    let corr_value = corr_df
-                        .column(column_names[j])
-                        .unwrap()
-                        .f64()
-                        .unwrap()
-                        .get(i)
-                        .unwrap_or(0.0);
-                    
-                    let cell_class = if i == j {
-                        "correlation-neutral"
-                    } else if corr_value > 0.7 {
-                        "correlation-high"
-                    } else if corr_value > 0.3 {
-                        "correlation-medium"
-                    } else if corr_value < -0.3 {
-                        "correlation-low"
-                    } else {
-                        "correlation-neutral"
-                    };
-                    
-                    let formatted_value = if i == j {
-                        "1.00".to_string()
-                    } else {
-                        format!("{:.2}", corr_value)
-                    };
-                    
-                    html_body.push_str(&format!(
-                        r#"<td class="{}">{}</td>"#,
-                        cell_class, formatted_value
-                    ));
+
+            for table in &correlations {
+                let corr_df = &table.matrix;
+                let column_names = corr_df.get_column_names();
+
+                html_body.push_str(&format!(
+                    "<p><strong>{}-day window</strong> ({} observations, {})",
+                    table.window_days, table.observations, table.method
+                ));
+                if let Some(average) = table.average {
+                    html_body.push_str(&format!(" - average pairwise correlation: <strong>{:.2}</strong>", average));
+                }
+                html_body.push_str("</p>");
+
+                html_body.push_str(r#"<table class="correlation-table">"#);
+
+                html_body.push_str("<tr><th></th>");
+                for name in column_names.iter() {
+                    html_body.push_str(&format!("<th>{}</th>", name));
                 }
-                
                 html_body.push_str("</tr>");
+
+                for (i, row_name) in column_names.iter().enumerate() {
+                    html_body.push_str(&format!("<tr><th>{}</th>", row_name));
+
+                    for (j, column_name) in column_names.iter().enumerate() {
+                        let Some(corr_value) = corr_df
+                            .column(column_name)
+                            .unwrap()
+                            .f64()
+                            .unwrap()
+                            .get(i)
+                        else {
+                            // Pair without enough shared history in this window.
+                            html_body.push_str(r#"<td class="correlation-neutral">n/a</td>"#);
+                            continue;
+                        };
+
+                        let cell_class = if i == j {
+                            "correlation-neutral"
+                        } else if corr_value > 0.7 {
+                            "correlation-high"
+                        } else if corr_value > 0.3 {
+                            "correlation-medium"
+                        } else if corr_value < -0.3 {
+                            "correlation-low"
+                        } else {
+                            "correlation-neutral"
+                        };
+
+                        html_body.push_str(&format!(
+                            r#"<td class="{}">{:.2}</td>"#,
+                            cell_class, corr_value
+                        ));
+                    }
+
+                    html_body.push_str("</tr>");
+                }
+
+                html_body.push_str("</table>");
             }
-            
-            html_body.push_str("</table>");
+
             html_body.push_str("<p><em>Note: High positive correlation (>0.7) indicates assets that tend to move together. Negative correlation indicates assets that tend to move in opposite directions, which can be useful for portfolio diversification.</em></p>");
         }
 
@@ -603,41 +1194,139 @@ impl EmailConfig {
         plain_text.push_str("Signal report:\n\n");
         
         for (crypto, action) in &status_list {
-            plain_text.push_str(&format!("{}: {:?}\n", crypto, action));
+            match positions.iter().find(|p| &p.symbol == crypto) {
+                Some(position) => plain_text.push_str(&format!(
+                    "{}: {:?} - position {:.6} (${:.2}), P&L ${:.2} ({:+.2}%)\n",
+                    crypto, action, position.quantity, position.market_value,
+                    position.unrealized_pnl, position.unrealized_pnl_pct
+                )),
+                None => plain_text.push_str(&format!("{}: {:?}\n", crypto, action)),
+            }
         }
 
         plain_text.push_str("\nRecommendations based on technical analysis and market indicators.\n");
 
-        if let Some(ref corr_df) = correlation_data {
+        if !stale_data.is_empty() {
+            plain_text.push_str("\nStale data: the latest prices could not be fetched for these assets, their signals were computed on older data:\n");
+            for data in &stale_data {
+                plain_text.push_str(&format!("{}: {}\n", data.symbol, data.freshness));
+            }
+        }
+
+        if !positions.is_empty() {
+            plain_text.push_str("\nHoldings:\n");
+
+            for position in &positions {
+                plain_text.push_str(&format!(
+                    "{}: {:.6} @ ${:.4} avg. cost - price ${:.4} - value ${:.2} ({:.2}%) - P&L ${:.2} ({:+.2}%)\n",
+                    position.symbol, position.quantity, position.average_cost, position.price,
+                    position.market_value, position.allocation * 100.0,
+                    position.unrealized_pnl, position.unrealized_pnl_pct
+                ));
+            }
+
+            let total_value: f64 = positions.iter().map(|p| p.market_value).sum();
+            let total_pnl: f64 = positions.iter().map(|p| p.unrealized_pnl).sum();
+            plain_text.push_str(&format!("Total value: ${:.2} - Unrealized P&L: ${:.2}\n", total_value, total_pnl));
+        }
+
+        if let Some(plan) = &rebalance {
+            plain_text.push_str("\nSuggested rebalance:\n");
+            plain_text.push_str(&format!("{}\n\n", plan.reason));
+
+            for trade in &plan.trades {
+                plain_text.push_str(&format!(
+                    "{} {}: ${:.2} ({:.6} units, est. fee ${:.2}) - weight {:.2}% -> {:.2}%\n",
+                    trade.action, trade.symbol, trade.notional, trade.quantity, trade.estimated_fee,
+                    trade.current_weight * 100.0, trade.target_weight * 100.0
+                ));
+            }
+        }
+
+        if let Some(paper) = &paper {
+            plain_text.push_str("\nPaper Trading:\n");
+
+            for account in &paper.accounts {
+                plain_text.push_str(&format!(
+                    "{}: {:.6} @ ${:.4} avg. cost + ${:.2} cash - equity ${:.2} - P&L ${:.2} ({:+.2}%), realized ${:.2}, fees ${:.2}, {} trades - simulated ROI {}\n",
+                    account.symbol, account.quantity, account.average_cost, account.cash, account.equity,
+                    account.pnl, account.pnl_pct, account.realized_pnl, account.fees_paid, account.trades,
+                    account.simulated_roi.map_or("-".to_string(), |roi| format!("{:.2}%", roi))
+                ));
+            }
+
+            if !paper.recent_trades.is_empty() {
+                plain_text.push_str("\nRecent paper trades:\n");
+                for trade in &paper.recent_trades {
+                    plain_text.push_str(&format!(
+                        "{} {} {}: {:.6} @ ${:.4} (${:.2}, fee ${:.2}, realized ${:.2})\n",
+                        trade.datetime.format("%d/%m/%Y"), trade.action, trade.symbol, trade.quantity,
+                        trade.price, trade.notional, trade.fee, trade.realized_pnl
+                    ));
+                }
+            }
+        }
+
+        if let Some(scorecard) = scorecard.as_ref().filter(|scorecard| !scorecard.rows.is_empty()) {
+            plain_text.push_str(&format!("\nSignal Scorecard (last {} days):\n", scorecard.window_days));
+
+            for row in &scorecard.rows {
+                let horizons: Vec<String> = row.horizons.iter()
+                    .map(|stats| format!("{}d {}", stats.days, format_horizon(stats)))
+                    .collect();
+                plain_text.push_str(&format!(
+                    "{} {}: {} alerts, avg. confidence {:.0}% - {}\n",
+                    row.symbol.as_deref().unwrap_or("All assets"), row.action, row.signals,
+                    row.average_confidence * 100.0, horizons.join(" | ")
+                ));
+            }
+        }
+
+        if !correlations.is_empty() {
             plain_text.push_str("\nCorrelation Analysis:\n");
-            plain_text.push_str("This matrix shows the correlation between different assets. Values close to 1 indicate high positive correlation, while values close to -1 indicate high negative correlation.\n\n");
-            
-            let column_names = corr_df.get_column_names();
-            
-            for (i, row_name) in column_names.iter().enumerate() {
-                plain_text.push_str(&format!("{}: ", row_name));
-                
-                for j in 0..column_names.len() {
-                    let corr_value = corr_df
-                        .column(column_names[j])
-                        .unwrap()
-                        .f64()
-                        .unwrap()
-                        .get(i)
-                        .unwrap_or(0.0);
-                    
-                    let formatted_value = if i == j {
-                        "1.00".to_string()
-                    } else {
-                        format!("{:.2}", corr_value)
-                    };
-                    
-                    plain_text.push_str(&format!("{} ", formatted_value));
+            plain_text.push_str("These matrices show the correlation between the daily log returns of your assets over several trailing windows. Values close to 1 indicate high positive correlation, while values close to -1 indicate high negative correlation.\n");
+
+            for warning in &correlation_warnings {
+                plain_text.push_str(&format!(
+                    "\nDIVERSIFICATION WARNING: average pairwise correlation over the last {} days is {:.2}, above the {:.2} limit.\n",
+                    warning.window_days, warning.average, warning.limit
+                ));
+            }
+
+            for table in &correlations {
+                let corr_df = &table.matrix;
+                let column_names = corr_df.get_column_names();
+
+                plain_text.push_str(&format!(
+                    "\n{}-day window ({} observations, {}):\n",
+                    table.window_days, table.observations, table.method
+                ));
+
+                for (i, row_name) in column_names.iter().enumerate() {
+                    plain_text.push_str(&format!("{}: ", row_name));
+
+                    for column_name in column_names.iter() {
+                        let corr_value = corr_df
+                            .column(column_name)
+                            .unwrap()
+                            .f64()
+                            .unwrap()
+                            .get(i);
+
+                        match corr_value {
+                            Some(value) => plain_text.push_str(&format!("{:.2} ", value)),
+                            None => plain_text.push_str("n/a "),
+                        }
+                    }
+
+                    plain_text.push('\n');
+                }
+
+                if let Some(average) = table.average {
+                    plain_text.push_str(&format!("Average pairwise correlation: {:.2}\n", average));
                 }
-                
-                plain_text.push_str("\n");
             }
-            
+
             plain_text.push_str("Note: High positive correlation (>0.7) indicates assets that tend to move together. Negative correlation indicates assets that tend to move in opposite directions, which can be useful for portfolio diversification.\n");
         }
 
@@ -652,6 +1341,43 @@ impl EmailConfig {
             plain_text.push_str("Note: ROI (Return on Investment) is calculated using historical data and our trading algorithm. Past performance is not indicative of future results.\n");
         }
         
+        if let Some(risk) = &risk_data {
+            plain_text.push_str("\nRisk Analysis:\n");
+            plain_text.push_str(&format!(
+                "Based on the last {} days ({} observations). Annualized portfolio volatility: {:.2}%\n\n",
+                risk.window_days, risk.observations, risk.portfolio_volatility * 100.0
+            ));
+            if !risk.excluded.is_empty() {
+                plain_text.push_str(&format!(
+                    "Excluded for lack of history in this window: {}\n\n",
+                    risk.excluded.join(", ")
+                ));
+            }
+
+            for var in &risk.var {
+                plain_text.push_str(&format!(
+                    "{:.0}% one-day VaR: {:.2}% historical / {:.2}% parametric - CVaR: {:.2}% historical / {:.2}% parametric\n",
+                    var.confidence * 100.0,
+                    var.historical_var * 100.0,
+                    var.parametric_var * 100.0,
+                    var.historical_cvar * 100.0,
+                    var.parametric_cvar * 100.0
+                ));
+            }
+
+            plain_text.push('\n');
+            for asset in &risk.assets {
+                plain_text.push_str(&format!(
+                    "{}: weight {:.2}% - volatility {:.2}% - marginal contribution {:.2}% - share of risk {:.2}%\n",
+                    asset.symbol,
+                    asset.weight * 100.0,
+                    asset.annualized_volatility * 100.0,
+                    asset.marginal_contribution * 100.0,
+                    asset.risk_contribution * 100.0
+                ));
+            }
+        }
+
         if let Some(market_data) = &global_market_data {
             plain_text.push_str("\nGlobal Cryptocurrency Market Overview:\n");
             plain_text.push_str(&format!("Total cryptocurrencies: {}\n", market_data.coins_count));
@@ -672,19 +1398,28 @@ impl EmailConfig {
             plain_text.push_str("The Fear & Greed Index measures market sentiment. Extreme fear can indicate buying opportunities, while extreme greed may suggest a market correction is coming.\n");
         }
 
+        EmailBody {
+            subject: format!("Daily Report - {}", date_today),
+            plain: plain_text,
+            html: html_body,
+        }
+    }
+
+    /// Sends `body` to the recipient and the CCs.
+    fn send(&self, body: EmailBody) -> Result<(), Box<dyn std::error::Error>> {
         let mut builder = Message::builder()
             .from(self.from_email.parse()?)
             .to(self.to_email.parse()?)
-            .subject(format!("Daily Report - {}", date_today));
-        
+            .subject(body.subject);
+
         for cc_email in &self.cc_emails {
             builder = builder.cc(Mailbox::from_str(cc_email)?);
         }
 
         let email = builder.multipart(
                 MultiPart::alternative()
-                    .singlepart(SinglePart::plain(plain_text))
-                    .singlepart(SinglePart::html(html_body))
+                    .singlepart(SinglePart::plain(body.plain))
+                    .singlepart(SinglePart::html(body.html))
             )?;
 
         let creds = Credentials::new(self.from_email.clone(), self.smtp_password.clone());
@@ -695,8 +1430,6 @@ impl EmailConfig {
 
         mailer.send(&email)?;
 
-        println!("Daily report sent to {} and {} CCs!", self.to_email, self.cc_emails.len());
-
         Ok(())
     }
 }
\ No newline at end of file
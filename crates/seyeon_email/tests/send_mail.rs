@@ -26,7 +26,7 @@ pub async fn report_sender() {
         sent: false,
     };
 
-    if let Err(e) = email_config.report_sender(&status).await {
+    if let Err(e) = email_config.report_sender(&status, None).await {
         eprintln!("Failed to send complete report: {}", e);
     } else {
         println!("Complete report sent successfully!");
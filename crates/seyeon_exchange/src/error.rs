@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ExchangeError {
+    #[error("Unknown market: {0}")]
+    UnknownMarket(String),
+    #[error("No price available for {0}")]
+    NoPrice(String),
+    #[error("Invalid order: {0}")]
+    InvalidOrder(String),
+    #[error("Insufficient {asset} balance: {available} available, {required} required")]
+    InsufficientBalance {
+        asset: String,
+        available: f64,
+        required: f64,
+    },
+    #[error("Order {0} not found")]
+    OrderNotFound(u64),
+    #[error("Order {0} is no longer open")]
+    OrderClosed(u64),
+}
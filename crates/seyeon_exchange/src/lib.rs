@@ -0,0 +1,132 @@
+pub mod error;
+pub mod mock;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+
+pub use error::ExchangeError;
+pub use mock::MockExchange;
+
+/// Asset all markets are quoted in.
+pub const QUOTE_ASSET: &str = "USD";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Buy => write!(f, "Buy"),
+            Side::Sell => write!(f, "Sell"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OrderType {
+    /// Fills immediately at the current price.
+    Market,
+    /// Fills once the price reaches the limit.
+    Limit { price: f64 },
+}
+
+/// An order to buy or sell `quantity` units of `symbol` against `QUOTE_ASSET`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderRequest {
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: f64,
+    pub order_type: OrderType,
+}
+
+impl OrderRequest {
+    pub fn market(symbol: &str, side: Side, quantity: f64) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            quantity,
+            order_type: OrderType::Market,
+        }
+    }
+
+    pub fn limit(symbol: &str, side: Side, quantity: f64, price: f64) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            quantity,
+            order_type: OrderType::Limit { price },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderStatus {
+    Open,
+    Filled,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub id: u64,
+    pub request: OrderRequest,
+    pub status: OrderStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fill {
+    pub order_id: u64,
+    pub symbol: String,
+    pub side: Side,
+    pub price: f64,
+    pub quantity: f64,
+    /// Charged in `QUOTE_ASSET`.
+    pub fee: f64,
+    pub datetime: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Balance {
+    pub asset: String,
+    pub free: f64,
+    /// Reserved by open orders.
+    pub locked: f64,
+}
+
+impl Balance {
+    pub fn total(&self) -> f64 {
+        self.free + self.locked
+    }
+}
+
+/// A venue orders can be sent to. Implementations are expected to reject orders the
+/// account cannot pay for rather than partially filling them.
+pub trait Exchange {
+    fn name(&self) -> &str;
+
+    /// Last traded price of `symbol` in `QUOTE_ASSET`.
+    fn price(&self, symbol: &str) -> impl Future<Output = Result<f64, ExchangeError>> + Send;
+
+    fn place_order(
+        &mut self,
+        request: OrderRequest,
+    ) -> impl Future<Output = Result<Order, ExchangeError>> + Send;
+
+    fn cancel_order(&mut self, id: u64) -> impl Future<Output = Result<Order, ExchangeError>> + Send;
+
+    fn open_orders(&self) -> impl Future<Output = Result<Vec<Order>, ExchangeError>> + Send;
+
+    fn balances(&self) -> impl Future<Output = Result<Vec<Balance>, ExchangeError>> + Send;
+
+    /// Fills executed at or after `since`, oldest first (all of them when `None`).
+    fn fills(
+        &self,
+        since: Option<DateTime<Utc>>,
+    ) -> impl Future<Output = Result<Vec<Fill>, ExchangeError>> + Send;
+}
@@ -0,0 +1,294 @@
+use crate::{Balance, Exchange, ExchangeError, Fill, Order, OrderRequest, OrderStatus, OrderType, QUOTE_ASSET, Side};
+use chrono::{DateTime, Utc};
+use seyeon_trading_engine::data_point::DataPoint;
+use std::collections::HashMap;
+
+/// In-process exchange that fills orders against daily candles, e.g. the cached
+/// CryptoCompare history.
+///
+/// Market orders fill at the close of the current candle. Limit orders that are already
+/// marketable fill the same way; the others stay open and fill at their limit on the
+/// first later candle whose range reaches it, once the clock gets there. The clock
+/// follows the latest candle of each market unless it is set with `advance_to`.
+#[derive(Debug, Clone)]
+pub struct MockExchange {
+    fee_rate: f64,
+    candles: HashMap<String, Vec<DataPoint>>,
+    now: Option<DateTime<Utc>>,
+    balances: HashMap<String, Balance>,
+    orders: Vec<Order>,
+    fills: Vec<Fill>,
+    next_id: u64,
+}
+
+impl MockExchange {
+    /// An exchange holding `quote_balance` of `QUOTE_ASSET` and charging `fee_rate` of the
+    /// notional on every fill.
+    pub fn new(quote_balance: f64, fee_rate: f64) -> Self {
+        let mut exchange = Self {
+            fee_rate,
+            candles: HashMap::new(),
+            now: None,
+            balances: HashMap::new(),
+            orders: Vec::new(),
+            fills: Vec::new(),
+            next_id: 1,
+        };
+        exchange.deposit(QUOTE_ASSET, quote_balance);
+        exchange
+    }
+
+    pub fn with_candles(mut self, symbol: &str, candles: Vec<DataPoint>) -> Self {
+        self.set_candles(symbol, candles);
+        self
+    }
+
+    /// Replaces the candles of `symbol` and fills the open orders the new candles reach.
+    pub fn set_candles(&mut self, symbol: &str, mut candles: Vec<DataPoint>) {
+        candles.retain(|c| c.price.is_finite() && c.price > 0.0);
+        candles.sort_by_key(|c| c.datetime);
+        self.candles.insert(symbol.to_uppercase(), candles);
+        self.match_open_orders();
+    }
+
+    /// Moves the clock to `datetime` and fills the open orders reached on the way.
+    pub fn advance_to(&mut self, datetime: DateTime<Utc>) {
+        self.now = Some(datetime);
+        self.match_open_orders();
+    }
+
+    pub fn deposit(&mut self, asset: &str, amount: f64) {
+        self.balance_mut(asset).free += amount;
+    }
+
+    pub fn balance(&self, asset: &str) -> Balance {
+        self.balances.get(&asset.to_uppercase()).cloned().unwrap_or(Balance {
+            asset: asset.to_uppercase(),
+            free: 0.0,
+            locked: 0.0,
+        })
+    }
+
+    fn balance_mut(&mut self, asset: &str) -> &mut Balance {
+        let asset = asset.to_uppercase();
+        self.balances.entry(asset.clone()).or_insert(Balance {
+            asset,
+            free: 0.0,
+            locked: 0.0,
+        })
+    }
+
+    /// Latest candle of `symbol` at or before the clock.
+    fn current_candle(&self, symbol: &str) -> Result<&DataPoint, ExchangeError> {
+        let candles = self
+            .candles
+            .get(symbol)
+            .ok_or_else(|| ExchangeError::UnknownMarket(symbol.to_string()))?;
+
+        let visible = match self.now {
+            Some(now) => candles.partition_point(|c| c.datetime <= now),
+            None => candles.len(),
+        };
+
+        visible
+            .checked_sub(1)
+            .map(|idx| &candles[idx])
+            .ok_or_else(|| ExchangeError::NoPrice(symbol.to_string()))
+    }
+
+    fn reserve(&mut self, asset: &str, amount: f64) -> Result<(), ExchangeError> {
+        let balance = self.balance_mut(asset);
+        if balance.free + 1e-9 < amount {
+            return Err(ExchangeError::InsufficientBalance {
+                asset: balance.asset.clone(),
+                available: balance.free,
+                required: amount,
+            });
+        }
+
+        balance.free -= amount;
+        balance.locked += amount;
+        Ok(())
+    }
+
+    /// Settles a fill whose cost (buys) or quantity (sells) was reserved beforehand.
+    fn settle(&mut self, order: &Order, price: f64, datetime: DateTime<Utc>) -> Fill {
+        let request = &order.request;
+        let notional = request.quantity * price;
+        let fee = notional * self.fee_rate;
+
+        match request.side {
+            Side::Buy => {
+                let reserved = match request.order_type {
+                    OrderType::Limit { price: limit } => request.quantity * limit * (1.0 + self.fee_rate),
+                    OrderType::Market => notional + fee,
+                };
+                let quote = self.balance_mut(QUOTE_ASSET);
+                quote.locked -= reserved;
+                quote.free += reserved - notional - fee;
+                self.balance_mut(&request.symbol).free += request.quantity;
+            }
+            Side::Sell => {
+                self.balance_mut(&request.symbol).locked -= request.quantity;
+                self.balance_mut(QUOTE_ASSET).free += notional - fee;
+            }
+        }
+
+        let fill = Fill {
+            order_id: order.id,
+            symbol: request.symbol.clone(),
+            side: request.side,
+            price,
+            quantity: request.quantity,
+            fee,
+            datetime,
+        };
+        self.fills.push(fill.clone());
+        fill
+    }
+
+    fn match_open_orders(&mut self) {
+        let mut matched = Vec::new();
+
+        for (idx, order) in self.orders.iter().enumerate() {
+            let OrderType::Limit { price: limit } = order.request.order_type else {
+                continue;
+            };
+            if order.status != OrderStatus::Open {
+                continue;
+            }
+            let Some(candles) = self.candles.get(&order.request.symbol) else {
+                continue;
+            };
+
+            let reached = candles
+                .iter()
+                .filter(|c| c.datetime > order.created_at && self.now.is_none_or(|now| c.datetime <= now))
+                .find(|c| match order.request.side {
+                    Side::Buy => c.low.min(c.price) <= limit,
+                    Side::Sell => c.high.max(c.price) >= limit,
+                });
+
+            if let Some(candle) = reached {
+                matched.push((idx, limit, candle.datetime));
+            }
+        }
+
+        for (idx, price, datetime) in matched {
+            self.orders[idx].status = OrderStatus::Filled;
+            let order = self.orders[idx].clone();
+            self.settle(&order, price, datetime);
+        }
+    }
+}
+
+impl Exchange for MockExchange {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    async fn price(&self, symbol: &str) -> Result<f64, ExchangeError> {
+        Ok(self.current_candle(&symbol.to_uppercase())?.price)
+    }
+
+    async fn place_order(&mut self, mut request: OrderRequest) -> Result<Order, ExchangeError> {
+        request.symbol = request.symbol.to_uppercase();
+        if !request.quantity.is_finite() || request.quantity <= 0.0 {
+            return Err(ExchangeError::InvalidOrder(format!("quantity {}", request.quantity)));
+        }
+        match request.order_type {
+            OrderType::Limit { price } if !price.is_finite() || price <= 0.0 => {
+                return Err(ExchangeError::InvalidOrder(format!("limit price {}", price)));
+            }
+            _ => {}
+        }
+
+        let candle = self.current_candle(&request.symbol)?;
+        let (close, datetime) = (candle.price, candle.datetime);
+
+        let marketable = match (request.order_type, request.side) {
+            (OrderType::Market, _) => true,
+            (OrderType::Limit { price }, Side::Buy) => price >= close,
+            (OrderType::Limit { price }, Side::Sell) => price <= close,
+        };
+
+        match (request.side, request.order_type) {
+            (Side::Buy, OrderType::Limit { price }) => {
+                self.reserve(QUOTE_ASSET, request.quantity * price * (1.0 + self.fee_rate))?
+            }
+            (Side::Buy, OrderType::Market) => {
+                self.reserve(QUOTE_ASSET, request.quantity * close * (1.0 + self.fee_rate))?
+            }
+            (Side::Sell, _) => self.reserve(&request.symbol, request.quantity)?,
+        }
+
+        let mut order = Order {
+            id: self.next_id,
+            request,
+            status: OrderStatus::Open,
+            created_at: datetime,
+        };
+        self.next_id += 1;
+
+        if marketable {
+            order.status = OrderStatus::Filled;
+            self.settle(&order, close, datetime);
+        }
+
+        self.orders.push(order.clone());
+        Ok(order)
+    }
+
+    async fn cancel_order(&mut self, id: u64) -> Result<Order, ExchangeError> {
+        let order = self
+            .orders
+            .iter_mut()
+            .find(|order| order.id == id)
+            .ok_or(ExchangeError::OrderNotFound(id))?;
+        if order.status != OrderStatus::Open {
+            return Err(ExchangeError::OrderClosed(id));
+        }
+
+        order.status = OrderStatus::Cancelled;
+        let order = order.clone();
+        let request = &order.request;
+
+        let (asset, reserved) = match (request.side, request.order_type) {
+            (Side::Buy, OrderType::Limit { price }) => {
+                (QUOTE_ASSET.to_string(), request.quantity * price * (1.0 + self.fee_rate))
+            }
+            _ => (request.symbol.clone(), request.quantity),
+        };
+        let balance = self.balance_mut(&asset);
+        balance.locked -= reserved;
+        balance.free += reserved;
+
+        Ok(order)
+    }
+
+    async fn open_orders(&self) -> Result<Vec<Order>, ExchangeError> {
+        Ok(self
+            .orders
+            .iter()
+            .filter(|order| order.status == OrderStatus::Open)
+            .cloned()
+            .collect())
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>, ExchangeError> {
+        let mut balances: Vec<Balance> = self.balances.values().cloned().collect();
+        balances.sort_by(|a, b| a.asset.cmp(&b.asset));
+        Ok(balances)
+    }
+
+    async fn fills(&self, since: Option<DateTime<Utc>>) -> Result<Vec<Fill>, ExchangeError> {
+        let mut fills: Vec<Fill> = self
+            .fills
+            .iter()
+            .filter(|fill| since.is_none_or(|since| fill.datetime >= since))
+            .cloned()
+            .collect();
+        fills.sort_by_key(|fill| fill.datetime);
+        Ok(fills)
+    }
+}
@@ -0,0 +1,74 @@
+use seyeon_exchange::{Exchange, ExchangeError, MockExchange, OrderRequest, OrderStatus, QUOTE_ASSET, Side};
+use seyeon_trading_engine::data_point::DailySeries;
+
+#[tokio::test]
+async fn market_orders_fill_at_the_latest_close_with_fees() {
+    let mut exchange = MockExchange::new(1_000.0, 0.01).with_candles("btc", DailySeries::new().spread(0.05).prices(&[90.0, 100.0]));
+
+    let order = exchange.place_order(OrderRequest::market("BTC", Side::Buy, 5.0)).await.unwrap();
+    assert_eq!(order.status, OrderStatus::Filled);
+
+    // 5 units at 100 plus a 1% fee.
+    assert!((exchange.balance(QUOTE_ASSET).free - 495.0).abs() < 1e-9);
+    assert_eq!(exchange.balance("BTC").free, 5.0);
+
+    exchange.place_order(OrderRequest::market("BTC", Side::Sell, 5.0)).await.unwrap();
+    assert!((exchange.balance(QUOTE_ASSET).free - 990.0).abs() < 1e-9);
+
+    let fills = exchange.fills(None).await.unwrap();
+    assert_eq!(fills.len(), 2);
+    assert!(fills.iter().all(|fill| fill.price == 100.0 && (fill.fee - 5.0).abs() < 1e-9));
+}
+
+#[tokio::test]
+async fn orders_the_account_cannot_pay_for_are_rejected() {
+    let mut exchange = MockExchange::new(100.0, 0.0).with_candles("ETH", DailySeries::new().spread(0.05).prices(&[50.0]));
+
+    let buy = exchange.place_order(OrderRequest::market("ETH", Side::Buy, 3.0)).await;
+    assert!(matches!(buy, Err(ExchangeError::InsufficientBalance { .. })));
+
+    let sell = exchange.place_order(OrderRequest::market("ETH", Side::Sell, 1.0)).await;
+    assert!(matches!(sell, Err(ExchangeError::InsufficientBalance { .. })));
+
+    let unknown = exchange.place_order(OrderRequest::market("SOL", Side::Buy, 1.0)).await;
+    assert_eq!(unknown, Err(ExchangeError::UnknownMarket("SOL".to_string())));
+    assert_eq!(exchange.balance(QUOTE_ASSET).free, 100.0);
+}
+
+#[tokio::test]
+async fn limit_orders_wait_for_a_later_candle_to_reach_them() {
+    let history = DailySeries::new().spread(0.05).prices(&[100.0, 100.0, 90.0, 80.0]);
+    let mut exchange = MockExchange::new(1_000.0, 0.0).with_candles("BTC", history.clone());
+    exchange.advance_to(history[1].datetime);
+
+    let order = exchange.place_order(OrderRequest::limit("BTC", Side::Buy, 5.0, 85.0)).await.unwrap();
+    assert_eq!(order.status, OrderStatus::Open);
+    assert_eq!(exchange.balance(QUOTE_ASSET).locked, 425.0);
+
+    // Day 2 trades down to 85.5, day 3 to 76.
+    exchange.advance_to(history[2].datetime);
+    assert_eq!(exchange.open_orders().await.unwrap().len(), 1);
+
+    exchange.advance_to(history[3].datetime);
+    assert!(exchange.open_orders().await.unwrap().is_empty());
+    assert_eq!(exchange.balance("BTC").free, 5.0);
+    assert_eq!(exchange.balance(QUOTE_ASSET).total(), 575.0);
+
+    let fills = exchange.fills(Some(history[3].datetime)).await.unwrap();
+    assert_eq!(fills.len(), 1);
+    assert_eq!(fills[0].price, 85.0);
+}
+
+#[tokio::test]
+async fn cancelling_releases_the_reserved_balance() {
+    let mut exchange = MockExchange::new(0.0, 0.0).with_candles("BTC", DailySeries::new().spread(0.05).prices(&[100.0]));
+    exchange.deposit("BTC", 2.0);
+
+    let order = exchange.place_order(OrderRequest::limit("BTC", Side::Sell, 2.0, 150.0)).await.unwrap();
+    assert_eq!(exchange.balance("BTC").locked, 2.0);
+
+    let cancelled = exchange.cancel_order(order.id).await.unwrap();
+    assert_eq!(cancelled.status, OrderStatus::Cancelled);
+    assert_eq!(exchange.balance("BTC").free, 2.0);
+    assert_eq!(exchange.cancel_order(order.id).await, Err(ExchangeError::OrderClosed(order.id)));
+}
@@ -0,0 +1,141 @@
+pub mod policy;
+
+pub use policy::{HttpPolicy, backoff_delay, parse_retry_after};
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, ClientBuilder, Response, StatusCode};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Semaphore;
+
+/// One semaphore per host, shared by every client of the process.
+static HOST_LIMITS: OnceLock<Mutex<HashMap<String, Arc<Semaphore>>>> = OnceLock::new();
+
+fn host_semaphore(url: &str, permits: usize) -> Arc<Semaphore> {
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+
+    let mut limits = HOST_LIMITS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    limits
+        .entry(host)
+        .or_insert_with(|| Arc::new(Semaphore::new(permits.max(1))))
+        .clone()
+}
+
+/// The base URL set in `env_var_name` (e.g. a proxy or a caching gateway), or `default`
+/// when it is unset or blank.
+pub fn base_url_from_env(env_var_name: &str, default: &str) -> String {
+    env::var(env_var_name)
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request()
+}
+
+/// A `reqwest` client with the timeouts, retries and per-host concurrency limit of an
+/// `HttpPolicy`. Requests answered with 429 or a 5xx status, or failing to connect or
+/// time out, are retried with exponential backoff and jitter, waiting at least as long
+/// as the `Retry-After` header asks.
+pub struct HttpClient {
+    reqwest: Client,
+    policy: HttpPolicy,
+}
+
+impl HttpClient {
+    /// A client with the policy of the environment (see `HttpPolicy::from_env`).
+    pub fn new(default_headers: HeaderMap) -> Self {
+        Self::with_policy(default_headers, HttpPolicy::from_env())
+    }
+
+    pub fn with_policy(default_headers: HeaderMap, policy: HttpPolicy) -> Self {
+        let reqwest = ClientBuilder::new()
+            .default_headers(default_headers)
+            .connect_timeout(policy.connect_timeout)
+            .timeout(policy.timeout)
+            .build()
+            .expect("Failed to build reqwest client");
+
+        Self { reqwest, policy }
+    }
+
+    pub fn policy(&self) -> &HttpPolicy {
+        &self.policy
+    }
+
+    /// Sends a GET request to `url` with the query `params`, retrying per the policy.
+    /// The last response is returned once it succeeds or the retries are exhausted.
+    pub async fn get<P: Serialize + ?Sized>(&self, url: &str, params: &P) -> reqwest::Result<Response> {
+        let semaphore = host_semaphore(url, self.policy.max_concurrency_per_host);
+        let mut attempt = 0;
+
+        loop {
+            let result = {
+                // Held for the request only, not while waiting to retry.
+                let _permit = semaphore.acquire().await.expect("Host semaphore closed");
+                self.reqwest.get(url).query(params).send().await
+            };
+
+            let retry_after = match &result {
+                Ok(response) if is_retryable_status(response.status()) => response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| parse_retry_after(value, SystemTime::now()))
+                    .or(Some(Duration::ZERO)),
+                Err(e) if is_retryable_error(e) => Some(Duration::ZERO),
+                _ => None,
+            };
+
+            let Some(retry_after) = retry_after.filter(|_| attempt < self.policy.max_retries) else {
+                return result?.error_for_status();
+            };
+
+            // A server asking to wait longer than the backoff allows is not retried.
+            if retry_after > self.policy.max_delay {
+                return result?.error_for_status();
+            }
+
+            let delay = backoff_delay(&self.policy, attempt, &mut rand::thread_rng()).max(retry_after);
+            eprintln!(
+                "Request to {} failed ({}), retrying in {:.1}s ({}/{})",
+                url,
+                match &result {
+                    Ok(response) => response.status().to_string(),
+                    Err(e) => e.to_string(),
+                },
+                delay.as_secs_f64(),
+                attempt + 1,
+                self.policy.max_retries
+            );
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Sends a GET request like `get` and deserializes the JSON body.
+    pub async fn get_json<R: DeserializeOwned, P: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        params: &P,
+    ) -> reqwest::Result<R> {
+        self.get(url, params).await?.json().await
+    }
+}
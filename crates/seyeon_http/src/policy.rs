@@ -0,0 +1,90 @@
+use rand::Rng;
+use std::env;
+use std::time::{Duration, SystemTime};
+
+/// Timeouts, retries and concurrency of an `HttpClient`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpPolicy {
+    pub connect_timeout: Duration,
+    /// Limit of a whole request, from connecting to reading the body.
+    pub timeout: Duration,
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on every retry.
+    pub base_delay: Duration,
+    /// Longest delay between two attempts. A `Retry-After` above it is not waited for.
+    pub max_delay: Duration,
+    /// Requests in flight to the same host, across all clients.
+    pub max_concurrency_per_host: usize,
+}
+
+impl Default for HttpPolicy {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(5),
+            timeout: Duration::from_secs(30),
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            max_concurrency_per_host: 4,
+        }
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let value = env::var(name).ok()?;
+    match value.trim().parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            eprintln!("Invalid {} '{}', using the default", name, value);
+            None
+        }
+    }
+}
+
+impl HttpPolicy {
+    /// The default policy, overridden by `HTTP_CONNECT_TIMEOUT_SECS`, `HTTP_TIMEOUT_SECS`,
+    /// `HTTP_MAX_RETRIES` and `HTTP_MAX_CONCURRENCY_PER_HOST` when set.
+    pub fn from_env() -> Self {
+        let mut policy = Self::default();
+
+        if let Some(secs) = env_parse("HTTP_CONNECT_TIMEOUT_SECS") {
+            policy.connect_timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = env_parse("HTTP_TIMEOUT_SECS") {
+            policy.timeout = Duration::from_secs(secs);
+        }
+        if let Some(retries) = env_parse("HTTP_MAX_RETRIES") {
+            policy.max_retries = retries;
+        }
+        if let Some(concurrency) = env_parse("HTTP_MAX_CONCURRENCY_PER_HOST") {
+            policy.max_concurrency_per_host = concurrency;
+        }
+
+        policy
+    }
+}
+
+/// Delay before retry number `attempt` (0 for the first): a random duration up to
+/// `base_delay * 2^attempt`, capped at `max_delay` ("full jitter").
+pub fn backoff_delay<R: Rng>(policy: &HttpPolicy, attempt: u32, rng: &mut R) -> Duration {
+    let ceiling = policy
+        .base_delay
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(policy.max_delay);
+
+    ceiling.mul_f64(rng.gen_range(0.0..=1.0))
+}
+
+/// Reads a `Retry-After` header, either delay seconds or an HTTP date. A date in the past
+/// gives a zero delay.
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or(Duration::ZERO))
+}
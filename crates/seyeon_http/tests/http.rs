@@ -0,0 +1,129 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+use seyeon_http::{HttpClient, HttpPolicy, backoff_delay, base_url_from_env, parse_retry_after};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn fast_policy() -> HttpPolicy {
+    HttpPolicy {
+        base_delay: Duration::from_millis(10),
+        max_delay: Duration::from_millis(100),
+        ..Default::default()
+    }
+}
+
+/// Serves `responses` in order, one per connection, then repeats the last one. Returns
+/// the base URL and the number of requests served.
+async fn serve(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let served = Arc::new(AtomicUsize::new(0));
+
+    let counter = served.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else { break };
+            let index = counter.fetch_add(1, Ordering::SeqCst).min(responses.len() - 1);
+
+            let mut buffer = [0u8; 4096];
+            let _ = socket.read(&mut buffer).await;
+            let _ = socket.write_all(responses[index].as_bytes()).await;
+            let _ = socket.shutdown().await;
+        }
+    });
+
+    (url, served)
+}
+
+const OK: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 11\r\nConnection: close\r\n\r\n{\"value\":1}";
+const UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const TOO_MANY: &str = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const TOO_MANY_LATER: &str = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 3600\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+#[derive(Debug, serde::Deserialize)]
+struct Value {
+    value: u32,
+}
+
+#[test]
+fn backoff_grows_and_stays_under_the_cap() {
+    let policy = fast_policy();
+    let mut rng = StdRng::seed_from_u64(7);
+
+    for attempt in 0..10 {
+        let ceiling = (policy.base_delay * 2u32.pow(attempt)).min(policy.max_delay);
+        let delay = backoff_delay(&policy, attempt, &mut rng);
+        assert!(delay <= ceiling, "attempt {attempt}: {delay:?} > {ceiling:?}");
+    }
+}
+
+#[test]
+fn retry_after_accepts_seconds_and_dates() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+    assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+    assert_eq!(
+        parse_retry_after(&httpdate::fmt_http_date(now + Duration::from_secs(30)), now),
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(parse_retry_after(&httpdate::fmt_http_date(now - Duration::from_secs(30)), now), Some(Duration::ZERO));
+    assert_eq!(parse_retry_after("soon", now), None);
+}
+
+#[tokio::test]
+async fn retries_server_errors_and_rate_limits() {
+    let (url, served) = serve(vec![UNAVAILABLE, TOO_MANY, OK]).await;
+    let client = HttpClient::with_policy(HeaderMap::new(), fast_policy());
+
+    let response: Value = client.get_json(&url, &()).await.unwrap();
+
+    assert_eq!(response.value, 1);
+    assert_eq!(served.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_the_last_retry() {
+    let (url, served) = serve(vec![UNAVAILABLE]).await;
+    let client = HttpClient::with_policy(HeaderMap::new(), fast_policy());
+
+    let error = client.get_json::<Value, _>(&url, &()).await.unwrap_err();
+
+    assert_eq!(error.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert_eq!(served.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors_or_long_retry_after() {
+    let client = HttpClient::with_policy(HeaderMap::new(), fast_policy());
+
+    let (url, served) = serve(vec![NOT_FOUND]).await;
+    let error = client.get_json::<Value, _>(&url, &()).await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+    assert_eq!(served.load(Ordering::SeqCst), 1);
+
+    let (url, served) = serve(vec![TOO_MANY_LATER]).await;
+    let error = client.get_json::<Value, _>(&url, &()).await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::TOO_MANY_REQUESTS));
+    assert_eq!(served.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn base_url_falls_back_to_the_default_when_unset_or_blank() {
+    let default = "https://api.example.com";
+    // Variables of this test only; set_var is unsafe as other threads may read the environment.
+    unsafe {
+        std::env::remove_var("SEYEON_HTTP_TEST_UNSET_BASE_URL");
+        std::env::set_var("SEYEON_HTTP_TEST_BLANK_BASE_URL", "  ");
+        std::env::set_var("SEYEON_HTTP_TEST_BASE_URL", "http://127.0.0.1:8080");
+    }
+
+    assert_eq!(base_url_from_env("SEYEON_HTTP_TEST_UNSET_BASE_URL", default), default);
+    assert_eq!(base_url_from_env("SEYEON_HTTP_TEST_BLANK_BASE_URL", default), default);
+    assert_eq!(base_url_from_env("SEYEON_HTTP_TEST_BASE_URL", default), "http://127.0.0.1:8080");
+}
@@ -0,0 +1,62 @@
+//! Re-records the `FIXTURES` from the live APIs. Needs `CRYPTOCOMPARE_API_KEY` and
+//! `RAPIDAPI_KEY` (the first key of each is used):
+//!
+//! cargo run -p seyeon_mock_api --bin record_fixtures
+
+use reqwest::header::{HeaderMap, HeaderValue};
+use seyeon_mock_api::{FIXTURES, fixtures_dir};
+use std::env;
+
+/// Days of price history recorded, enough for every indicator of the trading engine.
+const HISTODAY_LIMIT: &str = "399";
+
+fn first_key(env_var_name: &str) -> Result<HeaderValue, Box<dyn std::error::Error>> {
+    let keys = env::var(env_var_name).map_err(|e| format!("{}: {}", env_var_name, e))?;
+    let key = keys.split(',').map(str::trim).find(|key| !key.is_empty());
+
+    Ok(HeaderValue::from_str(key.ok_or_else(|| format!("No key in {}", env_var_name))?)?)
+}
+
+/// Public base URL and headers of the API a fixture was recorded from.
+fn live_api(file: &str) -> Result<(&'static str, HeaderMap), Box<dyn std::error::Error>> {
+    let mut headers = HeaderMap::new();
+
+    let base_url = match file.split('/').next() {
+        Some("cryptocompare") => {
+            let key = first_key("CRYPTOCOMPARE_API_KEY")?;
+            headers.insert("Authorization", HeaderValue::from_str(&format!("Apikey {}", key.to_str()?))?);
+            "https://min-api.cryptocompare.com"
+        }
+        Some("rapidapi") => {
+            headers.insert("x-rapidapi-key", first_key("RAPIDAPI_KEY")?);
+            "https://fear-and-greed-index.p.rapidapi.com"
+        }
+        Some("coinlore") => "https://api.coinlore.net",
+        _ => return Err(format!("Unknown API of fixture {}", file).into()),
+    };
+
+    Ok((base_url, headers))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = reqwest::Client::new();
+
+    for fixture in FIXTURES {
+        let (base_url, headers) = live_api(fixture.file)?;
+
+        let mut request = client
+            .get(format!("{}{}", base_url, fixture.path))
+            .headers(headers)
+            .query(fixture.query);
+        if fixture.path.ends_with("/histoday") {
+            request = request.query(&[("limit", HISTODAY_LIMIT)]);
+        }
+
+        let body = request.send().await?.error_for_status()?.text().await?;
+        std::fs::write(fixtures_dir().join(fixture.file), body)?;
+        println!("Recorded {}", fixture.file);
+    }
+
+    Ok(())
+}
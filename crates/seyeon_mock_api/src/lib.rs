@@ -0,0 +1,230 @@
+//! A local HTTP stand-in for the CryptoCompare, RapidAPI and Coinlore APIs. It serves the
+//! responses of `fixtures/` (see `FIXTURES`) so the clients and the pipeline
+//! built on them can be tested without network access or API keys.
+
+use reqwest::{StatusCode, Url};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// A recorded response and the request it answers.
+pub struct Fixture {
+    /// File under `fixtures/`, named after the API it was recorded from.
+    pub file: &'static str,
+    pub path: &'static str,
+    /// Query parameters a request must have to be answered with this fixture.
+    pub query: &'static [(&'static str, &'static str)],
+}
+
+/// Responses served by `MockApi::with_fixtures`, re-recorded by the `record_fixtures` binary.
+pub const FIXTURES: &[Fixture] = &[
+    Fixture {
+        file: "cryptocompare/histoday_btc.json",
+        path: "/data/v2/histoday",
+        query: &[("fsym", "BTC"), ("tsym", "USD")],
+    },
+    Fixture {
+        file: "cryptocompare/histoday_eth.json",
+        path: "/data/v2/histoday",
+        query: &[("fsym", "ETH"), ("tsym", "USD")],
+    },
+    Fixture {
+        file: "rapidapi/fgi.json",
+        path: "/v1/fgi",
+        query: &[],
+    },
+    Fixture {
+        file: "coinlore/global.json",
+        path: "/api/global/",
+        query: &[],
+    },
+    Fixture {
+        file: "coinlore/tickers.json",
+        path: "/api/tickers/",
+        query: &[],
+    },
+];
+
+pub fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// Body of the fixture `file`, e.g. "cryptocompare/histoday_rate_limit.json".
+pub fn fixture(file: &str) -> String {
+    std::fs::read_to_string(fixtures_dir().join(file))
+        .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", file, e))
+}
+
+/// A request received by a `MockApi`.
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// Header names are lowercase.
+    pub headers: Vec<(String, String)>,
+}
+
+impl ReceivedRequest {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers.iter().find(|(key, _)| *key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Route {
+    path: String,
+    query: Vec<(String, String)>,
+    status: u16,
+    body: String,
+}
+
+impl Route {
+    fn matches(&self, request: &ReceivedRequest) -> bool {
+        self.path == request.path
+            && self.query.iter().all(|(key, value)| request.param(key) == Some(value))
+    }
+}
+
+/// An HTTP server on a random local port, answering GET requests with the body of the
+/// last added route they match and 404 otherwise. Stopped when dropped.
+pub struct MockApi {
+    url: String,
+    routes: Arc<Mutex<Vec<Route>>>,
+    requests: Arc<Mutex<Vec<ReceivedRequest>>>,
+    server: JoinHandle<()>,
+}
+
+impl MockApi {
+    /// A server without routes.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Failed to bind the mock API");
+        let url = format!("http://{}", listener.local_addr().expect("Mock API has no address"));
+        let routes: Arc<Mutex<Vec<Route>>> = Arc::default();
+        let requests: Arc<Mutex<Vec<ReceivedRequest>>> = Arc::default();
+
+        let server = tokio::spawn({
+            let routes = routes.clone();
+            let requests = requests.clone();
+            async move {
+                while let Ok((socket, _)) = listener.accept().await {
+                    tokio::spawn(serve(socket, routes.clone(), requests.clone()));
+                }
+            }
+        });
+
+        Self { url, routes, requests, server }
+    }
+
+    /// A server answering with the recorded `FIXTURES`.
+    pub async fn with_fixtures() -> Self {
+        let api = Self::start().await;
+        for entry in FIXTURES {
+            api.route(entry.path, entry.query, 200, fixture(entry.file));
+        }
+        api
+    }
+
+    /// Base URL of the CryptoCompare and RapidAPI clients.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Base URL of the Coinlore client, whose endpoints live under `/api`.
+    pub fn coinlore_url(&self) -> String {
+        format!("{}/api", self.url)
+    }
+
+    /// Answers GET requests to `path` having the `query` parameters with `status` and
+    /// `body`, ahead of the routes added before.
+    pub fn route(&self, path: &str, query: &[(&str, &str)], status: u16, body: impl Into<String>) {
+        self.routes.lock().unwrap().push(Route {
+            path: path.to_string(),
+            query: query.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+            status,
+            body: body.into(),
+        });
+    }
+
+    /// Requests received so far, in order.
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockApi {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// Reads the head of a request, up to the blank line. Bodies are not read since only
+/// GET requests are served.
+async fn read_head(socket: &mut TcpStream) -> Option<String> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 4096];
+
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = socket.read(&mut buffer).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+
+    String::from_utf8(head).ok()
+}
+
+fn parse_request(head: &str) -> Option<ReceivedRequest> {
+    let mut lines = head.lines();
+    let target = lines.next()?.split_whitespace().nth(1)?;
+    let url = Url::parse(&format!("http://localhost{}", target)).ok()?;
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    Some(ReceivedRequest {
+        path: url.path().to_string(),
+        query: url.query_pairs().map(|(key, value)| (key.into_owned(), value.into_owned())).collect(),
+        headers,
+    })
+}
+
+async fn serve(mut socket: TcpStream, routes: Arc<Mutex<Vec<Route>>>, requests: Arc<Mutex<Vec<ReceivedRequest>>>) {
+    let Some(request) = read_head(&mut socket).await.as_deref().and_then(parse_request) else {
+        return;
+    };
+
+    let (status, body) = routes
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|route| route.matches(&request))
+        .map(|route| (route.status, route.body.clone()))
+        .unwrap_or_else(|| (404, format!(r#"{{"error":"No fixture for {}"}}"#, request.path)));
+    requests.lock().unwrap().push(request);
+
+    let reason = StatusCode::from_u16(status)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or("Unknown");
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+
+    let _ = socket.write_all(response.as_bytes()).await;
+    let _ = socket.shutdown().await;
+}
@@ -0,0 +1,57 @@
+use reqwest::StatusCode;
+use seyeon_mock_api::{FIXTURES, MockApi, fixture};
+
+#[tokio::test]
+async fn serves_every_fixture() {
+    let api = MockApi::with_fixtures().await;
+    let client = reqwest::Client::new();
+
+    for entry in FIXTURES {
+        let response = client
+            .get(format!("{}{}", api.url(), entry.path))
+            .query(entry.query)
+            .send()
+            .await
+            .expect("Mock API unreachable");
+
+        assert_eq!(response.status(), StatusCode::OK, "{}", entry.file);
+        assert_eq!(response.text().await.unwrap(), fixture(entry.file));
+    }
+}
+
+#[tokio::test]
+async fn later_routes_take_precedence_and_unknown_ones_are_not_found() {
+    let api = MockApi::with_fixtures().await;
+    api.route("/data/v2/histoday", &[("fsym", "BTC")], 429, "{}");
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/data/v2/histoday", api.url()))
+        .query(&[("fsym", "BTC"), ("tsym", "USD")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let response = client.get(format!("{}/data/v2/histohour", api.url())).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn records_the_received_requests() {
+    let api = MockApi::start().await;
+
+    reqwest::Client::new()
+        .get(format!("{}/v1/fgi", api.url()))
+        .query(&[("limit", "2")])
+        .header("x-rapidapi-key", "test-key")
+        .send()
+        .await
+        .unwrap();
+
+    let requests = api.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/v1/fgi");
+    assert_eq!(requests[0].param("limit"), Some("2"));
+    assert_eq!(requests[0].header("X-RapidAPI-Key"), Some("test-key"));
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, TimeZone, Utc};
+use seyeon_coinlore::global_market::GlobalMarketData;
+use seyeon_cryptocompare::histoday::CryptoCompareHistodayResponse;
+use seyeon_email::{DailyReport, EmailConfig, FearAndGreedData};
+use seyeon_mock_api::fixture;
+use seyeon_rapidapi::fgi::FearAndGreedIndexResponse;
+use seyeon_redis::{CryptoStatus, TradeAction};
+use seyeon_trading_engine::data_point::DataPoint;
+use seyeon_trading_engine::engine::{Event, Params, Signal, TradingEngine};
+use seyeon_trading_engine::indicators::Indicators;
+
+/// Daily history of `symbol` from its histoday fixture, converted like the CryptoCompare
+/// market data provider does.
+fn history(symbol: &str) -> Vec<DataPoint> {
+    let file = format!("cryptocompare/histoday_{}.json", symbol.to_lowercase());
+    let response: CryptoCompareHistodayResponse =
+        serde_json::from_str(&fixture(&file)).expect("Invalid histoday fixture");
+
+    response
+        .data
+        .expect("Histoday without data")
+        .data
+        .into_iter()
+        .filter_map(|entry| {
+            Some(DataPoint {
+                datetime: DateTime::from_timestamp(entry.time, 0)?,
+                price: entry.close,
+                high: entry.high,
+                low: entry.low,
+                open: entry.open,
+                volume: entry.volumefrom,
+            })
+        })
+        .collect()
+}
+
+fn fgi() -> FearAndGreedData {
+    let response: FearAndGreedIndexResponse =
+        serde_json::from_str(&fixture("rapidapi/fgi.json")).expect("Invalid FGI fixture");
+
+    FearAndGreedData {
+        value: response.fgi.now.value as u8,
+        classification: response.fgi.now.value_text,
+        timestamp: response.last_updated.human_date,
+    }
+}
+
+fn signal(symbol: &str, history: Vec<DataPoint>, fgi: u8) -> Event {
+    let df = Indicators::new(history).calculate().expect("Failed to calculate indicators");
+
+    TradingEngine::new(symbol.to_string(), df, Some(fgi), Params::default())
+        .expect("Failed to create the trading engine")
+        .poll_event()
+        .expect("Failed to poll the latest event")
+}
+
+fn action(signal: &Signal) -> TradeAction {
+    match signal {
+        Signal::Buy => TradeAction::Buy,
+        Signal::Sell => TradeAction::Sell,
+        Signal::Hold => TradeAction::Hold,
+    }
+}
+
+#[test]
+fn recorded_prices_produce_a_signal_alert() {
+    let fgi = fgi();
+    let event = signal("BTC", history("BTC"), fgi.value);
+
+    assert_eq!(event.datetime, Utc.with_ymd_and_hms(2025, 4, 30, 0, 0, 0).unwrap());
+    assert!((0.0..=1.0).contains(&event.confidence));
+
+    let status = CryptoStatus {
+        symbol: "BTC".to_string(),
+        action: action(&event.signal),
+        sent: false,
+    };
+    let alert = EmailConfig::signal_alert_body(&status, None);
+
+    assert!(alert.subject.contains("BTC"));
+    assert!(alert.plain.contains(&format!("{:?}", status.action)));
+    assert!(!alert.plain.contains("Stale data"));
+}
+
+#[test]
+fn recorded_market_data_fills_the_daily_report() {
+    let fgi = fgi();
+    let mut signals = Vec::new();
+    for symbol in ["BTC", "ETH"] {
+        let event = signal(symbol, history(symbol), fgi.value);
+        signals.push((symbol.to_string(), action(&event.signal)));
+    }
+
+    let mut global_market: Vec<GlobalMarketData> =
+        serde_json::from_str(&fixture("coinlore/global.json")).expect("Invalid global market fixture");
+
+    let report = EmailConfig::daily_report_body(DailyReport {
+        signals,
+        fgi: Some(fgi),
+        global_market: Some(global_market.remove(0)),
+        ..Default::default()
+    });
+
+    assert!(report.subject.starts_with("Daily Report"));
+    assert!(report.plain.contains("Bitcoin dominance: 62.49%"));
+    assert!(report.plain.contains("Current value: 54 (Neutral)"));
+    assert!(report.html.contains("ETH"));
+}
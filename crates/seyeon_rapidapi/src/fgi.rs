@@ -40,6 +40,6 @@ pub struct FgiValue {
 pub struct FearAndGreedIndex;
 
 impl Method0 for FearAndGreedIndex {
-    const PATH: &'static str = "https://fear-and-greed-index.p.rapidapi.com/v1/fgi";
+    const PATH: &'static str = "/v1/fgi";
     type Response = FearAndGreedIndexResponse;
 }
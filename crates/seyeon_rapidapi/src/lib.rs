@@ -2,16 +2,27 @@ pub mod fgi;
 mod method;
 
 use reqwest::header::{ACCEPT, CONTENT_TYPE, HeaderMap, HeaderValue};
-use reqwest::{Client, ClientBuilder};
 use serde::de::DeserializeOwned;
+use seyeon_http::{HttpClient, base_url_from_env};
+
+/// Public Fear & Greed Index API on RapidAPI, prepended to the `Method0::PATH`s.
+pub const DEFAULT_BASE_URL: &str = "https://fear-and-greed-index.p.rapidapi.com";
 
 pub struct RapidApiClient {
     pub api_key: String,
-    reqwest: Client,
+    http: HttpClient,
+    base_url: String,
 }
 
 impl RapidApiClient {
+    /// A client of `DEFAULT_BASE_URL`, or of `RAPIDAPI_BASE_URL` when set (e.g. a proxy or a
+    /// caching gateway).
     pub fn new(api_key: &str) -> Self {
+        Self::with_base_url(api_key, &base_url_from_env("RAPIDAPI_BASE_URL", DEFAULT_BASE_URL))
+    }
+
+    /// A client sending its requests to `base_url` instead of the public API.
+    pub fn with_base_url(api_key: &str, base_url: &str) -> Self {
         let mut default_headers = HeaderMap::new();
         default_headers.insert(
             "x-rapidapi-key",
@@ -21,25 +32,18 @@ impl RapidApiClient {
         default_headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
         default_headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
-        let reqwest = ClientBuilder::new()
-            .default_headers(default_headers)
-            .build()
-            .expect("Failed to build reqwest client");
-
         Self {
             api_key: api_key.to_string(),
-            reqwest,
+            http: HttpClient::new(default_headers),
+            base_url: base_url.trim_end_matches('/').to_string(),
         }
     }
 
     pub(crate) async fn get0<R: DeserializeOwned>(&self, url: &str) -> reqwest::Result<R> {
-        let response = self.reqwest.get(url).send().await?;
-        let response = response.error_for_status()?;
-
-        response.json().await
+        self.http.get_json(url, &()).await
     }
 
     pub async fn call0<M: method::Method0>(&self) -> reqwest::Result<M::Response> {
-        self.get0(M::PATH).await
+        self.get0(&format!("{}{}", self.base_url, M::PATH)).await
     }
 }
@@ -3,6 +3,7 @@ use seyeon_rapidapi::fgi::FearAndGreedIndex;
 use tokio::test;
 
 #[test]
+#[ignore = "calls the live RapidAPI with $RAPIDAPI_KEY"]
 pub async fn fetch_fear_and_greed_index() {
     let client = RapidApiClient::new(
         std::env::var("RAPIDAPI_KEY")
@@ -17,3 +18,36 @@ pub async fn fetch_fear_and_greed_index() {
 
     println!("{response:?}");
 }
+
+#[test]
+pub async fn fear_and_greed_index_from_mock_api() {
+    let api = seyeon_mock_api::MockApi::with_fixtures().await;
+    let client = RapidApiClient::with_base_url("test-key", api.url());
+
+    let response = client
+        .call0::<FearAndGreedIndex>()
+        .await
+        .expect("Failed to fetch fear and greed index");
+
+    assert_eq!(response.fgi.now.value, 54);
+    assert_eq!(response.fgi.now.value_text, "Neutral");
+    assert_eq!(api.requests()[0].header("x-rapidapi-key"), Some("test-key"));
+}
+
+#[test]
+pub async fn new_uses_the_base_url_of_the_environment() {
+    let api = seyeon_mock_api::MockApi::with_fixtures().await;
+    // Only read by `fetch_fear_and_greed_index` otherwise, which is ignored by default;
+    // removed below so that running it alongside still reaches the live API.
+    unsafe { std::env::set_var("RAPIDAPI_BASE_URL", api.url()) };
+    let client = RapidApiClient::new("test-key");
+    unsafe { std::env::remove_var("RAPIDAPI_BASE_URL") };
+
+    let response = client
+        .call0::<FearAndGreedIndex>()
+        .await
+        .expect("Failed to fetch fear and greed index");
+
+    assert_eq!(response.fgi.now.value, 54);
+    assert_eq!(api.requests()[0].path, "/v1/fgi");
+}
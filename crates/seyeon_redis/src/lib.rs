@@ -1,5 +1,5 @@
 pub mod models;
 pub mod operations;
 
-pub use models::{CryptoStatus, TradeAction, ReportStatus};
-pub use operations::{get_status, set_status, get_report_status, set_report_status, update_report_status};
+pub use models::{CryptoStatus, TradeAction, ReportStatus, DominanceSnapshot, Holding, PaperAccount, PaperTrade, OrderUsage, PendingApproval, SignalRecord, ApiKeyUsage};
+pub use operations::{get_status, set_status, get_report_status, set_report_status, update_report_status, record_btc_dominance, get_btc_dominance_history, get_holdings, set_holdings, get_last_rebalance, set_last_rebalance, get_paper_account, set_paper_account, record_paper_trade, get_paper_ledger, is_kill_switch_active, set_kill_switch, get_order_usage, record_order, add_pending_approval, get_pending_approvals, approve_pending, remove_pending_approval, record_signal, get_signal_records, get_api_key_usage, set_api_key_usage, record_api_key_call, get_api_key_calls};
@@ -1,4 +1,6 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -45,3 +47,132 @@ impl Default for ReportStatus {
         }
     }
 }
+
+/// Daily snapshot of the global BTC dominance, used to build its history since
+/// Coinlore only reports the current value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DominanceSnapshot {
+    pub date: String,
+    pub btc_dominance: f64,
+}
+
+/// A position actually held, as declared in `holdings.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Holding {
+    pub symbol: String,
+    pub quantity: f64,
+    pub average_cost: f64,
+    #[serde(default)]
+    pub acquisition_dates: Vec<NaiveDate>,
+}
+
+/// Paper-trading account of a single asset, following its Buy/Sell signals.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PaperAccount {
+    pub symbol: String,
+    pub initial_capital: f64,
+    pub cash: f64,
+    pub quantity: f64,
+    pub average_cost: f64,
+    /// Cash spent on the open position, fees included.
+    pub invested: f64,
+    pub realized_pnl: f64,
+    pub fees_paid: f64,
+    pub trades: usize,
+    /// Close time of the last bar a trade was executed on.
+    pub last_trade_at: Option<DateTime<Utc>>,
+}
+
+impl PaperAccount {
+    pub fn new(symbol: &str, initial_capital: f64) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            initial_capital,
+            cash: initial_capital,
+            quantity: 0.0,
+            average_cost: 0.0,
+            invested: 0.0,
+            realized_pnl: 0.0,
+            fees_paid: 0.0,
+            trades: 0,
+            last_trade_at: None,
+        }
+    }
+}
+
+/// An executed paper trade, as stored in the ledger.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PaperTrade {
+    pub symbol: String,
+    pub action: TradeAction,
+    pub datetime: DateTime<Utc>,
+    pub price: f64,
+    pub quantity: f64,
+    /// Cash spent (buys) or gross value sold (sells).
+    pub notional: f64,
+    pub fee: f64,
+    /// Profit booked by a sale, net of all fees; zero for buys.
+    pub realized_pnl: f64,
+}
+
+/// Orders executed on a given day, used to enforce the daily guardrails.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderUsage {
+    pub orders: u32,
+    pub total_notional: f64,
+    pub asset_notional: HashMap<String, f64>,
+}
+
+/// An order held back until it is approved with its token.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PendingApproval {
+    pub token: String,
+    pub symbol: String,
+    pub action: TradeAction,
+    pub quantity: f64,
+    pub price: f64,
+    pub notional: f64,
+    pub created_at: DateTime<Utc>,
+    pub approved: bool,
+}
+
+/// Quota of an API key as last reported by its provider. Keys are stored by fingerprint,
+/// never in clear.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiKeyUsage {
+    /// Calls left in the tightest reported period, if the provider reports it. Calls
+    /// made are counted separately, with `record_api_key_call`.
+    pub calls_left: Option<i64>,
+    /// The key is not used before this time after hitting a limit.
+    pub quarantined_until: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ApiKeyUsage {
+    pub fn is_quarantined(&self, now: DateTime<Utc>) -> bool {
+        self.quarantined_until.is_some_and(|until| until > now)
+    }
+}
+
+/// A Buy/Sell alert and how the price moved after it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignalRecord {
+    pub symbol: String,
+    pub action: TradeAction,
+    /// Close of the bar the signal was emitted on.
+    pub price: f64,
+    pub confidence: f64,
+    /// Date of that bar.
+    pub datetime: DateTime<Utc>,
+    /// Return from `price` (0.05 = +5%) by horizon in days, filled in once the horizon
+    /// has passed.
+    #[serde(default)]
+    pub forward_returns: BTreeMap<u32, f64>,
+}
+
+impl SignalRecord {
+    /// Field of the record in the signals hash; one record per symbol and bar.
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.symbol.to_uppercase(), self.datetime.timestamp())
+    }
+}
@@ -1,9 +1,22 @@
-use crate::models::{CryptoStatus, ReportStatus};
+use crate::models::{ApiKeyUsage, CryptoStatus, DominanceSnapshot, Holding, OrderUsage, PaperAccount, PaperTrade, PendingApproval, ReportStatus, SignalRecord};
 use redis::{AsyncCommands, Client, RedisError};
 use serde_json::{from_str, to_string};
+use std::collections::HashMap;
 use std::env;
 
 const REPORT_STATUS_KEY: &str = "seyeon:report_status";
+const BTC_DOMINANCE_KEY: &str = "seyeon:btc_dominance";
+const HOLDINGS_KEY: &str = "seyeon:holdings";
+const LAST_REBALANCE_KEY: &str = "seyeon:last_rebalance";
+const PAPER_ACCOUNTS_KEY: &str = "seyeon:paper:accounts";
+const PAPER_LEDGER_KEY: &str = "seyeon:paper:ledger";
+const KILL_SWITCH_KEY: &str = "seyeon:kill_switch";
+const ORDER_USAGE_PREFIX: &str = "seyeon:orders";
+const APPROVALS_KEY: &str = "seyeon:approvals";
+const SIGNALS_KEY: &str = "seyeon:signals";
+const API_KEYS_PREFIX: &str = "seyeon:api_keys";
+/// Daily order counters are kept a few days for inspection.
+const ORDER_USAGE_TTL_SECONDS: i64 = 7 * 24 * 3600;
 
 fn get_redis_url() -> String {
     env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".to_string())
@@ -84,3 +97,330 @@ pub async fn update_report_status(date: &str, sent: bool) -> Result<(), RedisErr
     set_report_status(&status).await?;
     Ok(())
 }
+
+pub async fn record_btc_dominance(date: &str, btc_dominance: f64) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let _: () = connection.hset(BTC_DOMINANCE_KEY, date, btc_dominance).await?;
+
+    Ok(())
+}
+
+/// Returns every recorded dominance snapshot, oldest first.
+pub async fn get_btc_dominance_history() -> Result<Vec<DominanceSnapshot>, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let entries: Vec<(String, f64)> = connection.hgetall(BTC_DOMINANCE_KEY).await?;
+
+    let mut history: Vec<DominanceSnapshot> = entries
+        .into_iter()
+        .map(|(date, btc_dominance)| DominanceSnapshot { date, btc_dominance })
+        .collect();
+    history.sort_by(|a, b| a.date.cmp(&b.date));
+
+    Ok(history)
+}
+
+pub async fn get_holdings() -> Result<Vec<Holding>, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let data: Option<String> = connection.get(HOLDINGS_KEY).await?;
+
+    match data {
+        Some(data) => serde_json::from_str(&data)
+            .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string()))),
+        None => Ok(Vec::new()),
+    }
+}
+
+pub async fn set_holdings(holdings: &[Holding]) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let data = serde_json::to_string(holdings)
+        .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))?;
+
+    let _: () = connection.set(HOLDINGS_KEY, data).await?;
+
+    Ok(())
+}
+
+/// Date (YYYY-MM-DD) of the last suggested calendar rebalance, if any.
+pub async fn get_last_rebalance() -> Result<Option<String>, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    connection.get(LAST_REBALANCE_KEY).await
+}
+
+pub async fn set_last_rebalance(date: &str) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let _: () = connection.set(LAST_REBALANCE_KEY, date).await?;
+
+    Ok(())
+}
+
+pub async fn get_paper_account(symbol: &str) -> Result<Option<PaperAccount>, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let data: Option<String> = connection.hget(PAPER_ACCOUNTS_KEY, symbol).await?;
+
+    data.map(|data| {
+        serde_json::from_str(&data)
+            .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))
+    })
+    .transpose()
+}
+
+pub async fn set_paper_account(account: &PaperAccount) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let data = serde_json::to_string(account)
+        .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))?;
+
+    let _: () = connection.hset(PAPER_ACCOUNTS_KEY, &account.symbol, data).await?;
+
+    Ok(())
+}
+
+pub async fn record_paper_trade(trade: &PaperTrade) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let data = serde_json::to_string(trade)
+        .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))?;
+
+    let _: () = connection.rpush(PAPER_LEDGER_KEY, data).await?;
+
+    Ok(())
+}
+
+/// Returns every recorded paper trade, oldest first.
+pub async fn get_paper_ledger() -> Result<Vec<PaperTrade>, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let entries: Vec<String> = connection.lrange(PAPER_LEDGER_KEY, 0, -1).await?;
+
+    entries
+        .iter()
+        .map(|data| {
+            serde_json::from_str(data)
+                .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))
+        })
+        .collect()
+}
+
+/// True while the kill switch key is set to anything but "0", "false" or "off".
+pub async fn is_kill_switch_active() -> Result<bool, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let value: Option<String> = connection.get(KILL_SWITCH_KEY).await?;
+
+    Ok(value.is_some_and(|value| {
+        !matches!(value.trim().to_lowercase().as_str(), "" | "0" | "false" | "off")
+    }))
+}
+
+pub async fn set_kill_switch(active: bool) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    if active {
+        let _: () = connection.set(KILL_SWITCH_KEY, "1").await?;
+    } else {
+        let _: () = connection.del(KILL_SWITCH_KEY).await?;
+    }
+
+    Ok(())
+}
+
+/// Orders recorded on `date` (YYYY-MM-DD).
+pub async fn get_order_usage(date: &str) -> Result<OrderUsage, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let entries: Vec<(String, String)> = connection.hgetall(format!("{}:{}", ORDER_USAGE_PREFIX, date)).await?;
+
+    let mut usage = OrderUsage::default();
+    for (field, value) in entries {
+        match field.as_str() {
+            "orders" => usage.orders = value.parse().unwrap_or(0),
+            "total" => usage.total_notional = value.parse().unwrap_or(0.0),
+            symbol => {
+                usage.asset_notional.insert(symbol.to_string(), value.parse().unwrap_or(0.0));
+            }
+        }
+    }
+
+    Ok(usage)
+}
+
+pub async fn record_order(date: &str, symbol: &str, notional: f64) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+    let key = format!("{}:{}", ORDER_USAGE_PREFIX, date);
+
+    redis::pipe()
+        .atomic()
+        .hincr(&key, "orders", 1)
+        .hincr(&key, "total", notional)
+        .hincr(&key, symbol.to_uppercase(), notional)
+        .expire(&key, ORDER_USAGE_TTL_SECONDS)
+        .query_async(&mut connection)
+        .await
+}
+
+pub async fn add_pending_approval(approval: &PendingApproval) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let data = serde_json::to_string(approval)
+        .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))?;
+
+    let _: () = connection.hset(APPROVALS_KEY, &approval.token, data).await?;
+
+    Ok(())
+}
+
+pub async fn get_pending_approvals() -> Result<Vec<PendingApproval>, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let entries: Vec<(String, String)> = connection.hgetall(APPROVALS_KEY).await?;
+
+    let mut approvals = entries
+        .iter()
+        .map(|(_, data)| {
+            serde_json::from_str(data)
+                .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))
+        })
+        .collect::<Result<Vec<PendingApproval>, RedisError>>()?;
+    approvals.sort_by_key(|approval| approval.created_at);
+
+    Ok(approvals)
+}
+
+/// Marks the pending order of `token` as approved. Returns `None` for an unknown token.
+pub async fn approve_pending(token: &str) -> Result<Option<PendingApproval>, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let data: Option<String> = connection.hget(APPROVALS_KEY, token).await?;
+    let Some(data) = data else {
+        return Ok(None);
+    };
+
+    let mut approval: PendingApproval = serde_json::from_str(&data)
+        .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))?;
+    approval.approved = true;
+    add_pending_approval(&approval).await?;
+
+    Ok(Some(approval))
+}
+
+pub async fn remove_pending_approval(token: &str) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let _: () = connection.hdel(APPROVALS_KEY, token).await?;
+
+    Ok(())
+}
+
+/// Stores `record`, replacing the record of the same symbol and bar if there is one.
+pub async fn record_signal(record: &SignalRecord) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let data = serde_json::to_string(record)
+        .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))?;
+
+    let _: () = connection.hset(SIGNALS_KEY, record.key(), data).await?;
+
+    Ok(())
+}
+
+/// Returns every recorded signal, oldest first.
+pub async fn get_signal_records() -> Result<Vec<SignalRecord>, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let entries: Vec<(String, String)> = connection.hgetall(SIGNALS_KEY).await?;
+
+    let mut records = entries
+        .iter()
+        .map(|(_, data)| {
+            serde_json::from_str(data)
+                .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))
+        })
+        .collect::<Result<Vec<SignalRecord>, RedisError>>()?;
+    records.sort_by_key(|record| record.datetime);
+
+    Ok(records)
+}
+
+/// Usage of the keys of `pool` (e.g. `CRYPTOCOMPARE_API_KEY`), by key fingerprint.
+pub async fn get_api_key_usage(pool: &str) -> Result<HashMap<String, ApiKeyUsage>, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let entries: Vec<(String, String)> = connection.hgetall(format!("{}:{}", API_KEYS_PREFIX, pool)).await?;
+
+    entries
+        .into_iter()
+        .map(|(fingerprint, data)| {
+            serde_json::from_str(&data)
+                .map(|usage| (fingerprint, usage))
+                .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))
+        })
+        .collect()
+}
+
+pub async fn set_api_key_usage(pool: &str, fingerprint: &str, usage: &ApiKeyUsage) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let data = serde_json::to_string(usage)
+        .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Serde error", e.to_string())))?;
+
+    let _: () = connection.hset(format!("{}:{}", API_KEYS_PREFIX, pool), fingerprint, data).await?;
+
+    Ok(())
+}
+
+fn api_key_calls_key(pool: &str, window: &str) -> String {
+    format!("{}:{}:calls:{}", API_KEYS_PREFIX, pool, window)
+}
+
+/// Counts a call of a key in each of `windows`, given as `(window, seconds_left)`; the
+/// counter of a window expires with it.
+pub async fn record_api_key_call(pool: &str, fingerprint: &str, windows: &[(String, i64)]) -> Result<(), RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    for (window, seconds_left) in windows {
+        let key = api_key_calls_key(pool, window);
+        pipe.hincr(&key, fingerprint, 1).ignore().expire(&key, *seconds_left).ignore();
+    }
+
+    pipe.query_async(&mut connection).await
+}
+
+/// Calls counted per key fingerprint in a window of `record_api_key_call`.
+pub async fn get_api_key_calls(pool: &str, window: &str) -> Result<HashMap<String, i64>, RedisError> {
+    let client = get_client().await?;
+    let mut connection = client.get_async_connection().await?;
+
+    connection.hgetall(api_key_calls_key(pool, window)).await
+}
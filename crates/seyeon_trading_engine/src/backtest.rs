@@ -0,0 +1,372 @@
+use crate::engine::{Position, Trade, TradeType};
+use crate::error::TradingEngineError;
+use chrono::{DateTime, TimeZone, Utc};
+use polars::prelude::*;
+use std::collections::VecDeque;
+
+/// One day of market data with the indicator values the strategy reads. Missing values
+/// (warm-up periods) are NaN, and so compare as false.
+#[derive(Debug, Clone, Copy)]
+pub struct Bar {
+    pub idx: usize,
+    pub datetime: DateTime<Utc>,
+    pub price: f64,
+    pub ma5: f64,
+    pub ma25: f64,
+    pub ma50: f64,
+    pub ma111: f64,
+    pub macd: f64,
+    pub signal: f64,
+    pub rsi: f64,
+    pub roc: f64,
+    pub lower_band: f64,
+    pub pi_cycle_top: f64,
+    /// Cross-asset features, only present for altcoins with BTC columns attached.
+    pub btc_rs: Option<f64>,
+    pub btc_beta: Option<f64>,
+    pub btc_trend: Option<f64>,
+    pub btc_dominance_change: Option<f64>,
+    /// False while the core indicators are still warming up (the `ready` column).
+    pub ready: bool,
+}
+
+impl Bar {
+    /// Names of the indicators the strategy cannot do without that are still null. The
+    /// Pi Cycle inputs are only required when `pi_cycle` is set (BTC).
+    pub fn missing_indicators(&self, pi_cycle: bool) -> Vec<&'static str> {
+        let mut required = vec![
+            ("price", self.price),
+            ("ma5", self.ma5),
+            ("ma25", self.ma25),
+            ("ma50", self.ma50),
+            ("macd", self.macd),
+            ("signal", self.signal),
+            ("rsi", self.rsi),
+            ("roc", self.roc),
+            ("lower_band", self.lower_band),
+        ];
+        if pi_cycle {
+            required.push(("ma111", self.ma111));
+            required.push(("pi_cycle_top", self.pi_cycle_top));
+        }
+
+        required
+            .into_iter()
+            .filter(|(_, value)| !value.is_finite())
+            .map(|(name, _)| name)
+            .collect()
+    }
+}
+
+/// Indicator columns extracted from the final dataframe into plain vectors.
+#[derive(Debug, Clone, Default)]
+pub struct BarSeries {
+    datetime: Vec<DateTime<Utc>>,
+    price: Vec<f64>,
+    ma5: Vec<f64>,
+    ma25: Vec<f64>,
+    ma50: Vec<f64>,
+    ma111: Vec<f64>,
+    macd: Vec<f64>,
+    signal: Vec<f64>,
+    rsi: Vec<f64>,
+    roc: Vec<f64>,
+    lower_band: Vec<f64>,
+    pi_cycle_top: Vec<f64>,
+    btc_rs: Option<Vec<f64>>,
+    btc_beta: Option<Vec<f64>>,
+    btc_trend: Option<Vec<f64>>,
+    btc_dominance_change: Option<Vec<f64>>,
+    ready: Option<Vec<bool>>,
+}
+
+impl BarSeries {
+    pub fn from_df(df: &DataFrame) -> Result<Self, TradingEngineError> {
+        let column = |name: &str| {
+            df.column(name).map_err(|e| match e {
+                PolarsError::ColumnNotFound(_) => TradingEngineError::MissingColumn(name.to_string()),
+                e => e.into(),
+            })
+        };
+        let required = |name: &str| -> Result<Vec<f64>, TradingEngineError> {
+            Ok(column(name)?
+                .f64()?
+                .into_iter()
+                .map(|value| value.unwrap_or(f64::NAN))
+                .collect())
+        };
+        let optional = |name: &str| required(name).ok();
+
+        let datetime = column("datetime")?
+            .i64()?
+            .into_iter()
+            .enumerate()
+            .map(|(idx, ms)| {
+                ms.and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+                    .ok_or(TradingEngineError::InvalidTimestamp(idx))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            datetime,
+            price: required("price")?,
+            ma5: required("ma5")?,
+            ma25: required("ma25")?,
+            ma50: required("ma50")?,
+            ma111: required("ma111")?,
+            macd: required("macd")?,
+            signal: required("signal")?,
+            rsi: required("rsi")?,
+            roc: required("roc")?,
+            lower_band: required("lower_band")?,
+            pi_cycle_top: required("pi_cycle_top")?,
+            btc_rs: optional("btc_rs"),
+            btc_beta: optional("btc_beta"),
+            btc_trend: optional("btc_trend"),
+            btc_dominance_change: optional("btc_dominance_change"),
+            ready: match df.column("ready") {
+                Ok(ready) => Some(
+                    ready
+                        .bool()?
+                        .into_iter()
+                        .map(|value| value.unwrap_or(false))
+                        .collect(),
+                ),
+                Err(_) => None,
+            },
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.price.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.price.is_empty()
+    }
+
+    /// Panics if `idx` is out of range; see `get` for a checked version.
+    pub fn bar(&self, idx: usize) -> Bar {
+        let optional = |column: &Option<Vec<f64>>| {
+            column
+                .as_ref()
+                .map(|values| values[idx])
+                .filter(|value| value.is_finite())
+        };
+
+        Bar {
+            idx,
+            datetime: self.datetime[idx],
+            price: self.price[idx],
+            ma5: self.ma5[idx],
+            ma25: self.ma25[idx],
+            ma50: self.ma50[idx],
+            ma111: self.ma111[idx],
+            macd: self.macd[idx],
+            signal: self.signal[idx],
+            rsi: self.rsi[idx],
+            roc: self.roc[idx],
+            lower_band: self.lower_band[idx],
+            pi_cycle_top: self.pi_cycle_top[idx],
+            btc_rs: optional(&self.btc_rs),
+            btc_beta: optional(&self.btc_beta),
+            btc_trend: optional(&self.btc_trend),
+            btc_dominance_change: optional(&self.btc_dominance_change),
+            ready: self.ready.as_ref().is_none_or(|ready| ready[idx]),
+        }
+    }
+
+    pub fn get(&self, idx: usize) -> Option<Bar> {
+        (idx < self.len()).then(|| self.bar(idx))
+    }
+
+    /// Yields the bars from `start` to the most recent one.
+    pub fn iter_from(&self, start: usize) -> impl Iterator<Item = Bar> + '_ {
+        (start..self.len()).map(|idx| self.bar(idx))
+    }
+
+    pub fn last(&self) -> Option<Bar> {
+        self.len().checked_sub(1).map(|idx| self.bar(idx))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Order {
+    /// Opens a position with a fraction of the available cash.
+    Enter { fraction: f64 },
+    /// Adds a fraction of the available cash to the open position.
+    DcaBuy { fraction: f64 },
+    /// Sells a fraction of the open position.
+    PartialSell { fraction: f64 },
+    /// Closes the open position.
+    FullSell,
+    /// Closes whatever is still open when the backtest ends.
+    FinalSell,
+}
+
+/// Orders waiting to be filled, in submission order.
+#[derive(Debug, Default)]
+pub struct OrderQueue {
+    orders: VecDeque<Order>,
+}
+
+impl OrderQueue {
+    pub fn submit(&mut self, order: Order) {
+        self.orders.push_back(order);
+    }
+
+    pub fn pop(&mut self) -> Option<Order> {
+        self.orders.pop_front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+}
+
+/// An executed order.
+#[derive(Debug, Clone)]
+pub struct Fill {
+    pub trade_type: TradeType,
+    pub datetime: DateTime<Utc>,
+    pub price: f64,
+    pub amount: f64,
+    /// Cash spent (buys) or gross value sold (sells), fees included.
+    pub notional: f64,
+    pub fee: f64,
+}
+
+/// Fills orders at the bar's close, charging a proportional fee.
+#[derive(Debug, Clone)]
+pub struct Broker {
+    pub fee_rate: f64,
+}
+
+impl Broker {
+    /// Sizes `order` against the current portfolio. Returns `None` when there is nothing
+    /// to buy with or sell.
+    pub fn execute(&self, order: Order, bar: &Bar, portfolio: &Portfolio) -> Option<Fill> {
+        let price = bar.price;
+
+        match order {
+            Order::Enter { fraction } | Order::DcaBuy { fraction } => {
+                let investment = portfolio.cash * fraction;
+                if investment <= 0.0 {
+                    return None;
+                }
+
+                let fee = investment * self.fee_rate;
+                Some(Fill {
+                    trade_type: match order {
+                        Order::Enter { .. } => TradeType::Buy,
+                        _ => TradeType::DcaBuy,
+                    },
+                    datetime: bar.datetime,
+                    price,
+                    amount: (investment - fee) / price,
+                    notional: investment,
+                    fee,
+                })
+            }
+            Order::PartialSell { .. } | Order::FullSell | Order::FinalSell => {
+                let position = portfolio.position.as_ref()?;
+                let (trade_type, amount, price) = match order {
+                    Order::PartialSell { fraction } => {
+                        (TradeType::PartialSell, position.amount * fraction, price)
+                    }
+                    Order::FullSell => (TradeType::FullSell, position.amount, price),
+                    _ => (TradeType::FinalSell, position.amount, price),
+                };
+
+                let value = amount * price;
+                Some(Fill {
+                    trade_type,
+                    datetime: bar.datetime,
+                    price,
+                    amount,
+                    notional: value,
+                    fee: value * self.fee_rate,
+                })
+            }
+        }
+    }
+}
+
+/// Cash, holdings, open position and trade ledger of a backtest.
+#[derive(Debug, Clone)]
+pub struct Portfolio {
+    pub cash: f64,
+    pub held: f64,
+    pub position: Option<Position>,
+    pub trade_history: Vec<Trade>,
+    /// Mark-to-market portfolio value at the close of each simulated day.
+    pub equity_curve: Vec<(DateTime<Utc>, f64)>,
+}
+
+impl Portfolio {
+    pub fn new(initial_capital: f64) -> Self {
+        Self {
+            cash: initial_capital,
+            held: 0.0,
+            position: None,
+            trade_history: Vec::new(),
+            equity_curve: Vec::new(),
+        }
+    }
+
+    /// Books a fill: moves cash and holdings, updates the position and logs the trade.
+    pub fn apply(&mut self, fill: &Fill) {
+        match fill.trade_type {
+            TradeType::Buy => {
+                self.cash -= fill.notional;
+                self.held += fill.amount;
+                self.position = Some(Position {
+                    avg_price: fill.price,
+                    amount: fill.amount,
+                    investment: fill.notional,
+                    entry_time: fill.datetime,
+                });
+            }
+            TradeType::DcaBuy => {
+                if let Some(position) = &mut self.position {
+                    let total_amount = position.amount + fill.amount;
+                    position.avg_price = ((position.avg_price * position.amount)
+                        + (fill.price * fill.amount))
+                        / total_amount;
+                    position.amount = total_amount;
+                    position.investment += fill.notional;
+                }
+                self.cash -= fill.notional;
+                self.held += fill.amount;
+            }
+            TradeType::PartialSell => {
+                self.cash += fill.notional - fill.fee;
+                self.held -= fill.amount;
+                if let Some(position) = &mut self.position {
+                    position.amount -= fill.amount;
+                }
+            }
+            TradeType::FullSell | TradeType::FinalSell => {
+                self.cash += fill.notional - fill.fee;
+                self.held -= fill.amount;
+                self.position = None;
+            }
+        }
+
+        self.trade_history.push(Trade {
+            trade_type: fill.trade_type.clone(),
+            datetime: fill.datetime,
+            price: fill.price,
+            amount: fill.amount,
+        });
+    }
+
+    pub fn value(&self, price: f64) -> f64 {
+        self.cash + self.held * price
+    }
+
+    /// Records the end-of-day value of the portfolio.
+    pub fn mark(&mut self, bar: &Bar) {
+        self.equity_curve.push((bar.datetime, self.value(bar.price)));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::engine::{Params, Summary};
+use crate::robustness::max_drawdown;
+use polars::prelude::*;
+use std::fmt;
+use std::str::FromStr;
+
+/// Days between two installments of the periodic DCA benchmark.
+pub const DCA_INTERVAL_DAYS: usize = 30;
+pub const SMA_FAST: usize = 50;
+pub const SMA_SLOW: usize = 200;
+pub const RSI_OVERSOLD: f64 = 30.0;
+pub const RSI_OVERBOUGHT: f64 = 70.0;
+
+/// Simple reference strategies to compare the default voting strategy against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Benchmark {
+    /// Invests all the capital on the first day and holds it.
+    BuyAndHold,
+    /// Invests equal installments every `DCA_INTERVAL_DAYS` and holds them.
+    Dca,
+    /// Fully invested while the 50-day SMA is above the 200-day SMA, in cash otherwise.
+    SmaCrossover,
+    /// Buys when the RSI falls below 30 and sells when it rises above 70.
+    RsiMeanReversion,
+}
+
+impl Benchmark {
+    pub const ALL: [Benchmark; 4] = [
+        Benchmark::BuyAndHold,
+        Benchmark::Dca,
+        Benchmark::SmaCrossover,
+        Benchmark::RsiMeanReversion,
+    ];
+}
+
+impl fmt::Display for Benchmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Benchmark::BuyAndHold => write!(f, "Buy & Hold"),
+            Benchmark::Dca => write!(f, "Periodic DCA"),
+            Benchmark::SmaCrossover => write!(f, "SMA {SMA_FAST}/{SMA_SLOW}"),
+            Benchmark::RsiMeanReversion => write!(f, "RSI Reversion"),
+        }
+    }
+}
+
+impl FromStr for Benchmark {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "buy-and-hold" | "hold" => Ok(Benchmark::BuyAndHold),
+            "dca" => Ok(Benchmark::Dca),
+            "sma-crossover" | "sma" => Ok(Benchmark::SmaCrossover),
+            "rsi-mean-reversion" | "rsi" => Ok(Benchmark::RsiMeanReversion),
+            other => Err(format!("Unknown strategy: {other}")),
+        }
+    }
+}
+
+/// Cash and holdings of a benchmark run, charged the same fee as the engine.
+struct Account {
+    cash: f64,
+    held: f64,
+    trades: usize,
+    fees: f64,
+    fee_rate: f64,
+    equity: Vec<f64>,
+}
+
+impl Account {
+    fn buy(&mut self, notional: f64, price: f64) {
+        let notional = notional.min(self.cash);
+        if notional <= 0.0 {
+            return;
+        }
+
+        let fee = notional * self.fee_rate;
+        self.cash -= notional;
+        self.held += (notional - fee) / price;
+        self.fees += fee;
+        self.trades += 1;
+    }
+
+    fn sell_all(&mut self, price: f64) {
+        if self.held <= 0.0 {
+            return;
+        }
+
+        let value = self.held * price;
+        let fee = value * self.fee_rate;
+        self.cash += value - fee;
+        self.held = 0.0;
+        self.fees += fee;
+        self.trades += 1;
+    }
+}
+
+/// Runs `benchmark` over the last `days` rows of an indicators dataframe, starting from
+/// `params.initial_capital` and paying `params.generic_fee` on every trade. Like the
+/// engine's closing sale, a position still open is sold at the last close.
+pub fn run(benchmark: Benchmark, df: &DataFrame, params: &Params, days: usize) -> PolarsResult<Summary> {
+    let prices: Vec<f64> = df
+        .column("price")?
+        .f64()?
+        .into_iter()
+        .map(|price| price.unwrap_or(f64::NAN))
+        .collect();
+    let start = prices.len().saturating_sub(days);
+
+    let mut account = Account {
+        cash: params.initial_capital,
+        held: 0.0,
+        trades: 0,
+        fees: 0.0,
+        fee_rate: params.generic_fee,
+        equity: Vec::with_capacity(prices.len() - start),
+    };
+
+    let rsi = match benchmark {
+        Benchmark::RsiMeanReversion => Some(df.column("rsi")?.f64()?.clone()),
+        _ => None,
+    };
+    let installments = (prices.len() - start).div_ceil(DCA_INTERVAL_DAYS).max(1);
+    let installment = params.initial_capital / installments as f64;
+
+    for idx in start..prices.len() {
+        let price = prices[idx];
+        if !price.is_finite() || price <= 0.0 {
+            continue;
+        }
+
+        match benchmark {
+            Benchmark::BuyAndHold => {
+                if account.held == 0.0 {
+                    account.buy(account.cash, price);
+                }
+            }
+            Benchmark::Dca => {
+                if (idx - start).is_multiple_of(DCA_INTERVAL_DAYS) {
+                    account.buy(installment, price);
+                }
+            }
+            Benchmark::SmaCrossover => {
+                if let (Some(fast), Some(slow)) = (sma(&prices, idx, SMA_FAST), sma(&prices, idx, SMA_SLOW)) {
+                    if fast > slow && account.held == 0.0 {
+                        account.buy(account.cash, price);
+                    } else if fast < slow {
+                        account.sell_all(price);
+                    }
+                }
+            }
+            Benchmark::RsiMeanReversion => {
+                let value = rsi.as_ref().and_then(|rsi| rsi.get(idx)).filter(|v| v.is_finite());
+                if let Some(value) = value {
+                    if value < RSI_OVERSOLD && account.held == 0.0 {
+                        account.buy(account.cash, price);
+                    } else if value > RSI_OVERBOUGHT {
+                        account.sell_all(price);
+                    }
+                }
+            }
+        }
+
+        account.equity.push(account.cash + account.held * price);
+    }
+
+    if let Some(&price) = prices[start..].iter().rev().find(|price| price.is_finite() && **price > 0.0) {
+        account.sell_all(price);
+        account.equity.push(account.cash);
+    }
+    let final_portfolio_value = account.equity.last().copied().unwrap_or(params.initial_capital);
+
+    Ok(Summary {
+        initial_capital: params.initial_capital,
+        final_portfolio_value,
+        roi: (final_portfolio_value - params.initial_capital) / params.initial_capital * 100.0,
+        num_trades: account.trades,
+        estimated_fees_paid: account.fees,
+        max_drawdown: max_drawdown(&account.equity) * 100.0,
+    })
+}
+
+/// Simple moving average of the `period` closes ending at `idx`, `None` during warm-up.
+fn sma(prices: &[f64], idx: usize, period: usize) -> Option<f64> {
+    if idx + 1 < period {
+        return None;
+    }
+
+    let window = &prices[idx + 1 - period..=idx];
+    let mean = window.iter().sum::<f64>() / period as f64;
+    mean.is_finite().then_some(mean)
+}
@@ -0,0 +1,318 @@
+use crate::data_point::DataPoint;
+use chrono::Duration;
+use polars::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorrelationMethod {
+    Pearson,
+    Spearman,
+}
+
+impl fmt::Display for CorrelationMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorrelationMethod::Pearson => write!(f, "Pearson"),
+            CorrelationMethod::Spearman => write!(f, "Spearman"),
+        }
+    }
+}
+
+impl FromStr for CorrelationMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pearson" => Ok(CorrelationMethod::Pearson),
+            "spearman" => Ok(CorrelationMethod::Spearman),
+            other => Err(format!("Unknown correlation method: {other}")),
+        }
+    }
+}
+
+/// Daily log returns of several assets, restricted to the timestamps they all share.
+#[derive(Debug, Clone)]
+pub struct AlignedReturns {
+    pub symbols: Vec<String>,
+    /// Timestamp (milliseconds) of the close each return ends on.
+    pub timestamps: Vec<i64>,
+    /// One return series per symbol, all of the same length as `timestamps`.
+    pub returns: Vec<Vec<f64>>,
+}
+
+impl AlignedReturns {
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// Keeps only the returns ending after `timestamp` (milliseconds).
+    pub fn since(&self, timestamp: i64) -> Self {
+        let start = self.timestamps.partition_point(|&ts| ts <= timestamp);
+
+        Self {
+            symbols: self.symbols.clone(),
+            timestamps: self.timestamps[start..].to_vec(),
+            returns: self.returns.iter().map(|r| r[start..].to_vec()).collect(),
+        }
+    }
+}
+
+/// Minimum share of a window two assets must have in common for their coefficient
+/// to be reported. Pairs with less overlap are left empty in the matrix.
+pub const MIN_OVERLAP: f64 = 0.5;
+
+/// Correlation matrix over the trailing `window_days` of each pair's aligned returns.
+#[derive(Debug, Clone)]
+pub struct CorrelationMatrix {
+    pub window_days: usize,
+    /// Fewest returns shared by any reported pair in the window.
+    pub observations: usize,
+    pub method: CorrelationMethod,
+    /// One column per symbol; row `i` holds the correlations of the `i`-th symbol,
+    /// null for pairs without enough overlap.
+    pub matrix: DataFrame,
+}
+
+impl CorrelationMatrix {
+    /// Mean of the off-diagonal coefficients, `None` with fewer than two assets.
+    pub fn average_pairwise(&self) -> Option<f64> {
+        average_pairwise_correlation(&self.matrix)
+    }
+}
+
+/// Builds log returns for each asset, aligned on the timestamps present (with a
+/// positive price) in every series, oldest first.
+pub fn aligned_log_returns(series: &[(&str, &[DataPoint])]) -> AlignedReturns {
+    let prices: Vec<HashMap<i64, f64>> = series
+        .iter()
+        .map(|(_, points)| {
+            points
+                .iter()
+                .filter(|p| p.price.is_finite() && p.price > 0.0)
+                .map(|p| (p.datetime.timestamp_millis(), p.price))
+                .collect()
+        })
+        .collect();
+
+    let common: BTreeSet<i64> = match prices.first() {
+        Some(first) => first
+            .keys()
+            .filter(|ts| prices.iter().all(|p| p.contains_key(ts)))
+            .copied()
+            .collect(),
+        None => BTreeSet::new(),
+    };
+    let common: Vec<i64> = common.into_iter().collect();
+
+    let returns = prices
+        .iter()
+        .map(|p| {
+            common
+                .windows(2)
+                .map(|pair| (p[&pair[1]] / p[&pair[0]]).ln())
+                .collect()
+        })
+        .collect();
+
+    AlignedReturns {
+        symbols: series.iter().map(|(symbol, _)| symbol.to_string()).collect(),
+        timestamps: common.iter().skip(1).copied().collect(),
+        returns,
+    }
+}
+
+/// Computes the correlation matrix of the given returns with the chosen method.
+pub fn correlation_matrix(
+    returns: &AlignedReturns,
+    method: CorrelationMethod,
+) -> PolarsResult<DataFrame> {
+    if returns.len() < 2 {
+        return Err(PolarsError::ComputeError(
+            "At least two aligned returns are required to compute correlations".into(),
+        ));
+    }
+
+    let series: Vec<Vec<f64>> = match method {
+        CorrelationMethod::Pearson => returns.returns.clone(),
+        CorrelationMethod::Spearman => returns.returns.iter().map(|r| ranks(r)).collect(),
+    };
+
+    let n = series.len();
+    let mut matrix = vec![vec![1.0; n]; n];
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let corr = pearson(&series[i], &series[j]);
+            matrix[i][j] = corr;
+            matrix[j][i] = corr;
+        }
+    }
+
+    let columns = returns
+        .symbols
+        .iter()
+        .zip(matrix)
+        .map(|(symbol, values)| Column::new(symbol.as_str().into(), values))
+        .collect();
+
+    DataFrame::new(columns)
+}
+
+/// Computes the correlation matrix over the trailing `window_days` (calendar days, ending
+/// at the latest close of any asset), aligning each pair on the timestamps those two
+/// assets share so a young asset only affects its own pairs.
+///
+/// Fails when no pair shares at least `MIN_OVERLAP` of the window.
+pub fn window_correlations(
+    series: &[(&str, &[DataPoint])],
+    window_days: usize,
+    method: CorrelationMethod,
+) -> PolarsResult<CorrelationMatrix> {
+    let start = series
+        .iter()
+        .filter_map(|(_, points)| points.last().map(|p| p.datetime))
+        .max()
+        .map_or(i64::MIN, |end| (end - Duration::days(window_days as i64)).timestamp_millis());
+    let required = ((window_days as f64 * MIN_OVERLAP).ceil() as usize).max(2);
+    let n = series.len();
+    let mut matrix: Vec<Vec<Option<f64>>> = vec![vec![None; n]; n];
+    let mut observations: Option<usize> = None;
+
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[i] = Some(1.0);
+    }
+
+    for (i, a) in series.iter().enumerate() {
+        for (j, b) in series.iter().enumerate().skip(i + 1) {
+            let pair = aligned_log_returns(&[*a, *b]).since(start);
+            if pair.len() < required {
+                continue;
+            }
+
+            let corr = coefficient(&pair.returns[0], &pair.returns[1], method);
+            matrix[i][j] = Some(corr);
+            matrix[j][i] = Some(corr);
+            observations = Some(observations.map_or(pair.len(), |o| o.min(pair.len())));
+        }
+    }
+
+    let observations = observations.ok_or_else(|| {
+        PolarsError::ComputeError(
+            format!("No pair of assets shares {required} returns in the {window_days}-day window")
+                .into(),
+        )
+    })?;
+
+    let columns = series
+        .iter()
+        .zip(matrix)
+        .map(|((symbol, _), values)| Column::new((*symbol).into(), values))
+        .collect();
+
+    Ok(CorrelationMatrix {
+        window_days,
+        observations,
+        method,
+        matrix: DataFrame::new(columns)?,
+    })
+}
+
+/// Computes one correlation matrix per trailing window (in days), in the order given.
+/// A window that cannot be computed yields an error without affecting the others.
+pub fn rolling_correlations(
+    series: &[(&str, &[DataPoint])],
+    windows: &[usize],
+    method: CorrelationMethod,
+) -> Vec<PolarsResult<CorrelationMatrix>> {
+    windows
+        .iter()
+        .map(|&window_days| window_correlations(series, window_days, method))
+        .collect()
+}
+
+/// Mean of the off-diagonal coefficients of a square correlation matrix.
+pub fn average_pairwise_correlation(matrix: &DataFrame) -> Option<f64> {
+    let columns = matrix.get_columns();
+    let n = columns.len();
+
+    if n < 2 {
+        return None;
+    }
+
+    let mut total = 0.0;
+    let mut pairs = 0;
+
+    for (j, column) in columns.iter().enumerate() {
+        let values = column.f64().ok()?;
+        for i in (j + 1)..n {
+            if let Some(value) = values.get(i) {
+                total += value;
+                pairs += 1;
+            }
+        }
+    }
+
+    (pairs > 0).then(|| total / pairs as f64)
+}
+
+fn coefficient(x: &[f64], y: &[f64], method: CorrelationMethod) -> f64 {
+    match method {
+        CorrelationMethod::Pearson => pearson(x, y),
+        CorrelationMethod::Spearman => pearson(&ranks(x), &ranks(y)),
+    }
+}
+
+fn pearson(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len().min(y.len()) as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+
+    let mut numerator = 0.0;
+    let mut denom_x = 0.0;
+    let mut denom_y = 0.0;
+
+    for (a, b) in x.iter().zip(y) {
+        let dx = a - mean_x;
+        let dy = b - mean_y;
+        numerator += dx * dy;
+        denom_x += dx * dx;
+        denom_y += dy * dy;
+    }
+
+    if denom_x == 0.0 || denom_y == 0.0 {
+        return 0.0;
+    }
+
+    (numerator / (denom_x.sqrt() * denom_y.sqrt())).clamp(-1.0, 1.0)
+}
+
+/// Fractional ranks (1-based), ties receiving the average of their positions.
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+
+    while start < order.len() {
+        let mut end = start;
+        while end + 1 < order.len() && values[order[end + 1]] == values[order[start]] {
+            end += 1;
+        }
+
+        let rank = (start + end) as f64 / 2.0 + 1.0;
+        for &idx in &order[start..=end] {
+            ranks[idx] = rank;
+        }
+
+        start = end + 1;
+    }
+
+    ranks
+}
@@ -1,4 +1,7 @@
 use chrono::{DateTime, Utc};
+#[cfg(any(test, feature = "test-util"))]
+use chrono::{Duration, TimeZone};
+use polars::prelude::*;
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,3 +13,117 @@ pub struct DataPoint {
     pub open: f64,
     pub volume: f64,
 }
+
+/// Columns of a price frame, in order. `datetime` holds Unix milliseconds.
+pub const FRAME_COLUMNS: [&str; 6] = ["datetime", "price", "high", "low", "open", "volume"];
+
+/// Builds a price frame with the `FRAME_COLUMNS`, one row per data point.
+pub fn to_frame(data: &[DataPoint]) -> PolarsResult<DataFrame> {
+    DataFrame::new(vec![
+        Column::new(
+            "datetime".into(),
+            data.iter().map(|d| d.datetime.timestamp_millis()).collect::<Vec<_>>(),
+        ),
+        Column::new("price".into(), data.iter().map(|d| d.price).collect::<Vec<_>>()),
+        Column::new("high".into(), data.iter().map(|d| d.high).collect::<Vec<_>>()),
+        Column::new("low".into(), data.iter().map(|d| d.low).collect::<Vec<_>>()),
+        Column::new("open".into(), data.iter().map(|d| d.open).collect::<Vec<_>>()),
+        Column::new("volume".into(), data.iter().map(|d| d.volume).collect::<Vec<_>>()),
+    ])
+}
+
+/// Reads the data points back from a frame with the `FRAME_COLUMNS`. Rows with a null
+/// or out-of-range value are skipped.
+pub fn from_frame(frame: &DataFrame) -> PolarsResult<Vec<DataPoint>> {
+    let datetime = frame.column("datetime")?.cast(&DataType::Int64)?;
+    let datetime = datetime.i64()?;
+    let price = frame.column("price")?.f64()?;
+    let high = frame.column("high")?.f64()?;
+    let low = frame.column("low")?.f64()?;
+    let open = frame.column("open")?.f64()?;
+    let volume = frame.column("volume")?.f64()?;
+
+    Ok((0..frame.height())
+        .filter_map(|idx| {
+            Some(DataPoint {
+                datetime: DateTime::from_timestamp_millis(datetime.get(idx)?)?,
+                price: price.get(idx)?,
+                high: high.get(idx)?,
+                low: low.get(idx)?,
+                open: open.get(idx)?,
+                volume: volume.get(idx)?,
+            })
+        })
+        .collect())
+}
+
+/// Builds synthetic daily histories for tests and examples: one candle per price, opening
+/// at the close, with the high and low `spread` around it. Only built for tests, or with
+/// the `test-util` feature.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Clone)]
+pub struct DailySeries {
+    start: DateTime<Utc>,
+    spread: f64,
+    volume: f64,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Default for DailySeries {
+    /// Starts on 2025-01-01, with no spread and a volume of 1.
+    fn default() -> Self {
+        Self {
+            start: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            spread: 0.0,
+            volume: 1.0,
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl DailySeries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts `days` later.
+    pub fn offset(mut self, days: i64) -> Self {
+        self.start += Duration::days(days);
+        self
+    }
+
+    /// High and low `spread` (0.01 = 1%) above and below the close.
+    pub fn spread(mut self, spread: f64) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub fn volume(mut self, volume: f64) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Date of the `n`-th candle.
+    pub fn day(&self, n: i64) -> DateTime<Utc> {
+        self.start + Duration::days(n)
+    }
+
+    pub fn candle(&self, n: i64, price: f64) -> DataPoint {
+        DataPoint {
+            datetime: self.day(n),
+            price,
+            high: price * (1.0 + self.spread),
+            low: price * (1.0 - self.spread),
+            open: price,
+            volume: self.volume,
+        }
+    }
+
+    pub fn prices(&self, prices: &[f64]) -> Vec<DataPoint> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(n, &price)| self.candle(n as i64, price))
+            .collect()
+    }
+}
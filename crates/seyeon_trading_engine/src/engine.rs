@@ -1,4 +1,7 @@
-use chrono::{DateTime, TimeZone, Utc};
+use crate::backtest::{Bar, BarSeries, Broker, Order, OrderQueue, Portfolio};
+use crate::error::TradingEngineError;
+use crate::robustness::max_drawdown;
+use chrono::{DateTime, Utc};
 use polars::prelude::*;
 
 // --- Trade-related structs remain the same ---
@@ -38,6 +41,7 @@ pub struct Params {
     pub generic_fee: f64,       // fixed fee (0.5% in this example)
     pub buy_threshold: usize,
     pub sell_threshold: usize,
+    pub btc_dominance_veto: bool, // don't buy altcoins while BTC dominance is rising
 }
 
 impl Default for Params {
@@ -52,6 +56,7 @@ impl Default for Params {
             generic_fee: 0.005,
             buy_threshold: 3,
             sell_threshold: 2,
+            btc_dominance_veto: true,
         }
     }
 }
@@ -61,12 +66,11 @@ pub struct TradingEngine {
     /// The final (fully calculated) dataframe with all indicators.
     pub symbol: String,
     pub final_df: DataFrame,
+    /// Indicator columns of `final_df`, extracted once.
+    pub bars: BarSeries,
     pub fgi: u8, // if not provided, assume 50 (neutral)
     pub params: Params,
-    pub current_cash: f64,
-    pub held: f64,
-    pub trade_history: Vec<Trade>,
-    pub position: Option<Position>,
+    pub portfolio: Portfolio,
 }
 
 #[derive(Debug, Clone)]
@@ -76,6 +80,8 @@ pub struct Summary {
     pub roi: f64,
     pub num_trades: usize,
     pub estimated_fees_paid: f64,
+    /// Largest peak-to-trough decline of the equity curve, in percent.
+    pub max_drawdown: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -90,6 +96,18 @@ pub struct Event {
     pub datetime: DateTime<Utc>,
     pub price: f64,
     pub signal: Signal,
+    /// Share of the signal's conditions that hold, from 0 to 1. For Hold, one minus
+    /// the larger of the buy and sell shares.
+    pub confidence: f64,
+}
+
+/// Conditions met on a bar.
+struct Votes {
+    buy_signal: bool,
+    sell_signal: bool,
+    /// Share of the buy conditions that hold.
+    buy_share: f64,
+    sell_share: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -101,114 +119,67 @@ pub struct PortfolioSimulation {
 }
 
 impl TradingEngine {
-    /// Constructs a new TradingEngine. Note that we immediately calculate the
-    /// final dataframe from the provided Indicators.
+    /// Constructs a new TradingEngine. The indicator values are extracted from the
+    /// final dataframe once, up front; fails if a required column is missing.
     pub fn new(
-        symbol: String, final_df: DataFrame, fgi: Option<u8>, params: Params) -> Self {
+        symbol: String, final_df: DataFrame, fgi: Option<u8>, params: Params) -> Result<Self, TradingEngineError> {
         let fgi_val = fgi.unwrap_or(50);
+        let bars = BarSeries::from_df(&final_df)?;
 
-        Self {
+        Ok(Self {
             symbol,
             final_df,
+            bars,
             fgi: fgi_val,
-            current_cash: params.initial_capital,
+            portfolio: Portfolio::new(params.initial_capital),
             params,
-            held: 0.0,
-            trade_history: Vec::new(),
-            position: None,
-        }
+        })
+    }
+
+    /// Generates buy and sell signals for the row `idx` of the final dataframe.
+    pub fn generate_signal(&self, idx: usize) -> Result<(bool, bool), TradingEngineError> {
+        let bar = self.bars.get(idx).ok_or(TradingEngineError::IndexOutOfRange {
+            idx,
+            len: self.bars.len(),
+        })?;
+
+        self.signals(&bar)
+    }
+
+    fn signals(&self, bar: &Bar) -> Result<(bool, bool), TradingEngineError> {
+        let votes = self.votes(bar)?;
+        Ok((votes.buy_signal, votes.sell_signal))
     }
 
-    /// Generates buy and sell signals using the latest row of the final dataframe.
-    pub fn generate_signal(&self, idx: usize) -> (bool, bool) {
-        let price = self
-            .final_df
-            .column("price")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let ma25 = self
-            .final_df
-            .column("ma25")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let ma50 = self
-            .final_df
-            .column("ma50")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let ma5 = self
-            .final_df
-            .column("ma5")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let macd = self
-            .final_df
-            .column("macd")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let signal_val = self
-            .final_df
-            .column("signal")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let rsi = self
-            .final_df
-            .column("rsi")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let roc = self
-            .final_df
-            .column("roc")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap_or(f64::NAN);
-        let lower_band = self
-            .final_df
-            .column("lower_band")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap_or(f64::NAN);
-        let ma111 = self
-            .final_df
-            .column("ma111")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let pi_cycle_top = self
-            .final_df
-            .column("pi_cycle_top")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
+    /// Votes on the bar's indicators. Fails with `WarmUp` until the bar is marked ready
+    /// and every required indicator is valid, rather than reading a meaningless comparison.
+    fn votes(&self, bar: &Bar) -> Result<Votes, TradingEngineError> {
+        let mut missing = bar.missing_indicators(self.symbol == "BTC");
+        if !bar.ready && missing.is_empty() {
+            missing.push("ready");
+        }
+        if !missing.is_empty() {
+            return Err(TradingEngineError::WarmUp {
+                symbol: self.symbol.clone(),
+                idx: bar.idx,
+                missing,
+            });
+        }
+
+        let Bar {
+            price,
+            ma5,
+            ma25,
+            ma50,
+            ma111,
+            macd,
+            signal: signal_val,
+            rsi,
+            roc,
+            lower_band,
+            pi_cycle_top,
+            ..
+        } = *bar;
 
         let mut buy_conditions = vec![
             price > ma25,
@@ -250,439 +221,173 @@ impl TradingEngine {
             
         }
 
+        // Cross-asset conditions, only available for altcoins with BTC columns attached.
+        if let Some(btc_rs) = bar.btc_rs {
+            buy_conditions.push(btc_rs > 0.0);
+            sell_conditions.push(btc_rs < 0.0);
+        }
+        if let (Some(btc_beta), Some(btc_trend)) = (bar.btc_beta, bar.btc_trend) {
+            // High-beta altcoins amplify a falling BTC.
+            sell_conditions.push(btc_beta > 1.0 && btc_trend < 0.0);
+        }
+        let dominance_rising = bar.btc_dominance_change.is_some_and(|change| change > 0.0);
+
         let buy_count = buy_conditions.iter().filter(|&&c| c).count();
 
         let sell_count = sell_conditions.iter().filter(|&&c| c).count();
 
-        let buy_signal = buy_count >= buy_threshold;
+        let buy_signal = buy_count >= buy_threshold
+            && !(self.params.btc_dominance_veto && self.symbol != "BTC" && dominance_rising);
         let sell_signal = sell_count >= sell_threshold;
 
-        (buy_signal, sell_signal)
+        Ok(Votes {
+            buy_signal,
+            sell_signal,
+            buy_share: buy_count as f64 / buy_conditions.len() as f64,
+            sell_share: sell_count as f64 / sell_conditions.len() as f64,
+        })
     }
 
     /// Runs in “production” mode (using only the most recent row).
-    pub fn poll_event(&self) -> Event {
-        let idx = self.final_df.height() - 1;
-        let price = self
-            .final_df
-            .column("price")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        // Extract datetime as an i64 (milliseconds) and convert it to DateTime<Utc>
-        let dt_val = self
-            .final_df
-            .column("datetime")
-            .unwrap()
-            .i64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let datetime = Utc.timestamp_millis_opt(dt_val).unwrap();
-        let (buy_signal, sell_signal) = self.generate_signal(idx);
-        if buy_signal && !sell_signal {
-            Event {
-                datetime,
-                price,
-                signal: Signal::Buy,
-            }
-        } else if sell_signal && !buy_signal {
-            Event {
-                datetime,
-                price,
-                signal: Signal::Sell,
-            }
+    pub fn poll_event(&self) -> Result<Event, TradingEngineError> {
+        let bar = self
+            .bars
+            .last()
+            .ok_or_else(|| TradingEngineError::EmptyData(self.symbol.clone()))?;
+        let votes = self.votes(&bar)?;
+
+        let (signal, confidence) = if votes.buy_signal && !votes.sell_signal {
+            (Signal::Buy, votes.buy_share)
+        } else if votes.sell_signal && !votes.buy_signal {
+            (Signal::Sell, votes.sell_share)
         } else {
-            Event {
-                datetime,
-                price,
-                signal: Signal::Hold,
-            }
-        }
-    }
+            (Signal::Hold, 1.0 - votes.buy_share.max(votes.sell_share))
+        };
 
-    fn enter_trade(&mut self, idx: usize, investment_fraction: f64) {
-        let price = self
-            .final_df
-            .column("price")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let dt_val = self
-            .final_df
-            .column("datetime")
-            .unwrap()
-            .i64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let datetime = Utc.timestamp_millis_opt(dt_val).unwrap();
-        let investment = self.current_cash * investment_fraction;
-        let fee = investment * self.params.generic_fee;
-        let amount = (investment - fee) / price;
-
-        self.current_cash -= investment;
-        self.held += amount;
-        self.position = Some(Position {
-            avg_price: price,
-            amount,
-            investment,
-            entry_time: datetime,
-        });
-        self.trade_history.push(Trade {
-            trade_type: TradeType::Buy,
-            datetime,
-            price,
-            amount,
-        });
+        Ok(Event {
+            datetime: bar.datetime,
+            price: bar.price,
+            signal,
+            confidence,
+        })
     }
 
-    fn dca_buy(&mut self, idx: usize) {
-        if let Some(pos) = &mut self.position {
-            let price = self
-                .final_df
-                .column("price")
-                .unwrap()
-                .f64()
-                .unwrap()
-                .get(idx)
-                .unwrap();
-            if price < pos.avg_price * (1.0 - self.params.dca_buy_threshold)
-                && self.current_cash > 100.0
-            {
-                let dt_val = self
-                    .final_df
-                    .column("datetime")
-                    .unwrap()
-                    .i64()
-                    .unwrap()
-                    .get(idx)
-                    .unwrap();
-                let datetime = Utc.timestamp_millis_opt(dt_val).unwrap();
-                let investment = self.current_cash * self.params.dca_buy_fraction;
-                if investment < 50.0 {
-                    return;
-                }
-                let fee = investment * self.params.generic_fee;
-                let amount = (investment - fee) / price;
-                let total_amount = pos.amount + amount;
-                pos.avg_price = ((pos.avg_price * pos.amount) + (price * amount)) / total_amount;
-                pos.amount = total_amount;
-                pos.investment += investment;
-                self.current_cash -= investment;
-                self.held += amount;
-                self.trade_history.push(Trade {
-                    trade_type: TradeType::DcaBuy,
-                    datetime,
-                    price,
-                    amount,
-                });
-            }
-        }
-    }
+    /// Turns the signals of a bar into orders, given the current position.
+    fn submit_orders(&self, bar: &Bar, (buy_signal, sell_signal): (bool, bool), orders: &mut OrderQueue) {
+        let price = bar.price;
 
-    fn partial_sell(&mut self, idx: usize) {
-        if let Some(pos) = &mut self.position {
-            let price = self
-                .final_df
-                .column("price")
-                .unwrap()
-                .f64()
-                .unwrap()
-                .get(idx)
-                .unwrap();
-            if price > pos.avg_price * (1.0 + self.params.profit_sell_threshold) {
-                let dt_val = self
-                    .final_df
-                    .column("datetime")
-                    .unwrap()
-                    .i64()
-                    .unwrap()
-                    .get(idx)
-                    .unwrap();
-                let datetime = Utc.timestamp_millis_opt(dt_val).unwrap();
-                let sell_amount = pos.amount * self.params.profit_sell_fraction;
-                let investment_value = sell_amount * price;
-                let fee = investment_value * self.params.generic_fee;
-                let proceeds = investment_value - fee;
-                self.current_cash += proceeds;
-                self.held -= sell_amount;
-                pos.amount -= sell_amount;
-                self.trade_history.push(Trade {
-                    trade_type: TradeType::PartialSell,
-                    datetime,
-                    price,
-                    amount: sell_amount,
+        let Some(pos) = &self.portfolio.position else {
+            if buy_signal && self.portfolio.cash > 50.0 {
+                orders.submit(Order::Enter {
+                    fraction: self.params.initial_investment_fraction,
                 });
             }
+            return;
+        };
+
+        // Only one of these can hold for a given price and average cost.
+        if price < pos.avg_price * (1.0 - self.params.dca_buy_threshold)
+            && self.portfolio.cash > 100.0
+            && self.portfolio.cash * self.params.dca_buy_fraction >= 50.0
+        {
+            orders.submit(Order::DcaBuy {
+                fraction: self.params.dca_buy_fraction,
+            });
+        } else if price > pos.avg_price * (1.0 + self.params.profit_sell_threshold) {
+            orders.submit(Order::PartialSell {
+                fraction: self.params.profit_sell_fraction,
+            });
         }
-    }
 
-    fn full_sell(&mut self, idx: usize) {
-        if let Some(pos) = &self.position {
-            let price = self
-                .final_df
-                .column("price")
-                .unwrap()
-                .f64()
-                .unwrap()
-                .get(idx)
-                .unwrap();
-            if price > pos.avg_price {
-                let dt_val = self
-                    .final_df
-                    .column("datetime")
-                    .unwrap()
-                    .i64()
-                    .unwrap()
-                    .get(idx)
-                    .unwrap();
-                let datetime = Utc.timestamp_millis_opt(dt_val).unwrap();
-                let sell_amount = pos.amount;
-                let investment_value = sell_amount * price;
-                let fee = investment_value * self.params.generic_fee;
-                let proceeds = investment_value - fee;
-                self.current_cash += proceeds;
-                self.held -= sell_amount;
-                self.trade_history.push(Trade {
-                    trade_type: TradeType::FullSell,
-                    datetime,
-                    price,
-                    amount: sell_amount,
-                });
-                self.position = None;
-            }
+        if sell_signal && price > pos.avg_price {
+            orders.submit(Order::FullSell);
         }
     }
 
-    fn final_sell(&mut self) {
-        if let Some(pos) = &self.position {
-            let idx = self.final_df.height() - 1;
-            let final_price = self
-                .final_df
-                .column("price")
-                .unwrap()
-                .f64()
-                .unwrap()
-                .get(idx)
-                .unwrap();
-            let sell_price = if final_price < pos.avg_price {
-                pos.avg_price
-            } else {
-                final_price
-            };
-            let dt_val = self
-                .final_df
-                .column("datetime")
-                .unwrap()
-                .i64()
-                .unwrap()
-                .get(idx)
-                .unwrap();
-            let datetime = Utc.timestamp_millis_opt(dt_val).unwrap();
-            let sell_amount = pos.amount;
-            let investment_value = sell_amount * sell_price;
-            let fee = investment_value * self.params.generic_fee;
-            let proceeds = investment_value - fee;
-            self.current_cash += proceeds;
-            self.held -= sell_amount;
-            self.trade_history.push(Trade {
-                trade_type: TradeType::FinalSell,
-                datetime,
-                price: sell_price,
-                amount: sell_amount,
-            });
-            self.position = None;
+    /// Routes every queued order through the broker and books the fills.
+    fn process_orders(&mut self, bar: &Bar, orders: &mut OrderQueue, broker: &Broker) {
+        while let Some(order) = orders.pop() {
+            if let Some(fill) = broker.execute(order, bar, &self.portfolio) {
+                self.portfolio.apply(&fill);
+            }
         }
     }
 
     /// Runs the simulation over the specified number of data points (or the full dataset if shorter).
     /// Default is 365 days if no value is provided.
-    pub fn run_simulation(&mut self, days: Option<usize>) {
-        let total_data = self.final_df.height();
+    ///
+    /// Each bar is replayed in order: the strategy submits orders from its signals, the
+    /// broker fills them at the close and the portfolio books the fills. Bars whose
+    /// indicators are still warming up are valued but never traded on.
+    pub fn run_simulation(&mut self, days: Option<usize>) -> Result<(), TradingEngineError> {
+        let total_data = self.bars.len();
+        if total_data == 0 {
+            return Err(TradingEngineError::EmptyData(self.symbol.clone()));
+        }
+
         let simulation_days = days.unwrap_or(365);
-        
-        let start_idx = if total_data > simulation_days {
-            total_data - simulation_days
-        } else {
-            0
+        let start_idx = total_data.saturating_sub(simulation_days);
+
+        let broker = Broker {
+            fee_rate: self.params.generic_fee,
         };
-        let mut in_position = false;
-
-        for idx in start_idx..total_data {
-            let price = self
-                .final_df
-                .column("price")
-                .unwrap()
-                .f64()
-                .unwrap()
-                .get(idx)
-                .unwrap();
-            let (buy_signal, sell_signal) = self.generate_signal(idx);
-
-            if !in_position {
-                if buy_signal && self.current_cash > 50.0 {
-                    self.enter_trade(idx, self.params.initial_investment_fraction);
-                    in_position = true;
-                }
-            } else {
-                self.dca_buy(idx);
-                self.partial_sell(idx);
-                if sell_signal {
-                    if let Some(pos) = &self.position {
-                        if price > pos.avg_price {
-                            self.full_sell(idx);
-                            in_position = false;
-                        }
-                    }
-                }
+        let mut orders = OrderQueue::default();
+        let bars = std::mem::take(&mut self.bars);
+
+        for bar in bars.iter_from(start_idx) {
+            // `signals` only fails on bars that are still warming up.
+            if let Ok(signals) = self.signals(&bar) {
+                self.submit_orders(&bar, signals, &mut orders);
             }
+            self.process_orders(&bar, &mut orders, &broker);
+            self.portfolio.mark(&bar);
         }
 
-        if in_position && self.position.is_some() {
-            self.final_sell();
+        if let Some(last) = bars.last() {
+            orders.submit(Order::FinalSell);
+            self.process_orders(&last, &mut orders, &broker);
         }
+
+        self.bars = bars;
+        Ok(())
     }
 
-    /// Displays a summary of the simulation results.
-    #[must_use]
-    pub fn get_summary(&self) -> Summary {
-        let idx = self.final_df.height() - 1;
+    /// Summarizes the simulation results, valuing holdings at the last close.
+    pub fn get_summary(&self) -> Result<Summary, TradingEngineError> {
         let final_price = self
-            .final_df
-            .column("price")
-            .unwrap()
-            .f64()
-            .unwrap()
-            .get(idx)
-            .unwrap();
-        let final_portfolio_value = self.current_cash + (self.held * final_price);
+            .bars
+            .last()
+            .map(|bar| bar.price)
+            .filter(|price| price.is_finite())
+            .ok_or_else(|| TradingEngineError::EmptyData(self.symbol.clone()))?;
+        let final_portfolio_value = self.portfolio.value(final_price);
         let profit = final_portfolio_value - self.params.initial_capital;
         let roi = (profit / self.params.initial_capital) * 100.0;
         let total_fees: f64 = self
+            .portfolio
             .trade_history
             .iter()
             .map(|t| t.price * t.amount * self.params.generic_fee)
             .sum();
 
-        // println!("Initial Capital: ${:.2}", self.params.initial_capital);
-        // println!("Final Portfolio Value: ${:.2}", final_portfolio_value);
-        // println!("Return: {:+.2}%", roi);
-        // println!("Total Number of Trades: {}", self.trade_history.len());
-        // println!("Total Fees Paid: ${:.2}", total_fees);
-        // println!("{:=^60}", "");
+        let equity: Vec<f64> = self
+            .portfolio
+            .equity_curve
+            .iter()
+            .map(|(_, value)| *value)
+            .chain(std::iter::once(final_portfolio_value))
+            .collect();
 
-        Summary {
+        Ok(Summary {
             final_portfolio_value,
             roi,
             initial_capital: self.params.initial_capital,
-            num_trades: self.trade_history.len(),
+            num_trades: self.portfolio.trade_history.len(),
             estimated_fees_paid: total_fees,
-        }
+            max_drawdown: max_drawdown(&equity) * 100.0,
+        })
     }
 
-    /// Calculates the correlation matrix between multiple assets
-    /// Returns a DataFrame with the correlation matrix
-    pub fn calculate_correlation_matrix(price_data: &[(&str, &Vec<f64>)]) -> Result<DataFrame, PolarsError> {
-        let mut columns = Vec::new();
-        
-        for (symbol, prices) in price_data {
-            columns.push(Series::new((*symbol).to_string().into(), (*prices).clone()).into());
-        }
-        
-        let df = DataFrame::new(columns)?;
-        
-        let col_names = df.get_column_names();
-        let n_cols = col_names.len();
-        
-        let mut corr_matrix = vec![vec![0.0; n_cols]; n_cols];
-        
-        for i in 0..n_cols {
-            for j in 0..n_cols {
-                if i == j {
-                    corr_matrix[i][j] = 1.0;
-                } else {
-                    let series_i = df.column(col_names[i])?.f64()?;
-                    let series_j = df.column(col_names[j])?.f64()?;
-                    
-                    let corr = Self::pearson_correlation(series_i, series_j)?;
-                    corr_matrix[i][j] = corr;
-                }
-            }
-        }
-        
-        let mut corr_columns = Vec::new();
-        
-        for (i, name) in col_names.iter().enumerate() {
-            let corr_series = Series::new(name.to_string().into(), corr_matrix[i].clone()).into();
-            corr_columns.push(corr_series);
-        }
-        
-        let corr_df = DataFrame::new(corr_columns)?;
-        
-        Ok(corr_df)
-    }
-    
-    fn pearson_correlation(s1: &ChunkedArray<Float64Type>, s2: &ChunkedArray<Float64Type>) -> Result<f64, PolarsError> {
-        // Get lengths, ensure they match
-        let len1 = s1.len();
-        let len2 = s2.len();
-        
-        if len1 != len2 {
-            return Err(PolarsError::ShapeMismatch(
-                format!("Series lengths don't match: {} vs {}", len1, len2).into(),
-            ));
-        }
-        
-        if len1 == 0 {
-            return Err(PolarsError::ComputeError(
-                "Cannot compute correlation on empty series".into(),
-            ));
-        }
-        
-        let mean1: f64 = s1.mean().unwrap_or(0.0);
-        let mean2: f64 = s2.mean().unwrap_or(0.0);
-        
-        let mut numerator = 0.0;
-        let mut denom1 = 0.0;
-        let mut denom2 = 0.0;
-        
-        for i in 0..len1 {
-            let v1 = match s1.get(i) {
-                Some(v) => v,
-                None => continue,
-            };
-            
-            let v2 = match s2.get(i) {
-                Some(v) => v,
-                None => continue,
-            };
-            
-            let diff1 = v1 - mean1;
-            let diff2 = v2 - mean2;
-            
-            numerator += diff1 * diff2;
-            denom1 += diff1 * diff1;
-            denom2 += diff2 * diff2;
-        }
-        
-        if denom1 == 0.0 || denom2 == 0.0 {
-            return Ok(0.0);
-        }
-        
-        let correlation = numerator / (denom1.sqrt() * denom2.sqrt());
-        
-        if correlation < -1.0 || correlation > 1.0 {
-            Ok(correlation.clamp(-1.0, 1.0))
-        } else {
-            Ok(correlation)
-        }
-    }
-    
     /// Exports the correlation matrix to an HTML heatmap
     pub fn export_correlation_heatmap(correlation_df: &DataFrame, file_path: &str) -> std::io::Result<()> {
         let mut html_content = String::from(r#"
@@ -802,10 +507,18 @@ impl TradingEngine {
         
         for (symbol, dataframe) in assets_data {
             let params = Params::default();
-            let mut engine = TradingEngine::new(symbol.to_string(), dataframe.clone(), None, params);
-            
-            engine.run_simulation(Some(days));
-            let summary = engine.get_summary();
+            let summary = TradingEngine::new(symbol.to_string(), dataframe.clone(), None, params)
+                .and_then(|mut engine| {
+                    engine.run_simulation(Some(days))?;
+                    engine.get_summary()
+                });
+            let summary = match summary {
+                Ok(summary) => summary,
+                Err(e) => {
+                    eprintln!("Skipping {} in the performance comparison: {}", symbol, e);
+                    continue;
+                }
+            };
             
             results.push(PortfolioSimulation {
                 symbol: symbol.to_string(),
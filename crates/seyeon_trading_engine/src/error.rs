@@ -0,0 +1,24 @@
+use polars::prelude::PolarsError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TradingEngineError {
+    #[error("Polars error: {0}")]
+    Polars(#[from] PolarsError),
+    #[error("Missing column in the indicators dataframe: {0}")]
+    MissingColumn(String),
+    #[error("No data available for {0}")]
+    EmptyData(String),
+    #[error("Invalid timestamp at row {0}")]
+    InvalidTimestamp(usize),
+    #[error("Row {idx} is out of range ({len} rows)")]
+    IndexOutOfRange { idx: usize, len: usize },
+    /// Some indicators are still null at this row, typically because the asset has less
+    /// history than their window.
+    #[error("Indicators not ready for {symbol} at row {idx}: {}", missing.join(", "))]
+    WarmUp {
+        symbol: String,
+        idx: usize,
+        missing: Vec<&'static str>,
+    },
+}
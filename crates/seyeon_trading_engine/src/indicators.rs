@@ -1,9 +1,67 @@
-use crate::data_point::DataPoint;
+use crate::data_point::{self, DataPoint};
+use chrono::{DateTime, Utc};
 use polars::prelude::*;
 use std::ops::Mul;
 
 pub struct Indicators {
     pub data: LazyFrame,
+    btc: Option<LazyFrame>,
+    btc_dominance: Option<LazyFrame>,
+}
+
+/// Number of rows each indicator needs before its first meaningful value. Values on
+/// earlier rows are nulled by `calculate`.
+pub const WARM_UP: [(&str, usize); 15] = [
+    ("ma5", 5),
+    ("ma25", 25),
+    ("ma50", 50),
+    ("ma111", 111),
+    ("ma350", 350),
+    ("ma365", 365),
+    ("upper_band", 25),
+    ("lower_band", 25),
+    ("macd", 26),
+    ("signal", 34),
+    ("roc", 13),
+    ("vma20", 20),
+    ("atr14", 15),
+    ("pi_cycle_top", 350),
+    ("rsi", 15),
+];
+
+/// Warm-up of the BTC-relative columns (30-day windows over daily returns).
+const BTC_WARM_UP: [(&str, usize); 4] = [("btc_rs", 30), ("btc_trend", 30), ("btc_beta", 31), ("btc_corr", 31)];
+
+/// Indicators every asset needs before the `ready` column turns true.
+pub const CORE_INDICATORS: [&str; 9] = [
+    "price",
+    "ma5",
+    "ma25",
+    "ma50",
+    "macd",
+    "signal",
+    "rsi",
+    "roc",
+    "lower_band",
+];
+
+/// Rows of valid history needed before an asset can be traded.
+pub fn min_history() -> usize {
+    WARM_UP
+        .iter()
+        .filter(|(name, _)| CORE_INDICATORS.contains(name))
+        .map(|(_, rows)| *rows)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Warm-up length of `indicator`, if it is one computed by `Indicators`.
+pub fn warm_up(indicator: &str) -> Option<usize> {
+    WARM_UP
+        .iter()
+        .chain(BTC_WARM_UP.iter())
+        .find(|(name, _)| *name == indicator)
+        .map(|(_, rows)| *rows)
 }
 
 fn window(size: usize) -> RollingOptionsFixedWindow {
@@ -14,43 +72,84 @@ fn window(size: usize) -> RollingOptionsFixedWindow {
 }
 
 impl Indicators {
+    /// Builds the base frame. Leading rows without a valid (positive, finite) price,
+    /// such as the zeros listed before a coin's launch, are dropped.
     pub fn new(data: Vec<DataPoint>) -> Self {
-        let msx = data
-            .iter()
-            .map(|d| d.datetime.timestamp() * 1000)
-            .collect::<Vec<_>>();
-        let datetime = Column::new("datetime".into(), msx.clone());
-        let at = Column::new("at".into(), msx)
-            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
-            .expect("Failed to cast column");
-
-        let instance = Self {
-            data: DataFrame::new(vec![
-                Column::new(
-                    "price".into(),
-                    data.iter().map(|d| d.price).collect::<Vec<_>>(),
-                ),
-                Column::new(
-                    "high".into(),
-                    data.iter().map(|d| d.high).collect::<Vec<_>>(),
-                ),
-                Column::new("low".into(), data.iter().map(|d| d.low).collect::<Vec<_>>()),
-                Column::new(
-                    "open".into(),
-                    data.iter().map(|d| d.open).collect::<Vec<_>>(),
-                ),
-                Column::new(
-                    "volume".into(),
-                    data.iter().map(|d| d.volume).collect::<Vec<_>>(),
-                ),
-                datetime.clone(),
-                at,
-            ])
-            .expect("Failed to create DataFrame")
-            .lazy(),
-        };
+        let frame = data_point::to_frame(&data).expect("Failed to create DataFrame");
+        Self::from_frame(frame.lazy())
+    }
+
+    /// Builds the base frame from a frame with the `data_point::FRAME_COLUMNS`, such as
+    /// a scanned cache file, without going through `DataPoint`s. Rows are expected in
+    /// `datetime` order; leading rows without a valid price are dropped, as in `new`.
+    pub fn from_frame(frame: LazyFrame) -> Self {
+        let valid_price = col("price").is_finite().and(col("price").gt(lit(0.0)));
+        let datetime = col("datetime").cast(DataType::Int64);
 
-        instance
+        let data = frame
+            .filter(valid_price.cast(DataType::UInt32).cum_sum(false).gt(lit(0)))
+            .select([
+                col("price").cast(DataType::Float64),
+                col("high").cast(DataType::Float64),
+                col("low").cast(DataType::Float64),
+                col("open").cast(DataType::Float64),
+                col("volume").cast(DataType::Float64),
+                datetime.clone().alias("datetime"),
+                datetime
+                    .cast(DataType::Datetime(TimeUnit::Milliseconds, None))
+                    .alias("at"),
+            ]);
+
+        Self {
+            data,
+            btc: None,
+            btc_dominance: None,
+        }
+    }
+
+    /// Attaches the BTC history so `calculate` also derives the BTC-relative columns
+    /// (`btc_ratio`, `btc_rs`, `btc_beta`, `btc_corr`, `btc_trend`).
+    /// Rows are matched on `datetime`, so both series may have different lengths.
+    pub fn with_btc(mut self, btc: &[DataPoint]) -> Self {
+        let frame = DataFrame::new(vec![
+            Column::new(
+                "datetime".into(),
+                btc.iter()
+                    .map(|d| d.datetime.timestamp() * 1000)
+                    .collect::<Vec<_>>(),
+            ),
+            Column::new(
+                "btc_price".into(),
+                btc.iter().map(|d| d.price).collect::<Vec<_>>(),
+            ),
+        ])
+        .expect("Failed to create BTC DataFrame");
+
+        self.btc = Some(frame.lazy());
+        self
+    }
+
+    /// Attaches the recorded daily BTC dominance (in percent) so `calculate` adds the
+    /// `btc_dominance` and `btc_dominance_change` columns. Days without a snapshot are
+    /// forward-filled from the previous one.
+    pub fn with_btc_dominance(mut self, history: &[(DateTime<Utc>, f64)]) -> Self {
+        let frame = DataFrame::new(vec![
+            Column::new(
+                "datetime".into(),
+                history
+                    .iter()
+                    .map(|(day, _)| day.timestamp() * 1000)
+                    .collect::<Vec<_>>(),
+            ),
+            Column::new(
+                "btc_dominance".into(),
+                history.iter().map(|(_, value)| *value).collect::<Vec<_>>(),
+            ),
+        ])
+        .expect("Failed to create BTC dominance DataFrame");
+
+        self.btc_dominance = Some(frame.lazy());
+        self
     }
 
     fn calculate_ema(prices: Expr, span: usize) -> Expr {
@@ -232,8 +331,91 @@ impl Indicators {
             .with_column((lit(100.0) - (lit(100.0) / (lit(1.0) + col("rs")))).alias("rsi"))
     }
 
+    /// Computes the asset's strength and co-movement relative to BTC.
+    /// - btc_ratio: price expressed in BTC
+    /// - btc_rs: distance (%) of btc_ratio from its `period`-day mean; positive while the asset outperforms BTC
+    /// - btc_trend: distance (%) of the BTC price from its `period`-day mean
+    /// - btc_beta / btc_corr: rolling beta and Pearson correlation of daily returns against BTC
+    fn calculate_btc_relative(df: LazyFrame, btc: LazyFrame, period: usize) -> LazyFrame {
+        let cov = col("ret_xy") - (col("ret_x") * col("ret_y"));
+        let var_asset = col("ret_xx") - (col("ret_x") * col("ret_x"));
+        let var_btc = col("ret_yy") - (col("ret_y") * col("ret_y"));
+
+        df.left_join(btc, col("datetime"), col("datetime"))
+            .with_columns([
+                (col("price") / col("btc_price")).alias("btc_ratio"),
+                ((col("price") / col("price").shift(lit(1))) - lit(1.0)).alias("asset_return"),
+                ((col("btc_price") / col("btc_price").shift(lit(1))) - lit(1.0)).alias("btc_return"),
+            ])
+            .with_columns([
+                col("btc_ratio").rolling_mean(window(period)).alias("btc_ratio_ma"),
+                col("btc_price").rolling_mean(window(period)).alias("btc_ma"),
+                col("asset_return").rolling_mean(window(period)).alias("ret_x"),
+                col("btc_return").rolling_mean(window(period)).alias("ret_y"),
+                (col("asset_return") * col("btc_return"))
+                    .rolling_mean(window(period))
+                    .alias("ret_xy"),
+                (col("asset_return") * col("asset_return"))
+                    .rolling_mean(window(period))
+                    .alias("ret_xx"),
+                (col("btc_return") * col("btc_return"))
+                    .rolling_mean(window(period))
+                    .alias("ret_yy"),
+            ])
+            .with_columns([
+                ((col("btc_ratio") / col("btc_ratio_ma") - lit(1.0)) * lit(100.0)).alias("btc_rs"),
+                ((col("btc_price") / col("btc_ma") - lit(1.0)) * lit(100.0)).alias("btc_trend"),
+                (cov.clone() / var_btc.clone()).alias("btc_beta"),
+                (cov / (var_asset * var_btc).sqrt()).alias("btc_corr"),
+            ])
+    }
+
+    /// Joins the BTC dominance history and computes its change over `lookback` days
+    /// in the `btc_dominance_change` column (positive while dominance is rising).
+    fn calculate_btc_dominance(df: LazyFrame, dominance: LazyFrame, lookback: i32) -> LazyFrame {
+        df.left_join(dominance, col("datetime"), col("datetime"))
+            .with_column(col("btc_dominance").forward_fill(None))
+            .with_column(
+                (col("btc_dominance") - col("btc_dominance").shift(lit(lookback)))
+                    .alias("btc_dominance_change"),
+            )
+    }
+
+    /// Nulls each indicator on the rows before its warm-up is complete.
+    fn mask_warm_up(frame: LazyFrame, warm_up: &[(&str, usize)]) -> LazyFrame {
+        let masks: Vec<Expr> = warm_up
+            .iter()
+            .map(|(name, rows)| {
+                when(col("row_nr").lt(lit(*rows as u32)))
+                    .then(lit(NULL).cast(DataType::Float64))
+                    .otherwise(col(*name))
+                    .alias(*name)
+            })
+            .collect();
+
+        frame
+            .with_row_index("row_nr", None)
+            .with_columns(masks)
+            .drop([col("row_nr")])
+    }
+
+    /// Adds the boolean `ready` column, true once every core indicator is valid.
+    fn mark_ready(frame: LazyFrame) -> LazyFrame {
+        let ready = CORE_INDICATORS
+            .iter()
+            .map(|name| col(*name).is_not_null().and(col(*name).is_not_nan()))
+            .reduce(|acc, expr| acc.and(expr))
+            .unwrap_or(lit(true));
+
+        frame.with_column(ready.alias("ready"))
+    }
+
     pub fn calculate(self) -> PolarsResult<DataFrame> {
-        let Self { data } = self;
+        let Self {
+            data,
+            btc,
+            btc_dominance,
+        } = self;
 
         let data = Self::calculate_moving_averages(data);
         let data = Self::calculate_bollinger_bands(data);
@@ -245,6 +427,24 @@ impl Indicators {
         let data = Self::calculate_ath(data);
         let data = Self::calculate_rsi(data, 14);
 
+        let has_btc = btc.is_some();
+        let data = match btc {
+            Some(btc) => Self::calculate_btc_relative(data, btc, 30),
+            None => data,
+        };
+        let data = match btc_dominance {
+            Some(dominance) => Self::calculate_btc_dominance(data, dominance, 7),
+            None => data,
+        };
+
+        let data = Self::mask_warm_up(data, &WARM_UP);
+        let data = if has_btc {
+            Self::mask_warm_up(data, &BTC_WARM_UP)
+        } else {
+            data
+        };
+        let data = Self::mark_ready(data);
+
         let mut df = data.collect()?;
 
         /* Rechunk the DataFrame to optimize performance for subsequent operations.
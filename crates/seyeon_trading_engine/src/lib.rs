@@ -1,3 +1,10 @@
+pub mod backtest;
+pub mod benchmark;
+pub mod correlation;
 pub mod data_point;
 pub mod engine;
+pub mod error;
 pub mod indicators;
+pub mod risk;
+pub mod robustness;
+pub mod validation;
@@ -0,0 +1,275 @@
+use crate::correlation::{aligned_log_returns, AlignedReturns, MIN_OVERLAP};
+use crate::data_point::DataPoint;
+use chrono::Duration;
+use std::f64::consts::PI;
+
+/// Crypto trades every day of the year.
+pub const TRADING_DAYS_PER_YEAR: f64 = 365.0;
+
+/// One-day Value-at-Risk and Conditional VaR (expected shortfall) at a confidence level.
+/// All figures are positive fractions of portfolio value (0.05 = a 5% loss).
+#[derive(Debug, Clone)]
+pub struct VarEstimate {
+    pub confidence: f64,
+    pub historical_var: f64,
+    pub historical_cvar: f64,
+    pub parametric_var: f64,
+    pub parametric_cvar: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AssetRisk {
+    pub symbol: String,
+    pub weight: f64,
+    pub annualized_volatility: f64,
+    /// Change in portfolio volatility (annualized) per unit of additional weight.
+    pub marginal_contribution: f64,
+    /// Share of the portfolio volatility coming from this asset; sums to 1 across assets.
+    pub risk_contribution: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RiskReport {
+    pub observations: usize,
+    /// Assets left out because they have less than `MIN_OVERLAP` of the window's returns.
+    pub excluded: Vec<String>,
+    pub portfolio_volatility: f64,
+    pub var: Vec<VarEstimate>,
+    pub assets: Vec<AssetRisk>,
+}
+
+/// Computes portfolio risk over the trailing `window_days` (calendar days, ending at
+/// the latest close of any asset) of aligned daily log returns.
+///
+/// Assets with less than `MIN_OVERLAP` of the window's returns are excluded rather than
+/// shrinking the window for everyone; the others are aligned within the window.
+/// `weights` are matched by position with `series` and normalized to sum to 1 over the
+/// included assets; when `None`, the portfolio is equally weighted. Returns `None` if
+/// fewer than two aligned returns are available.
+pub fn analyze(
+    series: &[(&str, &[DataPoint])],
+    weights: Option<&[f64]>,
+    window_days: usize,
+    confidences: &[f64],
+) -> Option<RiskReport> {
+    let end = series
+        .iter()
+        .filter_map(|(_, points)| points.last().map(|p| p.datetime))
+        .max()?;
+    let start = (end - Duration::days(window_days as i64)).timestamp_millis();
+    let required = ((window_days as f64 * MIN_OVERLAP).ceil() as usize).max(2);
+
+    let (included, excluded): (Vec<usize>, Vec<usize>) = (0..series.len())
+        .partition(|&i| aligned_log_returns(&series[i..=i]).since(start).len() >= required);
+
+    let included_series: Vec<(&str, &[DataPoint])> = included.iter().map(|&i| series[i]).collect();
+    let returns = aligned_log_returns(&included_series).since(start);
+    if returns.len() < 2 {
+        return None;
+    }
+
+    let weights = match weights {
+        Some(weights) if weights.len() == series.len() => {
+            Some(included.iter().map(|&i| weights[i]).collect::<Vec<_>>())
+        }
+        Some(_) => return None,
+        None => None,
+    };
+    let weights = normalize_weights(weights.as_deref(), returns.symbols.len())?;
+    let covariance = covariance_matrix(&returns);
+    let n = weights.len();
+
+    // Σw, the covariance of each asset with the portfolio.
+    let cov_with_portfolio: Vec<f64> = (0..n)
+        .map(|i| (0..n).map(|j| covariance[i][j] * weights[j]).sum())
+        .collect();
+    let portfolio_variance: f64 = (0..n).map(|i| weights[i] * cov_with_portfolio[i]).sum();
+    let portfolio_daily_vol = portfolio_variance.max(0.0).sqrt();
+    let annualization = TRADING_DAYS_PER_YEAR.sqrt();
+
+    let assets = returns
+        .symbols
+        .iter()
+        .enumerate()
+        .map(|(i, symbol)| {
+            let marginal = if portfolio_daily_vol > 0.0 {
+                cov_with_portfolio[i] / portfolio_daily_vol
+            } else {
+                0.0
+            };
+
+            AssetRisk {
+                symbol: symbol.clone(),
+                weight: weights[i],
+                annualized_volatility: covariance[i][i].max(0.0).sqrt() * annualization,
+                marginal_contribution: marginal * annualization,
+                risk_contribution: if portfolio_daily_vol > 0.0 {
+                    weights[i] * marginal / portfolio_daily_vol
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+
+    // Daily simple returns of the rebalanced portfolio.
+    let portfolio_returns: Vec<f64> = (0..returns.len())
+        .map(|t| {
+            (0..n)
+                .map(|i| weights[i] * (returns.returns[i][t].exp() - 1.0))
+                .sum()
+        })
+        .collect();
+
+    let mean = portfolio_returns.iter().sum::<f64>() / portfolio_returns.len() as f64;
+    let std_dev = sample_std(&portfolio_returns, mean);
+
+    let var = confidences
+        .iter()
+        .map(|&confidence| {
+            let (historical_var, historical_cvar) = historical_var(&portfolio_returns, confidence);
+            let z = normal_quantile(confidence);
+
+            VarEstimate {
+                confidence,
+                historical_var,
+                historical_cvar,
+                parametric_var: (z * std_dev - mean).max(0.0),
+                parametric_cvar: (std_dev * normal_pdf(z) / (1.0 - confidence) - mean).max(0.0),
+            }
+        })
+        .collect();
+
+    Some(RiskReport {
+        observations: returns.len(),
+        excluded: excluded.iter().map(|&i| series[i].0.to_string()).collect(),
+        portfolio_volatility: portfolio_daily_vol * annualization,
+        var,
+        assets,
+    })
+}
+
+fn normalize_weights(weights: Option<&[f64]>, n: usize) -> Option<Vec<f64>> {
+    let weights = match weights {
+        Some(weights) if weights.len() == n => weights.to_vec(),
+        Some(_) => return None,
+        None => vec![1.0; n],
+    };
+
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    Some(weights.iter().map(|w| w / total).collect())
+}
+
+/// Sample covariance matrix of the aligned log returns.
+fn covariance_matrix(returns: &AlignedReturns) -> Vec<Vec<f64>> {
+    let len = returns.len() as f64;
+    let means: Vec<f64> = returns
+        .returns
+        .iter()
+        .map(|r| r.iter().sum::<f64>() / len)
+        .collect();
+    let n = returns.returns.len();
+    let mut covariance = vec![vec![0.0; n]; n];
+
+    for i in 0..n {
+        for j in i..n {
+            let cov = returns.returns[i]
+                .iter()
+                .zip(&returns.returns[j])
+                .map(|(a, b)| (a - means[i]) * (b - means[j]))
+                .sum::<f64>()
+                / (len - 1.0);
+            covariance[i][j] = cov;
+            covariance[j][i] = cov;
+        }
+    }
+
+    covariance
+}
+
+fn sample_std(values: &[f64], mean: f64) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    variance.sqrt()
+}
+
+/// Empirical VaR (loss at the `1 - confidence` quantile) and the mean loss beyond it.
+fn historical_var(returns: &[f64], confidence: f64) -> (f64, f64) {
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    // The epsilon keeps e.g. (1 - 0.95) * 20 from rounding up to a two-element tail.
+    let tail_len = (((1.0 - confidence) * sorted.len() as f64 - 1e-9).ceil() as usize).max(1);
+    let tail = &sorted[..tail_len.min(sorted.len())];
+
+    let var = -tail[tail.len() - 1];
+    let cvar = -tail.iter().sum::<f64>() / tail.len() as f64;
+
+    (var.max(0.0), cvar.max(0.0))
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * PI).sqrt()
+}
+
+/// Inverse of the standard normal CDF (Acklam's rational approximation, |error| < 1.2e-9).
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -normal_quantile(1.0 - p)
+    }
+}
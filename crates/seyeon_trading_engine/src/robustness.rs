@@ -0,0 +1,280 @@
+use crate::data_point::DataPoint;
+use crate::engine::{Params, TradingEngine};
+use crate::error::TradingEngineError;
+use crate::indicators::Indicators;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResamplingMethod {
+    /// Rebuilds synthetic price histories from blocks of consecutive daily returns.
+    Bootstrap,
+    /// Replays the actual backtest with the order of its trades shuffled.
+    Shuffle,
+}
+
+impl fmt::Display for ResamplingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResamplingMethod::Bootstrap => write!(f, "Block bootstrap"),
+            ResamplingMethod::Shuffle => write!(f, "Trade shuffle"),
+        }
+    }
+}
+
+impl FromStr for ResamplingMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bootstrap" => Ok(ResamplingMethod::Bootstrap),
+            "shuffle" => Ok(ResamplingMethod::Shuffle),
+            other => Err(format!("Unknown resampling method: {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MonteCarloConfig {
+    pub method: ResamplingMethod,
+    pub runs: usize,
+    /// Length (in days) of the return blocks drawn by the bootstrap.
+    pub block_size: usize,
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        Self {
+            method: ResamplingMethod::Bootstrap,
+            runs: 500,
+            block_size: 10,
+        }
+    }
+}
+
+/// Percentiles of a simulated distribution.
+#[derive(Debug, Clone)]
+pub struct Distribution {
+    pub mean: f64,
+    pub p5: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p95: f64,
+}
+
+impl Distribution {
+    /// Returns `None` for an empty sample.
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        Some(Self {
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p5: percentile(&sorted, 0.05),
+            p25: percentile(&sorted, 0.25),
+            p50: percentile(&sorted, 0.50),
+            p75: percentile(&sorted, 0.75),
+            p95: percentile(&sorted, 0.95),
+        })
+    }
+}
+
+/// ROI and drawdown distributions (both in percent) over all Monte Carlo runs.
+#[derive(Debug, Clone)]
+pub struct MonteCarloReport {
+    pub method: ResamplingMethod,
+    pub runs: usize,
+    pub roi: Distribution,
+    pub max_drawdown: Distribution,
+    /// Fraction of runs that ended below the initial capital.
+    pub probability_of_loss: f64,
+}
+
+impl MonteCarloReport {
+    fn from_runs(method: ResamplingMethod, results: &[(f64, f64)]) -> Option<Self> {
+        let rois: Vec<f64> = results.iter().map(|(roi, _)| *roi).collect();
+        let drawdowns: Vec<f64> = results.iter().map(|(_, drawdown)| *drawdown).collect();
+
+        Some(Self {
+            method,
+            runs: results.len(),
+            roi: Distribution::from_samples(&rois)?,
+            max_drawdown: Distribution::from_samples(&drawdowns)?,
+            probability_of_loss: rois.iter().filter(|roi| **roi < 0.0).count() as f64
+                / rois.len() as f64,
+        })
+    }
+}
+
+/// Largest peak-to-trough decline of an equity curve, as a fraction of the peak.
+pub fn max_drawdown(equity: &[f64]) -> f64 {
+    let mut peak = f64::MIN;
+    let mut worst: f64 = 0.0;
+
+    for &value in equity {
+        peak = peak.max(value);
+        if peak > 0.0 {
+            worst = worst.max((peak - value) / peak);
+        }
+    }
+
+    worst
+}
+
+/// Builds a synthetic history of the same length as `history` by concatenating randomly
+/// chosen blocks of `block_size` consecutive days. Daily close-to-close returns keep their
+/// short-term autocorrelation within a block; each day's open, high and low keep their
+/// ratio to that day's close. The first close and all timestamps are kept as they are.
+pub fn block_bootstrap<R: Rng + ?Sized>(
+    history: &[DataPoint],
+    block_size: usize,
+    rng: &mut R,
+) -> Vec<DataPoint> {
+    if history.len() < 2 {
+        return history.to_vec();
+    }
+
+    let block_size = block_size.clamp(1, history.len() - 1);
+    // Day `i` pairs the return from close i-1 to close i with the shape of day i.
+    let days: Vec<usize> = (1..history.len()).collect();
+
+    let mut sampled = Vec::with_capacity(days.len());
+    while sampled.len() < days.len() {
+        let start = rng.gen_range(0..=days.len() - block_size);
+        sampled.extend_from_slice(&days[start..start + block_size]);
+    }
+    sampled.truncate(days.len());
+
+    let mut points = Vec::with_capacity(history.len());
+    points.push(history[0].clone());
+
+    for (target, &day) in history[1..].iter().zip(&sampled) {
+        let source = &history[day];
+        let close = points[points.len() - 1].price * source.price / history[day - 1].price;
+        let scale = close / source.price;
+
+        points.push(DataPoint {
+            datetime: target.datetime,
+            price: close,
+            high: source.high * scale,
+            low: source.low * scale,
+            open: source.open * scale,
+            volume: source.volume,
+        });
+    }
+
+    points
+}
+
+/// Runs the engine on `config.runs` block-bootstrapped versions of `history`, simulating the
+/// last `days` of each. Indicators are recomputed on every synthetic path; the
+/// BTC-relative features are left out since a resampled path has no BTC counterpart.
+pub fn bootstrap_simulations<R: Rng + ?Sized>(
+    symbol: &str,
+    history: &[DataPoint],
+    fgi: Option<u8>,
+    params: &Params,
+    days: usize,
+    config: &MonteCarloConfig,
+    rng: &mut R,
+) -> Result<Option<MonteCarloReport>, TradingEngineError> {
+    let valid: Vec<DataPoint> = history
+        .iter()
+        .filter(|p| p.price.is_finite() && p.price > 0.0)
+        .cloned()
+        .collect();
+
+    let mut results = Vec::with_capacity(config.runs);
+    for _ in 0..config.runs {
+        let path = block_bootstrap(&valid, config.block_size, rng);
+        let df = Indicators::new(path).calculate()?;
+
+        let mut engine = TradingEngine::new(symbol.to_string(), df, fgi, params.clone())?;
+        engine.run_simulation(Some(days))?;
+        let summary = engine.get_summary()?;
+        // Marked to the last close rather than taken after the closing sale, so a losing
+        // path counts as the loss it is.
+        let roi = engine
+            .portfolio
+            .equity_curve
+            .last()
+            .map_or(summary.roi, |(_, value)| (value / params.initial_capital - 1.0) * 100.0);
+
+        results.push((roi, summary.max_drawdown));
+    }
+
+    Ok(MonteCarloReport::from_runs(ResamplingMethod::Bootstrap, &results))
+}
+
+/// Shuffles the order of the trades of a finished backtest `runs` times.
+///
+/// The daily equity returns are cut into segments, each starting the day after a trade
+/// and ending on the next one, and the segments are replayed in random order. The final
+/// ROI is unchanged by construction, so this measures how much of the drawdown is owed
+/// to the particular sequence of trades. Returns `None` if the engine has not run yet.
+pub fn shuffle_trades<R: Rng + ?Sized>(
+    engine: &TradingEngine,
+    runs: usize,
+    rng: &mut R,
+) -> Option<MonteCarloReport> {
+    let summary = engine.get_summary().ok()?;
+    let initial = summary.initial_capital;
+
+    let mut equity: Vec<f64> = engine.portfolio.equity_curve.iter().map(|(_, value)| *value).collect();
+    // The closing sale of an open position settles after the last close.
+    equity.push(summary.final_portfolio_value);
+    if equity.len() < 2 || initial <= 0.0 {
+        return None;
+    }
+
+    let trade_days: HashSet<_> = engine.portfolio.trade_history.iter().map(|t| t.datetime).collect();
+
+    let mut segments: Vec<Vec<f64>> = Vec::new();
+    let mut current = vec![equity[0] / initial];
+    for (i, pair) in equity.windows(2).enumerate() {
+        let at_trade = engine
+            .portfolio
+            .equity_curve
+            .get(i)
+            .is_some_and(|(datetime, _)| trade_days.contains(datetime));
+        if at_trade {
+            segments.push(std::mem::take(&mut current));
+        }
+        current.push(if pair[0] > 0.0 { pair[1] / pair[0] } else { 1.0 });
+    }
+    segments.push(current);
+
+    let results: Vec<(f64, f64)> = (0..runs)
+        .map(|_| {
+            segments.shuffle(rng);
+
+            let mut value = initial;
+            let mut path = vec![initial];
+            for ratio in segments.iter().flatten() {
+                value *= ratio;
+                path.push(value);
+            }
+
+            ((value - initial) / initial * 100.0, max_drawdown(&path) * 100.0)
+        })
+        .collect();
+
+    MonteCarloReport::from_runs(ResamplingMethod::Shuffle, &results)
+}
+
+/// Linear interpolation between the closest ranks of an ascending sample.
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    let rank = q * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
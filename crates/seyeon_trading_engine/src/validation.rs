@@ -0,0 +1,328 @@
+use crate::data_point::DataPoint;
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+/// How invalid rows, outliers and missing days are repaired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepairStrategy {
+    /// Removes invalid rows and outliers; gaps are left as they are.
+    Drop,
+    /// Repeats the previous valid close, with no volume.
+    #[default]
+    ForwardFill,
+    /// Interpolates the close linearly in time between the surrounding valid rows.
+    Interpolate,
+}
+
+impl fmt::Display for RepairStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepairStrategy::Drop => write!(f, "drop"),
+            RepairStrategy::ForwardFill => write!(f, "forward-fill"),
+            RepairStrategy::Interpolate => write!(f, "interpolate"),
+        }
+    }
+}
+
+impl FromStr for RepairStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "drop" => Ok(RepairStrategy::Drop),
+            "forward-fill" | "ffill" => Ok(RepairStrategy::ForwardFill),
+            "interpolate" => Ok(RepairStrategy::Interpolate),
+            other => Err(format!("Unknown repair strategy: {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidationConfig {
+    pub strategy: RepairStrategy,
+    /// A close that is more than `1 + max_spike` times both of its neighbours (or less
+    /// than their value divided by it) is treated as a bad tick. 1.0 = a 100% spike.
+    pub max_spike: f64,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            strategy: RepairStrategy::default(),
+            max_spike: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnomalyKind {
+    /// Zero, negative or non-finite close.
+    InvalidPrice,
+    /// High below low, a missing high/low, or a close outside the high-low range. Fixed
+    /// in place by widening the range, whatever the strategy.
+    InconsistentRange,
+    /// Isolated spike relative to both neighbouring closes.
+    Outlier,
+}
+
+#[derive(Debug, Clone)]
+pub struct Anomaly {
+    pub datetime: DateTime<Utc>,
+    pub kind: AnomalyKind,
+}
+
+/// Consecutive rows more than one day apart.
+#[derive(Debug, Clone)]
+pub struct Gap {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub missing_days: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub input_rows: usize,
+    pub output_rows: usize,
+    /// Rows before the first valid close (e.g. before the coin was listed); always removed.
+    pub leading_invalid: usize,
+    pub duplicates: usize,
+    pub gaps: Vec<Gap>,
+    pub anomalies: Vec<Anomaly>,
+    pub strategy: RepairStrategy,
+    pub dropped: usize,
+    pub filled: usize,
+}
+
+impl ValidationReport {
+    pub fn is_clean(&self) -> bool {
+        self.duplicates == 0 && self.gaps.is_empty() && self.anomalies.is_empty()
+    }
+
+    pub fn missing_days(&self) -> i64 {
+        self.gaps.iter().map(|gap| gap.missing_days).sum()
+    }
+
+    pub fn count(&self, kind: AnomalyKind) -> usize {
+        self.anomalies.iter().filter(|a| a.kind == kind).count()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rows -> {} ({} leading invalid removed, {} duplicates, {} gaps / {} missing days, \
+             {} invalid prices, {} inconsistent ranges, {} outliers; {}: {} dropped, {} filled)",
+            self.input_rows,
+            self.output_rows,
+            self.leading_invalid,
+            self.duplicates,
+            self.gaps.len(),
+            self.missing_days(),
+            self.count(AnomalyKind::InvalidPrice),
+            self.count(AnomalyKind::InconsistentRange),
+            self.count(AnomalyKind::Outlier),
+            self.strategy,
+            self.dropped,
+            self.filled
+        )
+    }
+}
+
+/// Checks a daily series and repairs it with `config.strategy`.
+///
+/// Rows are sorted by time and duplicated timestamps keep their last occurrence. Rows
+/// before the first valid close are removed. Invalid closes and outliers are then
+/// dropped or filled, and with a filling strategy the missing days are inserted too.
+pub fn repair(points: Vec<DataPoint>, config: &ValidationConfig) -> (Vec<DataPoint>, ValidationReport) {
+    let mut report = ValidationReport {
+        input_rows: points.len(),
+        strategy: config.strategy,
+        ..Default::default()
+    };
+
+    let mut points = points;
+    points.sort_by_key(|p| p.datetime);
+    let mut unique: Vec<DataPoint> = Vec::with_capacity(points.len());
+    for point in points {
+        match unique.last_mut() {
+            Some(last) if last.datetime == point.datetime => {
+                *last = point;
+                report.duplicates += 1;
+            }
+            _ => unique.push(point),
+        }
+    }
+
+    report.leading_invalid = unique
+        .iter()
+        .position(|p| valid_price(p.price))
+        .unwrap_or(unique.len());
+    unique.drain(..report.leading_invalid);
+
+    let bad = flag_anomalies(&mut unique, config.max_spike, &mut report);
+
+    for pair in unique.windows(2) {
+        let missing_days = (pair[1].datetime - pair[0].datetime).num_days() - 1;
+        if missing_days > 0 {
+            report.gaps.push(Gap {
+                from: pair[0].datetime,
+                to: pair[1].datetime,
+                missing_days,
+            });
+        }
+    }
+
+    let repaired = match config.strategy {
+        RepairStrategy::Drop => {
+            report.dropped = bad.iter().filter(|b| **b).count();
+            unique
+                .into_iter()
+                .zip(bad)
+                .filter(|(_, bad)| !bad)
+                .map(|(point, _)| point)
+                .collect()
+        }
+        RepairStrategy::ForwardFill | RepairStrategy::Interpolate => {
+            fill(unique, &bad, config.strategy, &mut report)
+        }
+    };
+
+    report.output_rows = repaired.len();
+    (repaired, report)
+}
+
+/// Reports the problems of a series without changing it.
+pub fn validate(points: &[DataPoint], config: &ValidationConfig) -> ValidationReport {
+    let (_, mut report) = repair(points.to_vec(), config);
+    report.output_rows = report.input_rows;
+    report.dropped = 0;
+    report.filled = 0;
+    report
+}
+
+fn valid_price(price: f64) -> bool {
+    price.is_finite() && price > 0.0
+}
+
+/// Fixes inconsistent high/low ranges in place and returns which rows need repair.
+fn flag_anomalies(points: &mut [DataPoint], max_spike: f64, report: &mut ValidationReport) -> Vec<bool> {
+    let mut bad = vec![false; points.len()];
+
+    for (i, point) in points.iter_mut().enumerate() {
+        if !valid_price(point.price) {
+            bad[i] = true;
+            report.anomalies.push(Anomaly {
+                datetime: point.datetime,
+                kind: AnomalyKind::InvalidPrice,
+            });
+            continue;
+        }
+
+        // CryptoCompare's aggregated open often falls outside the day's range, so only
+        // the close is checked against it.
+        let high = if valid_price(point.high) { point.high } else { point.price };
+        let low = if valid_price(point.low) { point.low } else { point.price };
+        let fixed_high = high.max(low).max(point.price);
+        let fixed_low = high.min(low).min(point.price);
+
+        if fixed_high != point.high || fixed_low != point.low {
+            point.high = fixed_high;
+            point.low = fixed_low;
+            report.anomalies.push(Anomaly {
+                datetime: point.datetime,
+                kind: AnomalyKind::InconsistentRange,
+            });
+        }
+    }
+
+    // A spike must stand out from the nearest valid close on each side.
+    let valid: Vec<usize> = (0..points.len()).filter(|&i| !bad[i]).collect();
+    let limit = 1.0 + max_spike;
+    for window in valid.windows(3) {
+        let (prev, idx, next) = (points[window[0]].price, points[window[1]].price, points[window[2]].price);
+        let up = idx / prev > limit && idx / next > limit;
+        let down = prev / idx > limit && next / idx > limit;
+
+        if up || down {
+            bad[window[1]] = true;
+            report.anomalies.push(Anomaly {
+                datetime: points[window[1]].datetime,
+                kind: AnomalyKind::Outlier,
+            });
+        }
+    }
+
+    bad
+}
+
+/// Replaces the flagged rows and inserts the missing days, reading the surrounding valid
+/// closes. Trailing bad rows (with no later valid close) are always forward-filled.
+fn fill(
+    points: Vec<DataPoint>,
+    bad: &[bool],
+    strategy: RepairStrategy,
+    report: &mut ValidationReport,
+) -> Vec<DataPoint> {
+    let next_valid: Vec<Option<usize>> = {
+        let mut next = vec![None; points.len()];
+        let mut upcoming = None;
+        for i in (0..points.len()).rev() {
+            next[i] = upcoming;
+            if !bad[i] {
+                upcoming = Some(i);
+            }
+        }
+        next
+    };
+
+    let synthetic = |datetime: DateTime<Utc>, prev: &DataPoint, next: Option<&DataPoint>| {
+        let price = match (strategy, next) {
+            (RepairStrategy::Interpolate, Some(next)) => {
+                let span = (next.datetime - prev.datetime).num_seconds() as f64;
+                let elapsed = (datetime - prev.datetime).num_seconds() as f64;
+                prev.price + (next.price - prev.price) * elapsed / span
+            }
+            _ => prev.price,
+        };
+
+        DataPoint {
+            datetime,
+            price,
+            high: price,
+            low: price,
+            open: price,
+            volume: 0.0,
+        }
+    };
+
+    let mut repaired: Vec<DataPoint> = Vec::with_capacity(points.len());
+    let mut last_valid: Option<DataPoint> = None;
+
+    for (i, point) in points.iter().enumerate() {
+        let next = next_valid[i].map(|j| &points[j]);
+
+        if let Some(prev) = &last_valid {
+            let mut day = repaired.last().map_or(prev.datetime, |p| p.datetime) + Duration::days(1);
+            while day < point.datetime {
+                repaired.push(synthetic(day, prev, Some(point).filter(|_| !bad[i]).or(next)));
+                report.filled += 1;
+                day += Duration::days(1);
+            }
+        }
+
+        if bad[i] {
+            // Rows before the first valid close were removed, so there is always one.
+            if let Some(prev) = &last_valid {
+                repaired.push(synthetic(point.datetime, prev, next));
+                report.filled += 1;
+            }
+        } else {
+            repaired.push(point.clone());
+            last_valid = Some(point.clone());
+        }
+    }
+
+    repaired
+}
@@ -0,0 +1,87 @@
+use seyeon_trading_engine::backtest::{BarSeries, Broker, Order, OrderQueue, Portfolio};
+use seyeon_trading_engine::data_point::DailySeries;
+use seyeon_trading_engine::engine::TradeType;
+use seyeon_trading_engine::indicators::Indicators;
+
+fn bars(prices: &[f64]) -> BarSeries {
+    let history = DailySeries::new().prices(prices);
+
+    BarSeries::from_df(&Indicators::new(history).calculate().unwrap()).unwrap()
+}
+
+#[test]
+fn bar_series_exposes_warm_up_as_nan() {
+    let series = bars(&[100.0, 101.0, 102.0]);
+
+    assert_eq!(series.len(), 3);
+    let last = series.last().unwrap();
+    assert_eq!(last.price, 102.0);
+    assert!(last.roc.is_nan());
+    assert!(last.btc_rs.is_none());
+    assert_eq!(series.iter_from(1).count(), 2);
+}
+
+#[test]
+fn orders_are_filled_and_booked_in_sequence() {
+    let series = bars(&[100.0, 80.0, 120.0]);
+    let broker = Broker { fee_rate: 0.01 };
+    let mut portfolio = Portfolio::new(1_000.0);
+    let mut queue = OrderQueue::default();
+
+    let fill_all = |bar_idx: usize, queue: &mut OrderQueue, portfolio: &mut Portfolio| {
+        let bar = series.bar(bar_idx);
+        while let Some(order) = queue.pop() {
+            if let Some(fill) = broker.execute(order, &bar, portfolio) {
+                portfolio.apply(&fill);
+            }
+        }
+    };
+
+    queue.submit(Order::Enter { fraction: 0.5 });
+    fill_all(0, &mut queue, &mut portfolio);
+    assert!((portfolio.cash - 500.0).abs() < 1e-9);
+    assert!((portfolio.held - 4.95).abs() < 1e-9);
+
+    queue.submit(Order::DcaBuy { fraction: 1.0 });
+    fill_all(1, &mut queue, &mut portfolio);
+    let position = portfolio.position.clone().unwrap();
+    assert!((position.amount - (4.95 + 6.1875)).abs() < 1e-9);
+    assert!((position.avg_price - (4.95 * 100.0 + 6.1875 * 80.0) / position.amount).abs() < 1e-9);
+
+    queue.submit(Order::PartialSell { fraction: 0.5 });
+    queue.submit(Order::FullSell);
+    fill_all(2, &mut queue, &mut portfolio);
+    assert!(queue.is_empty());
+    assert!(portfolio.position.is_none());
+    assert!(portfolio.held.abs() < 1e-9);
+    assert!((portfolio.cash - 11.1375 * 120.0 * 0.99).abs() < 1e-9);
+
+    let kinds: Vec<_> = portfolio.trade_history.iter().map(|t| format!("{:?}", t.trade_type)).collect();
+    assert_eq!(kinds, ["Buy", "DcaBuy", "PartialSell", "FullSell"]);
+    assert!(matches!(portfolio.trade_history[3].trade_type, TradeType::FullSell));
+}
+
+#[test]
+fn sells_need_an_open_position() {
+    let series = bars(&[100.0]);
+    let broker = Broker { fee_rate: 0.0 };
+    let portfolio = Portfolio::new(1_000.0);
+
+    assert!(broker.execute(Order::FullSell, &series.bar(0), &portfolio).is_none());
+}
+
+#[test]
+fn final_sale_books_a_loss_below_the_entry_price() {
+    let series = bars(&[100.0, 80.0]);
+    let broker = Broker { fee_rate: 0.01 };
+    let mut portfolio = Portfolio::new(1_000.0);
+
+    let fill = broker.execute(Order::Enter { fraction: 1.0 }, &series.bar(0), &portfolio).unwrap();
+    portfolio.apply(&fill);
+    let fill = broker.execute(Order::FinalSell, &series.bar(1), &portfolio).unwrap();
+    portfolio.apply(&fill);
+
+    // Sold at the last close like the benchmarks, not at the average cost of 100.
+    assert_eq!(fill.price, 80.0);
+    assert!((portfolio.cash - 9.9 * 80.0 * 0.99).abs() < 1e-9);
+}
@@ -0,0 +1,73 @@
+use polars::prelude::*;
+use seyeon_trading_engine::benchmark::{self, Benchmark};
+use seyeon_trading_engine::engine::Params;
+
+fn frame(prices: &[f64], rsi: &[f64]) -> DataFrame {
+    df!(
+        "datetime" => (0..prices.len() as i64).map(|i| i * 86_400_000).collect::<Vec<_>>(),
+        "price" => prices,
+        "rsi" => rsi,
+    )
+    .unwrap()
+}
+
+#[test]
+fn buy_and_hold_tracks_the_price_net_of_the_entry_and_closing_fees() {
+    let prices = [100.0, 80.0, 150.0, 200.0];
+    let df = frame(&prices, &[50.0; 4]);
+    let params = Params::default();
+
+    let summary = benchmark::run(Benchmark::BuyAndHold, &df, &params, 4).unwrap();
+
+    let expected = params.initial_capital * (1.0 - params.generic_fee).powi(2) * 2.0;
+    assert!((summary.final_portfolio_value - expected).abs() < 1e-6);
+    assert_eq!(summary.num_trades, 2);
+    assert!((summary.max_drawdown - 20.0).abs() < 1e-9);
+}
+
+#[test]
+fn dca_spreads_the_capital_over_the_window() {
+    let prices = vec![100.0; 90];
+    let df = frame(&prices, &vec![50.0; 90]);
+    let params = Params::default();
+
+    let summary = benchmark::run(Benchmark::Dca, &df, &params, 60).unwrap();
+
+    // Two installments, then the closing sale of what they bought at the same price.
+    let fee = params.generic_fee;
+    assert_eq!(summary.num_trades, 3);
+    assert!((summary.estimated_fees_paid - params.initial_capital * (fee + (1.0 - fee) * fee)).abs() < 1e-6);
+}
+
+#[test]
+fn rsi_mean_reversion_trades_on_extremes_only() {
+    let prices = [100.0, 90.0, 95.0, 120.0, 110.0];
+    let rsi = [50.0, 25.0, 45.0, 75.0, 20.0];
+    let df = frame(&prices, &rsi);
+    let params = Params { generic_fee: 0.0, ..Params::default() };
+
+    let summary = benchmark::run(Benchmark::RsiMeanReversion, &df, &params, 5).unwrap();
+
+    // Bought at 90, sold at 120, bought again at 110 and closed there.
+    assert_eq!(summary.num_trades, 4);
+    assert!((summary.roi - (120.0 / 90.0 - 1.0) * 100.0).abs() < 1e-9);
+}
+
+#[test]
+fn parses_strategy_names() {
+    assert_eq!("buy-and-hold".parse::<Benchmark>(), Ok(Benchmark::BuyAndHold));
+    assert_eq!("SMA_CROSSOVER".parse::<Benchmark>(), Ok(Benchmark::SmaCrossover));
+    assert!("martingale".parse::<Benchmark>().is_err());
+}
+
+#[test]
+fn open_positions_are_closed_at_a_loss_below_the_entry_price() {
+    let prices = [100.0, 90.0, 70.0];
+    let df = frame(&prices, &[50.0; 3]);
+    let params = Params { generic_fee: 0.0, ..Params::default() };
+
+    let summary = benchmark::run(Benchmark::BuyAndHold, &df, &params, 3).unwrap();
+
+    assert_eq!(summary.num_trades, 2);
+    assert!((summary.roi + 30.0).abs() < 1e-9);
+}
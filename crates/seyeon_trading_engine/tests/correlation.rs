@@ -0,0 +1,99 @@
+use seyeon_trading_engine::correlation::{self, CorrelationMethod};
+use seyeon_trading_engine::data_point::DailySeries;
+
+#[test]
+fn aligns_series_with_different_history_lengths() {
+    let old = DailySeries::new().prices(&[1.0, 2.0, 4.0, 8.0, 16.0, 32.0]);
+    let young = DailySeries::new().offset(3).prices(&[10.0, 20.0, 40.0]);
+
+    let returns = correlation::aligned_log_returns(&[("OLD", &old), ("YOUNG", &young)]);
+
+    assert_eq!(returns.len(), 2);
+    assert_eq!(returns.timestamps[0], young[1].datetime.timestamp_millis());
+    assert!((returns.returns[0][0] - 2f64.ln()).abs() < 1e-12);
+    assert!((returns.returns[1][1] - 2f64.ln()).abs() < 1e-12);
+}
+
+#[test]
+fn spearman_is_one_for_monotonic_relationships() {
+    let a_prices: [f64; 6] = [100.0, 101.0, 99.0, 105.0, 104.0, 110.0];
+    // B's daily log return is the cube of A's: same ordering, non-linear magnitude.
+    let mut b_prices = vec![50.0];
+    for pair in a_prices.windows(2) {
+        let r = (pair[1] / pair[0]).ln();
+        b_prices.push(b_prices.last().unwrap() * (r.powi(3) * 1000.0).exp());
+    }
+
+    let a = DailySeries::new().prices(&a_prices);
+    let b = DailySeries::new().prices(&b_prices);
+
+    let matrices =
+        correlation::rolling_correlations(&[("A", &a), ("B", &b)], &[10], CorrelationMethod::Spearman);
+
+    let matrix = matrices[0].as_ref().unwrap();
+    let average = matrix.average_pairwise().unwrap();
+    assert!((average - 1.0).abs() < 1e-12);
+    assert_eq!(matrix.observations, 5);
+}
+
+#[test]
+fn window_keeps_only_recent_returns() {
+    let a = DailySeries::new().prices(&[1.0, 2.0, 1.0, 2.0, 1.0, 2.0, 4.0, 8.0]);
+    let b = DailySeries::new().prices(&[2.0, 1.0, 2.0, 1.0, 2.0, 1.0, 2.0, 1.0]);
+
+    let matrices =
+        correlation::rolling_correlations(&[("A", &a), ("B", &b)], &[3, 10], CorrelationMethod::Pearson);
+
+    assert_eq!(matrices[0].as_ref().unwrap().observations, 3);
+    assert_eq!(matrices[1].as_ref().unwrap().observations, 7);
+    assert!(matrices[1].as_ref().unwrap().average_pairwise().unwrap() < 0.0);
+}
+
+#[test]
+fn window_spans_calendar_days_not_rows() {
+    let prices: Vec<f64> = (0..20).map(|i| 100.0 + (i as f64).sin() * 5.0).collect();
+    let a = DailySeries::new().prices(&prices);
+    // B misses days 12 to 15: its 10 latest rows reach back to day 6.
+    let mut b = DailySeries::new().prices(&prices);
+    b.drain(12..16);
+
+    let matrix = correlation::window_correlations(&[("A", &a), ("B", &b)], 10, CorrelationMethod::Pearson).unwrap();
+
+    // Returns of days 10, 11 and 16 to 19, the days after day 9 both assets have.
+    assert_eq!(matrix.observations, 6);
+}
+
+#[test]
+fn young_asset_only_affects_its_own_pairs() {
+    let a_prices: Vec<f64> = (0..40).map(|i| 100.0 + (i as f64).sin() * 5.0).collect();
+    let b_prices: Vec<f64> = (0..40).map(|i| 50.0 + (i as f64 * 0.7).cos() * 3.0).collect();
+    let a = DailySeries::new().prices(&a_prices);
+    let b = DailySeries::new().prices(&b_prices);
+    let young = DailySeries::new().offset(35).prices(&[10.0, 11.0, 10.5, 12.0, 11.0]);
+
+    let matrix = correlation::window_correlations(
+        &[("A", &a), ("B", &b), ("YOUNG", &young)],
+        30,
+        CorrelationMethod::Pearson,
+    )
+    .unwrap();
+
+    assert_eq!(matrix.observations, 30);
+    let a_column = matrix.matrix.column("A").unwrap().f64().unwrap();
+    assert!(a_column.get(1).is_some());
+    assert_eq!(a_column.get(2), None);
+    assert_eq!(matrix.matrix.column("YOUNG").unwrap().f64().unwrap().get(2), Some(1.0));
+}
+
+#[test]
+fn windows_without_enough_overlap_fail_on_their_own() {
+    let a = DailySeries::new().prices(&[1.0, 2.0, 1.0, 2.0, 1.0, 2.0, 4.0, 8.0]);
+    let b = DailySeries::new().prices(&[2.0, 1.0, 2.0, 1.0, 2.0, 1.0, 2.0, 1.0]);
+
+    let matrices =
+        correlation::rolling_correlations(&[("A", &a), ("B", &b)], &[5, 90], CorrelationMethod::Pearson);
+
+    assert_eq!(matrices.len(), 2);
+    assert_eq!(matrices[0].as_ref().unwrap().observations, 5);
+    assert!(matrices[1].is_err());
+}
@@ -0,0 +1,38 @@
+use polars::prelude::*;
+use seyeon_trading_engine::data_point::{self, DailySeries};
+use seyeon_trading_engine::indicators::Indicators;
+
+/// Prices oscillating 8 around 100, after two leading zeros as listed before a coin's launch.
+fn prices(len: usize) -> Vec<f64> {
+    (0..len)
+        .map(|i| if i < 2 { 0.0 } else { 100.0 + (i as f64 / 4.0).cos() * 8.0 })
+        .collect()
+}
+
+#[test]
+fn frame_round_trips_data_points() {
+    let points = DailySeries::new().spread(0.01).volume(500.0).prices(&prices(10));
+    let frame = data_point::to_frame(&points).unwrap();
+
+    assert_eq!(frame.get_column_names(), data_point::FRAME_COLUMNS);
+    let restored = data_point::from_frame(&frame).unwrap();
+
+    assert_eq!(restored.len(), points.len());
+    for (restored, point) in restored.iter().zip(&points) {
+        assert_eq!(restored.datetime, point.datetime);
+        assert_eq!(restored.price, point.price);
+        assert_eq!(restored.volume, point.volume);
+    }
+}
+
+#[test]
+fn indicators_from_frame_match_indicators_from_points() {
+    let points = DailySeries::new().spread(0.01).volume(500.0).prices(&prices(120));
+    let frame = data_point::to_frame(&points).unwrap().lazy();
+
+    let from_points = Indicators::new(points).calculate().unwrap();
+    let from_frame = Indicators::from_frame(frame).calculate().unwrap();
+
+    assert_eq!(from_frame.height(), 118);
+    assert!(from_frame.equals_missing(&from_points));
+}
@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use polars::prelude::*;
+use seyeon_trading_engine::data_point::{DailySeries, DataPoint};
+use seyeon_trading_engine::engine::{Params, TradingEngine};
+use seyeon_trading_engine::error::TradingEngineError;
+use seyeon_trading_engine::indicators::{min_history, Indicators};
+
+/// Prices oscillating 10 around 100.
+fn wave(len: usize) -> Vec<f64> {
+    (0..len).map(|i| 100.0 + (i as f64 / 3.0).sin() * 10.0).collect()
+}
+
+fn indicators(len: usize) -> DataFrame {
+    Indicators::new(DailySeries::new().spread(0.01).volume(1_000.0).prices(&wave(len))).calculate().unwrap()
+}
+
+/// Daily dominance over the days of a `len`-day history, rising or falling by 0.1 a day.
+fn dominance(len: usize, rising: bool) -> Vec<(DateTime<Utc>, f64)> {
+    let step = if rising { 0.1 } else { -0.1 };
+    DailySeries::new().spread(0.01).volume(1_000.0).prices(&wave(len))
+        .iter()
+        .enumerate()
+        .map(|(i, point)| (point.datetime, 50.0 + step * i as f64))
+        .collect()
+}
+
+#[test]
+fn missing_columns_are_reported_instead_of_panicking() {
+    let df = indicators(30).drop("rsi").unwrap();
+
+    let result = TradingEngine::new("ETH".to_string(), df, None, Params::default());
+
+    assert!(matches!(result, Err(TradingEngineError::MissingColumn(column)) if column == "rsi"));
+}
+
+#[test]
+fn warm_up_rows_do_not_signal() {
+    let engine = TradingEngine::new("ETH".to_string(), indicators(80), None, Params::default()).unwrap();
+
+    match engine.generate_signal(30) {
+        Err(TradingEngineError::WarmUp { idx, missing, .. }) => {
+            assert_eq!(idx, 30);
+            assert_eq!(missing, ["ma50", "signal"]);
+        }
+        other => panic!("expected a warm-up error, got {other:?}"),
+    }
+
+    assert!(engine.generate_signal(min_history() - 1).is_err());
+    assert!(engine.generate_signal(min_history()).is_ok());
+    assert!(matches!(
+        engine.generate_signal(80),
+        Err(TradingEngineError::IndexOutOfRange { idx: 80, len: 80 })
+    ));
+    let event = engine.poll_event().unwrap();
+    assert!((0.0..=1.0).contains(&event.confidence));
+}
+
+#[test]
+fn simulation_covers_young_assets() {
+    let mut engine = TradingEngine::new("ETH".to_string(), indicators(30), None, Params::default()).unwrap();
+
+    // Too short to ever be ready: valued every day, never traded.
+    engine.run_simulation(Some(365)).unwrap();
+    let summary = engine.get_summary().unwrap();
+
+    assert_eq!(engine.portfolio.equity_curve.len(), 30);
+    assert_eq!(summary.num_trades, 0);
+    assert_eq!(summary.final_portfolio_value, summary.initial_capital);
+    assert!(engine.poll_event().is_err());
+}
+
+#[test]
+fn leading_zero_prices_are_stripped_and_warm_up_is_masked() {
+    let mut prices = vec![0.0; 10];
+    prices.push(5.0);
+    let history = DailySeries::new().prices(&prices);
+
+    let df = Indicators::new(history).calculate().unwrap();
+    assert_eq!(df.height(), 1);
+    assert_eq!(df.column("price").unwrap().f64().unwrap().get(0), Some(5.0));
+    assert_eq!(df.column("roc").unwrap().null_count(), 1);
+    assert_eq!(df.column("ready").unwrap().bool().unwrap().get(0), Some(false));
+
+    let df = indicators(120);
+    let ready = df.column("ready").unwrap().bool().unwrap();
+    assert_eq!(ready.into_iter().position(|r| r == Some(true)), Some(min_history()));
+    assert_eq!(df.column("ma111").unwrap().null_count(), 111);
+}
+
+#[test]
+fn btc_columns_are_joined_by_date() {
+    let asset = DailySeries::new().spread(0.01).volume(1_000.0).prices(&wave(80));
+    // BTC starts 5 days later and misses day 60; its price is twice the asset's.
+    let btc: Vec<DataPoint> = asset
+        .iter()
+        .skip(5)
+        .filter(|point| point.datetime != asset[60].datetime)
+        .map(|point| DataPoint { price: point.price * 2.0, ..point.clone() })
+        .collect();
+
+    let df = Indicators::new(asset).with_btc(&btc).calculate().unwrap();
+    let ratio = df.column("btc_ratio").unwrap().f64().unwrap();
+
+    assert_eq!(df.height(), 80);
+    assert_eq!(ratio.get(4), None);
+    assert_eq!(ratio.get(5), Some(0.5));
+    assert_eq!(ratio.get(59), Some(0.5));
+    assert_eq!(ratio.get(60), None);
+    assert_eq!(ratio.get(61), Some(0.5));
+}
+
+#[test]
+fn rising_dominance_vetoes_altcoin_buys() {
+    // With no threshold, every ready bar votes Buy unless the veto applies.
+    // BTC's own cycle indicators need a year of history before its bars are ready.
+    let params = Params {
+        buy_threshold: 0,
+        ..Params::default()
+    };
+    let signal = |symbol: &str, rising: bool, params: Params| {
+        let df = Indicators::new(DailySeries::new().spread(0.01).volume(1_000.0).prices(&wave(400)))
+            .with_btc_dominance(&dominance(400, rising))
+            .calculate()
+            .unwrap();
+        let engine = TradingEngine::new(symbol.to_string(), df, None, params).unwrap();
+        engine.generate_signal(399).unwrap().0
+    };
+
+    assert!(signal("ETH", false, params.clone()));
+    assert!(!signal("ETH", true, params.clone()));
+    assert!(signal("BTC", true, params.clone()));
+    assert!(signal(
+        "ETH",
+        true,
+        Params {
+            btc_dominance_veto: false,
+            ..params
+        }
+    ));
+}
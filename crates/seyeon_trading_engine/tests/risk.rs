@@ -0,0 +1,70 @@
+use seyeon_trading_engine::data_point::{DailySeries, DataPoint};
+use seyeon_trading_engine::risk;
+
+/// Prices starting at 100 and moving by the daily `returns`.
+fn compound(returns: &[f64]) -> Vec<f64> {
+    std::iter::once(&0.0)
+        .chain(returns)
+        .scan(100.0, |price, r| {
+            *price *= 1.0 + r;
+            Some(*price)
+        })
+        .collect()
+}
+
+#[test]
+fn normal_quantile_matches_known_values() {
+    assert!((risk::normal_quantile(0.95) - 1.644854).abs() < 1e-6);
+    assert!((risk::normal_quantile(0.99) - 2.326348).abs() < 1e-6);
+    assert!((risk::normal_quantile(0.5)).abs() < 1e-12);
+}
+
+#[test]
+fn historical_var_picks_the_tail_loss() {
+    // Twenty daily returns, the worst being -10% and the second worst -5%.
+    let mut returns = vec![0.01; 18];
+    returns.push(-0.05);
+    returns.push(-0.10);
+    let asset = DailySeries::new().prices(&compound(&returns));
+
+    let report = risk::analyze(&[("A", &asset)], None, 20, &[0.95, 0.90]).unwrap();
+
+    assert_eq!(report.observations, 20);
+    assert!((report.var[0].historical_var - 0.10).abs() < 1e-9);
+    assert!((report.var[1].historical_var - 0.05).abs() < 1e-9);
+    assert!((report.var[1].historical_cvar - 0.075).abs() < 1e-9);
+}
+
+#[test]
+fn risk_contributions_sum_to_one() {
+    let a = DailySeries::new().prices(&compound(&[0.02, -0.01, 0.03, -0.02, 0.01, -0.03, 0.02, 0.0]));
+    let b = DailySeries::new().prices(&compound(&[0.01, 0.01, -0.02, 0.00, 0.02, -0.01, -0.01, 0.03]));
+
+    let report = risk::analyze(&[("A", &a), ("B", &b)], Some(&[3.0, 1.0]), 8, &[0.95]).unwrap();
+
+    let total: f64 = report.assets.iter().map(|a| a.risk_contribution).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    assert!((report.assets[0].weight - 0.75).abs() < 1e-12);
+}
+
+#[test]
+fn young_assets_are_excluded_instead_of_shrinking_the_window() {
+    let returns: Vec<f64> = (0..40).map(|i| if i % 3 == 0 { -0.02 } else { 0.015 }).collect();
+    let a = DailySeries::new().prices(&compound(&returns));
+    let b = DailySeries::new().prices(&compound(&returns.iter().rev().copied().collect::<Vec<_>>()));
+    // Listed five days before the last close.
+    let young: Vec<DataPoint> = DailySeries::new().prices(&compound(&returns)).split_off(36);
+
+    let report = risk::analyze(
+        &[("A", &a), ("YOUNG", &young), ("B", &b)],
+        Some(&[1.0, 2.0, 1.0]),
+        30,
+        &[0.95],
+    )
+    .unwrap();
+
+    assert_eq!(report.observations, 30);
+    assert_eq!(report.excluded, vec!["YOUNG".to_string()]);
+    assert_eq!(report.assets.len(), 2);
+    assert!((report.assets[1].weight - 0.5).abs() < 1e-12);
+}
@@ -0,0 +1,89 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use seyeon_trading_engine::data_point::DailySeries;
+use seyeon_trading_engine::engine::{Params, Trade, TradeType, TradingEngine};
+use seyeon_trading_engine::indicators::Indicators;
+use seyeon_trading_engine::robustness::{self, Distribution, MonteCarloConfig};
+
+#[test]
+fn max_drawdown_measures_the_deepest_decline_from_a_peak() {
+    let equity = [100.0, 120.0, 90.0, 110.0, 60.0, 130.0];
+
+    assert!((robustness::max_drawdown(&equity) - 0.5).abs() < 1e-12);
+    assert_eq!(robustness::max_drawdown(&[100.0, 101.0, 102.0]), 0.0);
+}
+
+#[test]
+fn distribution_interpolates_percentiles() {
+    let samples: Vec<f64> = (0..=100).map(f64::from).collect();
+    let distribution = Distribution::from_samples(&samples).unwrap();
+
+    assert!((distribution.p5 - 5.0).abs() < 1e-12);
+    assert!((distribution.p50 - 50.0).abs() < 1e-12);
+    assert!((distribution.p95 - 95.0).abs() < 1e-12);
+    assert!(Distribution::from_samples(&[]).is_none());
+}
+
+#[test]
+fn block_bootstrap_reuses_historical_returns() {
+    let history = DailySeries::new().spread(0.02).prices(&[100.0, 110.0, 99.0, 105.0, 126.0, 120.0, 132.0, 118.8]);
+    let original: Vec<f64> = history.windows(2).map(|w| w[1].price / w[0].price).collect();
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let path = robustness::block_bootstrap(&history, 3, &mut rng);
+
+    assert_eq!(path.len(), history.len());
+    assert_eq!(path[0].price, history[0].price);
+
+    for (i, pair) in path.windows(2).enumerate() {
+        let ratio = pair[1].price / pair[0].price;
+        assert!(original.iter().any(|r| (r - ratio).abs() < 1e-12));
+        assert_eq!(pair[1].datetime, history[i + 1].datetime);
+        assert!(pair[1].low <= pair[1].price && pair[1].price <= pair[1].high);
+    }
+}
+
+#[test]
+fn trade_shuffle_keeps_the_final_return() {
+    let history = DailySeries::new().spread(0.02).prices(&[100.0, 90.0, 120.0, 80.0, 130.0]);
+    let df = Indicators::new(history.clone()).calculate().unwrap();
+
+    let mut engine = TradingEngine::new("TEST".to_string(), df, None, Params::default()).unwrap();
+    engine.portfolio.held = 100.0;
+    engine.portfolio.cash = 0.0;
+    engine.portfolio.equity_curve = history.iter().map(|p| (p.datetime, p.price * 100.0)).collect();
+    engine.portfolio.trade_history = vec![Trade {
+        trade_type: TradeType::DcaBuy,
+        datetime: history[2].datetime,
+        price: 120.0,
+        amount: 0.0,
+    }];
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let report = robustness::shuffle_trades(&engine, 50, &mut rng).unwrap();
+
+    let expected_roi = engine.get_summary().unwrap().roi;
+    assert_eq!(report.runs, 50);
+    assert!((report.roi.p5 - expected_roi).abs() < 1e-9);
+    assert!((report.roi.p95 - expected_roi).abs() < 1e-9);
+    assert!(report.max_drawdown.p95 >= report.max_drawdown.p5);
+    assert_eq!(report.probability_of_loss, 0.0);
+}
+
+#[test]
+fn bootstrap_counts_a_steadily_falling_path_as_a_loss() {
+    // Every daily return is -0.3%, so every resampled path falls the same way.
+    let prices: Vec<f64> = (0..300).map(|i| 100.0 * 0.997f64.powi(i)).collect();
+    let history = DailySeries::new().spread(0.02).prices(&prices);
+    // Buys on the first bar past warm-up, then keeps averaging down.
+    let params = Params { buy_threshold: 0, ..Params::default() };
+    let config = MonteCarloConfig { runs: 5, block_size: 10, ..Default::default() };
+
+    let mut rng = StdRng::seed_from_u64(3);
+    let report = robustness::bootstrap_simulations("TEST", &history, None, &params, 100, &config, &mut rng)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(report.probability_of_loss, 1.0);
+    assert!(report.roi.p95 < 0.0);
+}
@@ -0,0 +1,87 @@
+use chrono::Duration;
+use seyeon_trading_engine::data_point::{DailySeries, DataPoint};
+use seyeon_trading_engine::validation::{self, AnomalyKind, RepairStrategy, ValidationConfig};
+
+fn config(strategy: RepairStrategy) -> ValidationConfig {
+    ValidationConfig {
+        strategy,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn clean_series_is_left_untouched() {
+    let series = DailySeries::new().spread(0.02);
+    let points: Vec<DataPoint> = (0..5).map(|day| series.candle(day, 100.0 + day as f64)).collect();
+    let (repaired, report) = validation::repair(points.clone(), &ValidationConfig::default());
+
+    assert!(report.is_clean());
+    assert_eq!(repaired.len(), points.len());
+    assert_eq!(repaired[4].price, 104.0);
+}
+
+#[test]
+fn reports_duplicates_gaps_and_anomalies() {
+    let series = DailySeries::new().spread(0.02);
+    let mut inverted = series.candle(2, 100.0);
+    inverted.high = 90.0;
+    inverted.low = 110.0;
+
+    let points = vec![
+        series.candle(0, 0.0),
+        series.candle(1, 100.0),
+        inverted,
+        series.candle(2, 101.0),
+        series.candle(3, 0.0),
+        series.candle(4, 102.0),
+        series.candle(5, 400.0),
+        series.candle(6, 103.0),
+        series.candle(9, 104.0),
+    ];
+    let report = validation::validate(&points, &ValidationConfig::default());
+
+    assert_eq!(report.leading_invalid, 1);
+    assert_eq!(report.duplicates, 1);
+    assert_eq!(report.gaps.len(), 1);
+    assert_eq!(report.missing_days(), 2);
+    assert_eq!(report.count(AnomalyKind::InvalidPrice), 1);
+    assert_eq!(report.count(AnomalyKind::Outlier), 1);
+    // The duplicate keeps its last occurrence, which has a consistent range.
+    assert_eq!(report.count(AnomalyKind::InconsistentRange), 0);
+}
+
+#[test]
+fn repair_strategies_drop_or_fill_bad_rows_and_gaps() {
+    let series = DailySeries::new().spread(0.02);
+    let points = vec![series.candle(0, 100.0), series.candle(1, 0.0), series.candle(2, 110.0), series.candle(5, 140.0)];
+
+    let (dropped, report) = validation::repair(points.clone(), &config(RepairStrategy::Drop));
+    assert_eq!(dropped.len(), 3);
+    assert_eq!(report.dropped, 1);
+
+    let (filled, report) = validation::repair(points.clone(), &config(RepairStrategy::ForwardFill));
+    let prices: Vec<f64> = filled.iter().map(|p| p.price).collect();
+    assert_eq!(prices, vec![100.0, 100.0, 110.0, 110.0, 110.0, 140.0]);
+    assert_eq!(report.filled, 3);
+    assert_eq!(filled[3].volume, 0.0);
+
+    let (interpolated, _) = validation::repair(points, &config(RepairStrategy::Interpolate));
+    let prices: Vec<f64> = interpolated.iter().map(|p| p.price).collect();
+    assert_eq!(prices, vec![100.0, 105.0, 110.0, 120.0, 130.0, 140.0]);
+    assert!(interpolated.windows(2).all(|w| w[1].datetime - w[0].datetime == Duration::days(1)));
+}
+
+#[test]
+fn inconsistent_ranges_are_widened_to_include_the_close() {
+    let series = DailySeries::new().spread(0.02);
+    let mut bad = series.candle(1, 100.0);
+    bad.high = 95.0;
+    bad.low = 105.0;
+
+    let (repaired, report) = validation::repair(vec![series.candle(0, 100.0), bad, series.candle(2, 100.0)], &config(RepairStrategy::Drop));
+
+    assert_eq!(report.count(AnomalyKind::InconsistentRange), 1);
+    assert_eq!(repaired.len(), 3);
+    assert!(repaired[1].low <= repaired[1].price && repaired[1].price <= repaired[1].high);
+    assert_eq!(repaired[1].high, 105.0);
+}